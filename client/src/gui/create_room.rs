@@ -1,43 +1,75 @@
-use std::sync::Arc;
-
-use common::message::MessageTrait;
 use macroquad::prelude::*;
 use macroquad::ui::{hash, root_ui};
-use serde_json::to_string;
 use tokio::runtime::Runtime;
 
 use common::{
-    message::client_message::RegisterRoomMessage,
+    message::client_message::{RegisterMatchMessage, RegisterRoomMessage},
     room::{RoomId, RoomInfo, Visibility},
 };
 
-use crate::client::Client;
+use client_core::Client;
+
+use crate::requests::Connection;
+
+const TEAM_MATCH_BOARDS: usize = 8;
+const BROADCAST_DELAY_SECS: u64 = 30;
 
-pub fn create_room_ui(
-    socket: Arc<rust_socketio::asynchronous::Client>,
-    runtime: &Runtime,
-    client: &mut Client,
-) {
+pub fn create_room_ui(connection: &Connection, runtime: &Runtime, client: &mut Client) {
     clear_background(Color::from_rgba(50, 115, 85, 255));
 
-    root_ui().window(hash!(), vec2(10.0, 10.0), vec2(400.0, 150.0), |ui| {
+    root_ui().window(hash!(), vec2(10.0, 10.0), vec2(400.0, 240.0), |ui| {
         ui.label(None, "Enter Room Name:");
         ui.input_text(hash!(), "Room Name:", &mut client.selected_room_name);
+        ui.checkbox(hash!(), "Allow kibitzing", &mut client.allow_kibitzing);
+        ui.checkbox(
+            hash!(),
+            &format!("Broadcast with {}s delay", BROADCAST_DELAY_SECS),
+            &mut client.broadcast,
+        );
 
         if ui.button(None, "Confirm") || is_key_pressed(KeyCode::Enter) {
             let msg = RegisterRoomMessage {
                 room_info: RoomInfo {
                     id: RoomId::new(client.selected_room_name.clone().into()),
                     visibility: Visibility::Public,
+                    deal_constraints: None,
+                    time_control: None,
+                    allow_kibitzing: client.allow_kibitzing,
+                    broadcast_delay_secs: client.broadcast.then_some(BROADCAST_DELAY_SECS),
+                    seed: None,
                 },
             };
 
-            let socket_clone = socket.clone();
+            let connection = connection.clone();
+            runtime.spawn(async move {
+                connection.register_room(msg).await;
+            });
+        }
+
+        if ui.button(None, "Create Team Match") {
+            let name = client.selected_room_name.clone();
+            let allow_kibitzing = client.allow_kibitzing;
+            let broadcast_delay_secs = client.broadcast.then_some(BROADCAST_DELAY_SECS);
+            let room_info = |id: String| RoomInfo {
+                id: RoomId::new(id.into()),
+                visibility: Visibility::Public,
+                deal_constraints: None,
+                time_control: None,
+                allow_kibitzing,
+                broadcast_delay_secs,
+                seed: None,
+            };
+            let msg = RegisterMatchMessage {
+                open_room: room_info(format!("{} open", name)),
+                closed_room: room_info(format!("{} closed", name)),
+                boards: TEAM_MATCH_BOARDS,
+            };
+            // Join the open room once the match is registered
+            client.selected_room_name = format!("{} open", name);
+
+            let connection = connection.clone();
             runtime.spawn(async move {
-                socket_clone
-                    .emit(RegisterRoomMessage::MSG_TYPE, to_string(&msg).unwrap())
-                    .await
-                    .unwrap();
+                connection.register_match(msg).await;
             });
         }
     });
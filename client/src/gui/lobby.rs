@@ -1,23 +1,16 @@
-use crate::client::{Client, ClientState};
+use client_core::{Client, ClientState};
 
 use common::{
-    message::{
-        client_message::{JoinRoomMessage, ListRoomsMessage},
-        MessageTrait,
-    },
+    message::client_message::{JoinTournamentMessage, WatchBroadcastMessage},
     room::RoomId,
 };
 use macroquad::prelude::*;
 use macroquad::ui::{hash, root_ui};
-use serde_json::to_string;
-use std::sync::Arc;
 use tokio::runtime::Runtime;
 
-pub fn list_rooms(
-    socket: Arc<rust_socketio::asynchronous::Client>,
-    runtime: &Runtime,
-    client: &mut Client,
-) {
+use crate::requests::Connection;
+
+pub fn list_rooms(connection: &Connection, runtime: &Runtime, client: &mut Client) {
     clear_background(Color::from_rgba(50, 115, 85, 255));
 
     root_ui().window(hash!(), vec2(10.0, 10.0), vec2(400.0, 400.0), |ui| {
@@ -25,15 +18,9 @@ pub fn list_rooms(
 
         // Align buttons horizontally by creating two separate groups
         if ui.button(None, "Refresh") {
-            let socket_clone = socket.clone();
+            let connection = connection.clone();
             runtime.spawn(async move {
-                socket_clone
-                    .emit(
-                        ListRoomsMessage::MSG_TYPE,
-                        to_string(&ListRoomsMessage {}).unwrap(),
-                    )
-                    .await
-                    .unwrap();
+                connection.list_rooms().await;
             });
         }
 
@@ -41,6 +28,21 @@ pub fn list_rooms(
             client.state = ClientState::CreatingRoom;
         }
 
+        ui.input_text(hash!(), "Tournament:", &mut client.tournament_id);
+        if ui.button(None, "Follow Tournament") {
+            let msg = JoinTournamentMessage {
+                tournament_id: client.tournament_id.clone(),
+            };
+            let connection = connection.clone();
+            runtime.spawn(async move {
+                connection.join_tournament(msg).await;
+            });
+        }
+
+        if ui.button(None, "Replay Deals") {
+            client.state = ClientState::Replaying;
+        }
+
         if ui.button(None, "Exit") {
             std::process::exit(0);
         }
@@ -52,15 +54,19 @@ pub fn list_rooms(
                 if ui.button(None, "Join") {
                     let room_id = RoomId::new(room.clone().into());
                     client.selected_room_name = room.clone();
-                    let socket_clone = socket.clone();
+                    let connection = connection.clone();
+                    runtime.spawn(async move {
+                        connection.join_room(room_id).await;
+                    });
+                }
+                ui.same_line(0.0);
+                if ui.button(None, "Watch") {
+                    let room_id = RoomId::new(room.clone().into());
+                    let connection = connection.clone();
                     runtime.spawn(async move {
-                        socket_clone
-                            .emit(
-                                JoinRoomMessage::MSG_TYPE,
-                                to_string(&JoinRoomMessage { room_id }).unwrap(),
-                            )
-                            .await
-                            .unwrap();
+                        connection
+                            .watch_broadcast(WatchBroadcastMessage { room_id })
+                            .await;
                     });
                 }
             });
@@ -1,18 +1,13 @@
-use common::message::MessageTrait;
 use macroquad::input::KeyCode;
 use macroquad::prelude::*;
 use macroquad::ui::{hash, root_ui};
-use serde_json::to_string;
-use std::sync::Arc;
 use tokio::runtime::Runtime;
 
 use common::{message::client_message::LoginMessage, user::User};
 
-pub fn login_ui(
-    socket: Arc<rust_socketio::asynchronous::Client>,
-    runtime: &Runtime,
-    nickname: &mut String,
-) {
+use crate::requests::Connection;
+
+pub fn login_ui(connection: &Connection, runtime: &Runtime, nickname: &mut String) {
     clear_background(Color::from_rgba(50, 115, 85, 255));
 
     root_ui().window(hash!(), vec2(10.0, 10.0), vec2(400.0, 150.0), |ui| {
@@ -24,12 +19,9 @@ pub fn login_ui(
                 user: User::new(nickname.trim()),
             };
 
-            let socket_clone = socket.clone();
+            let connection = connection.clone();
             runtime.spawn(async move {
-                socket_clone
-                    .emit(LoginMessage::MSG_TYPE, to_string(&login_message).unwrap())
-                    .await
-                    .unwrap();
+                connection.login(login_message).await;
             });
         }
     });
@@ -2,4 +2,5 @@ pub mod create_room;
 pub mod lobby;
 pub mod login;
 pub mod play;
+pub mod replay;
 pub mod room;
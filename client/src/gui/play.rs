@@ -1,16 +1,15 @@
-use common::message::MessageTrait;
 use common::{
-    message::client_message::{MakeBidMessage, MakeTrickMessage},
-    Bid, BidType, Player, Suit,
+    message::client_message::MakeBidMessage, room::SpectatorView, Bid, BidType, Card, Player, Suit,
 };
 use macroquad::prelude::*;
 use macroquad::texture::{load_texture, DrawTextureParams, Texture2D};
-use serde_json::to_string;
+use macroquad::ui::{hash, root_ui};
 use std::collections::HashMap;
-use std::sync::Arc;
 use tokio::runtime::Runtime;
 
-use crate::client::Client;
+use client_core::Client;
+
+use crate::requests::Connection;
 
 pub async fn preload_textures() -> HashMap<String, Texture2D> {
     let mut textures = HashMap::new();
@@ -61,27 +60,131 @@ pub async fn preload_cards() -> HashMap<String, Texture2D> {
     textures
 }
 
-fn place_bid(
-    socket: &Arc<rust_socketio::asynchronous::Client>,
-    runtime: &Runtime,
-    bid: &mut Option<Bid>,
-    placed_bid: Bid,
-) {
+fn place_bid(connection: &Connection, runtime: &Runtime, bid: &mut Option<Bid>, placed_bid: Bid) {
     *bid = Some(placed_bid);
-    let socket_clone = socket.clone();
+    let connection = connection.clone();
     runtime.spawn(async move {
-        socket_clone
-            .emit(
-                MakeBidMessage::MSG_TYPE,
-                to_string(&MakeBidMessage { bid: placed_bid }).unwrap(),
-            )
-            .await
-            .unwrap();
+        connection
+            .make_bid(MakeBidMessage {
+                bid: placed_bid,
+                explanation: None,
+            })
+            .await;
     });
 }
 
+/// Side of the table on the screen, relative to the player at the bottom
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TableSide {
+    Left,
+    Top,
+    Right,
+    Bottom,
+}
+
+/// Position of the square representing the table
+pub struct TableRect {
+    pub x: f32,
+    pub y: f32,
+    pub size: f32,
+}
+
+/// Draws face-up cards of a hand next to given side of the table, like the dummy's cards
+pub fn draw_side_hand(
+    card_textures: &HashMap<String, Texture2D>,
+    cards: &[Card],
+    side: TableSide,
+    table: &TableRect,
+    card_width: f32,
+) {
+    // Sort cards by suit, then by rank
+    let mut cards_sorted = cards.to_vec();
+    cards_sorted.sort_by(|a, b| a.suit.cmp(&b.suit).then(b.rank.cmp(&a.rank)));
+
+    let card_spacing = 30.0; // Overlapping spacing for the cards
+    let extra_offset = 100.0; // Additional spacing from the table
+
+    // Calculate total width/height of the pile
+    let total_pile_length = (cards_sorted.len() as f32 - 1.0) * card_spacing + card_width;
+
+    for (i, card) in cards_sorted.iter().enumerate() {
+        let card_name = format!("{}{}", card.rank.to_str(), card.suit.to_str());
+        let Some(texture) = card_textures.get(&card_name) else {
+            continue;
+        };
+        let offset = i as f32 * card_spacing;
+        let (x, y, rotation) = match side {
+            // Centered vertically on the left side
+            TableSide::Left => (
+                table.x - card_width - 20.0 - extra_offset,
+                table.y + (table.size - total_pile_length) / 2.0 + offset,
+                std::f32::consts::FRAC_PI_2,
+            ),
+            // Centered vertically on the right side
+            TableSide::Right => (
+                table.x + table.size + 20.0 + extra_offset,
+                table.y + (table.size - total_pile_length) / 2.0 + offset,
+                -std::f32::consts::FRAC_PI_2,
+            ),
+            // Centered horizontally at the top
+            TableSide::Top => (
+                table.x + (table.size - total_pile_length) / 2.0 + offset,
+                table.y - card_width - 20.0 - extra_offset,
+                0.0,
+            ),
+            // Centered horizontally at the bottom, like the player's own cards
+            TableSide::Bottom => (
+                table.x + (table.size - total_pile_length) / 2.0 + offset,
+                table.y + table.size + extra_offset,
+                0.0,
+            ),
+        };
+
+        draw_texture_ex(
+            texture,
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(Vec2::new(card_width, card_width)),
+                rotation,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// Lets a spectator choose which hands to watch
+fn spectator_view_ui(connection: &Connection, runtime: &Runtime, client: &mut Client) {
+    root_ui().window(
+        hash!(),
+        vec2(10.0, screen_height() - 60.0),
+        vec2(420.0, 50.0),
+        |ui| {
+            let views = Player::ALL
+                .map(SpectatorView::Seat)
+                .into_iter()
+                .chain([SpectatorView::AllHands]);
+            for view in views {
+                let label = match view {
+                    SpectatorView::Seat(player) => player.to_string(),
+                    SpectatorView::AllHands => String::from("All hands"),
+                };
+                if ui.button(None, label.as_str()) {
+                    client.spectator_view = Some(view);
+                    let connection = connection.clone();
+                    runtime.spawn(async move {
+                        connection.kibitz(view).await;
+                    });
+                }
+                ui.same_line(0.0);
+            }
+        },
+    );
+}
+
 pub fn play_ui(
-    socket: Arc<rust_socketio::asynchronous::Client>,
+    connection: &Connection,
     runtime: &Runtime,
     client: &mut Client,
     bid_textures: &HashMap<String, Texture2D>,
@@ -89,9 +192,12 @@ pub fn play_ui(
 ) {
     clear_background(Color::from_rgba(50, 115, 85, 255));
 
-    let Some(player_position) = client.selected_seat else {
-        return;
-    };
+    // Spectators watch the table from South's seat
+    let is_spectator = client.selected_seat.is_none();
+    let player_position = client.selected_seat.unwrap_or(Player::South);
+    if is_spectator && !client.watching_broadcast {
+        spectator_view_ui(connection, runtime, client);
+    }
 
     // Dynamic rotation logic to keep the player's seat at the bottom
     let bottom_player = player_position;
@@ -257,8 +363,13 @@ pub fn play_ui(
         rect_height,
         DARKGRAY,
     );
+    let bottom_label = if is_spectator {
+        format!("{}: {}", bottom_player.to_str(), bottom_username)
+    } else {
+        format!("{} (You): {}", bottom_player.to_str(), bottom_username)
+    };
     center_text(
-        &format!("{} (You): {}", bottom_player.to_str(), bottom_username),
+        &bottom_label,
         square_x + (square_size - rect_width) / 2.0,
         square_y + square_size,
         rect_width,
@@ -322,95 +433,40 @@ pub fn play_ui(
     let grid_cell_size = 60.0;
     let grid_spacing = 10.0;
 
-    if let (Some(dummy_cards), Some(dummy_player)) =
-        (client.dummy_cards.clone(), client.dummy_player)
-    {
-        if dummy_player != player_position {
-            // Sort dummy cards by suit, then by rank
-            let mut dummy_cards_sorted = dummy_cards.clone();
-            dummy_cards_sorted.sort_by(|a, b| a.suit.cmp(&b.suit).then(b.rank.cmp(&a.rank)));
-
-            let dummy_card_width = grid_cell_size * 2.0; // Size of each dummy card
-            let dummy_card_spacing = 30.0; // Overlapping spacing for dummy cards
-            let extra_offset = 100.0; // Increased additional spacing from the table
-
-            // Calculate total width/height of the pile
-            let total_pile_length =
-                (dummy_cards_sorted.len() as f32 - 1.0) * dummy_card_spacing + dummy_card_width;
-
-            match dummy_player {
-                p if p == left_player => {
-                    // Center vertically on the left side with additional left offset
-                    let pile_y = square_y + (square_size - total_pile_length) / 2.0;
-                    let pile_x = square_x - dummy_card_width - 20.0 - extra_offset; // Move further left
-
-                    for (i, card) in dummy_cards_sorted.iter().enumerate() {
-                        let card_name = format!("{}{}", card.rank.to_str(), card.suit.to_str());
-                        if let Some(texture) = card_textures.get(&card_name) {
-                            let card_y = pile_y + i as f32 * dummy_card_spacing;
-
-                            draw_texture_ex(
-                                texture,
-                                pile_x,
-                                card_y,
-                                WHITE,
-                                DrawTextureParams {
-                                    dest_size: Some(Vec2::new(dummy_card_width, dummy_card_width)),
-                                    rotation: std::f32::consts::FRAC_PI_2, // Rotate 90 degrees
-                                    ..Default::default()
-                                },
-                            );
-                        }
-                    }
-                }
-                p if p == right_player => {
-                    // Center vertically on the right side with additional right offset
-                    let pile_y = square_y + (square_size - total_pile_length) / 2.0;
-                    let pile_x = square_x + square_size + 20.0 + extra_offset; // Move further right
-
-                    for (i, card) in dummy_cards_sorted.iter().enumerate() {
-                        let card_name = format!("{}{}", card.rank.to_str(), card.suit.to_str());
-                        if let Some(texture) = card_textures.get(&card_name) {
-                            let card_y = pile_y + i as f32 * dummy_card_spacing;
-
-                            draw_texture_ex(
-                                texture,
-                                pile_x,
-                                card_y,
-                                WHITE,
-                                DrawTextureParams {
-                                    dest_size: Some(Vec2::new(dummy_card_width, dummy_card_width)),
-                                    rotation: -std::f32::consts::FRAC_PI_2, // Rotate -90 degrees
-                                    ..Default::default()
-                                },
-                            );
-                        }
-                    }
-                }
-                p if p == top_player => {
-                    // Center horizontally at the top with additional top offset
-                    let pile_x = square_x + (square_size - total_pile_length) / 2.0;
-                    let pile_y = square_y - dummy_card_width - 20.0 - extra_offset; // Move further up
-
-                    for (i, card) in dummy_cards_sorted.iter().enumerate() {
-                        let card_name = format!("{}{}", card.rank.to_str(), card.suit.to_str());
-                        if let Some(texture) = card_textures.get(&card_name) {
-                            let card_x = pile_x + i as f32 * dummy_card_spacing;
-
-                            draw_texture_ex(
-                                texture,
-                                card_x,
-                                pile_y,
-                                WHITE,
-                                DrawTextureParams {
-                                    dest_size: Some(Vec2::new(dummy_card_width, dummy_card_width)),
-                                    ..Default::default()
-                                },
-                            );
-                        }
-                    }
-                }
-                _ => {}
+    let side_of = |player: Player| match player {
+        p if p == left_player => Some(TableSide::Left),
+        p if p == top_player => Some(TableSide::Top),
+        p if p == right_player => Some(TableSide::Right),
+        _ => None,
+    };
+    let table = TableRect {
+        x: square_x,
+        y: square_y,
+        size: square_size,
+    };
+
+    if let (Some(dummy_cards), Some(dummy_player)) = (&client.dummy_cards, client.dummy_player) {
+        if let Some(side) = side_of(dummy_player) {
+            draw_side_hand(
+                card_textures,
+                dummy_cards,
+                side,
+                &table,
+                grid_cell_size * 2.0,
+            );
+        }
+    }
+
+    // Hands watched by a spectator, dummy is already shown
+    if is_spectator {
+        for player in [left_player, top_player, right_player] {
+            if Some(player) == client.dummy_player && client.dummy_cards.is_some() {
+                continue;
+            }
+            if let (Some(cards), Some(side)) =
+                (&client.kibitzed_hands[player.to_usize()], side_of(player))
+            {
+                draw_side_hand(card_textures, cards, side, &table, grid_cell_size * 2.0);
             }
         }
     }
@@ -496,17 +552,28 @@ pub fn play_ui(
         BidType::NoTrump,
     ];
     let suit_names = ["C", "D", "H", "S", "NT"];
+
+    // Illegal bids and cards are greyed out when it's player's turn
+    let legal_bids = client.legal_bids.clone();
+    let is_legal_bid =
+        |bid: &Bid| !is_spectator && (legal_bids.is_empty() || legal_bids.contains(bid));
+    let legal_cards = client.legal_cards.clone();
+    let is_legal_card = |card: &Card| legal_cards.is_empty() || legal_cards.contains(card);
+    let legal_color = |is_legal: bool| if is_legal { WHITE } else { GRAY };
+
     for row in 0u8..7 {
         // Rows for numbers 1-7
         for col in 0..5 {
             let bid_name = format!("{}{}", row + 1, suit_names[col]);
+            // Unwrap is valid, as row must be between 1 and 7, and bid_types[col] are of valid types
+            let bid = Bid::new(row + 1, bid_types[col]).unwrap();
 
             if let Some(texture) = bid_textures.get(&bid_name) {
                 draw_texture_ex(
                     texture,
                     grid_x + col as f32 * (grid_cell_size + grid_spacing),
                     grid_y + row as f32 * (grid_cell_size + grid_spacing),
-                    WHITE,
+                    legal_color(is_legal_bid(&bid)),
                     DrawTextureParams {
                         dest_size: Some(Vec2::new(grid_cell_size, grid_cell_size)),
                         ..Default::default()
@@ -515,15 +582,14 @@ pub fn play_ui(
 
                 let click_x = grid_x + col as f32 * (grid_cell_size + grid_spacing);
                 let click_y = grid_y + row as f32 * (grid_cell_size + grid_spacing);
-                if is_mouse_button_pressed(MouseButton::Left)
+                if is_legal_bid(&bid)
+                    && is_mouse_button_pressed(MouseButton::Left)
                     && mouse_position().0 >= click_x
                     && mouse_position().0 <= click_x + grid_cell_size
                     && mouse_position().1 >= click_y
                     && mouse_position().1 <= click_y + grid_cell_size
                 {
-                    // Unwrap is valid, as row must be between 1 and 7, and bid_types[col] are of valid types
-                    let placed_bid = Bid::new(row + 1, bid_types[col]).unwrap();
-                    place_bid(&socket, runtime, &mut client.placed_bid, placed_bid);
+                    place_bid(connection, runtime, &mut client.placed_bid, bid);
                 }
             }
         }
@@ -542,7 +608,7 @@ pub fn play_ui(
                 texture,
                 texture_x,
                 extra_row_y,
-                WHITE,
+                legal_color(is_legal_bid(&extra_bids[i])),
                 DrawTextureParams {
                     dest_size: Some(Vec2::new(grid_cell_size, grid_cell_size)),
                     ..Default::default()
@@ -551,20 +617,26 @@ pub fn play_ui(
 
             let click_x = texture_x;
             let click_y = extra_row_y;
-            if is_mouse_button_pressed(MouseButton::Left)
+            if is_legal_bid(&extra_bids[i])
+                && is_mouse_button_pressed(MouseButton::Left)
                 && mouse_position().0 >= click_x
                 && mouse_position().0 <= click_x + grid_cell_size
                 && mouse_position().1 >= click_y
                 && mouse_position().1 <= click_y + grid_cell_size
             {
                 let placed_bid = extra_bids[i];
-                place_bid(&socket, runtime, &mut client.placed_bid, placed_bid);
+                place_bid(connection, runtime, &mut client.placed_bid, placed_bid);
             }
         }
     }
 
     // DISPLAY PLAYER CARDS --------------------------------------------------------------------------------------------------
-    if let Some(mut cards) = client.card_list.clone() {
+    let cards = if is_spectator {
+        client.kibitzed_hands[bottom_player.to_usize()].clone()
+    } else {
+        client.card_list.clone()
+    };
+    if let Some(mut cards) = cards {
         // Sort cards by suit, then by rank
         cards.sort_by(|a, b| a.suit.cmp(&b.suit).then(b.rank.cmp(&a.rank)));
 
@@ -588,7 +660,7 @@ pub fn play_ui(
                     texture,
                     card_x,
                     pile_y,
-                    WHITE,
+                    legal_color(is_spectator || is_legal_card(card)),
                     DrawTextureParams {
                         dest_size: Some(Vec2::new(card_width, card_width)),
                         ..Default::default()
@@ -616,18 +688,12 @@ pub fn play_ui(
             }
         }
 
-        // Handle the clicked card
-        if let Some(card) = clicked_card {
-            let socket_clone = socket.clone();
+        // Handle the clicked card, clicks on illegal cards are ignored
+        if let Some(card) = clicked_card.filter(|card| !is_spectator && is_legal_card(card)) {
+            let connection = connection.clone();
             client.placed_trick = Some(card);
             runtime.spawn(async move {
-                socket_clone
-                    .emit(
-                        MakeTrickMessage::MSG_TYPE,
-                        to_string(&MakeTrickMessage { card }).unwrap(),
-                    )
-                    .await
-                    .unwrap();
+                connection.make_trick(card).await;
             });
         }
     }
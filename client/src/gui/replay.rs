@@ -0,0 +1,222 @@
+use client_core::{client::Replay, Client, ClientState};
+use common::{record::DealRecord, Player};
+use macroquad::prelude::*;
+use macroquad::texture::{DrawTextureParams, Texture2D};
+use macroquad::ui::{hash, root_ui};
+use std::collections::HashMap;
+
+use super::play::{draw_side_hand, TableRect, TableSide};
+
+/// Side of the table each player is drawn at, South is always at the bottom
+fn table_side(player: Player) -> TableSide {
+    match player {
+        Player::North => TableSide::Top,
+        Player::East => TableSide::Right,
+        Player::South => TableSide::Bottom,
+        Player::West => TableSide::Left,
+    }
+}
+
+/// Position of the player's card in the trick, in units of the trick grid
+fn trick_position(player: Player) -> (f32, f32) {
+    match player {
+        Player::North => (0.0, -1.0),
+        Player::East => (1.0, 0.0),
+        Player::South => (0.0, 1.0),
+        Player::West => (-1.0, 0.0),
+    }
+}
+
+fn save_records(replay: &mut Replay) {
+    let result = serde_json::to_string_pretty(&replay.records)
+        .map_err(|err| err.to_string())
+        .and_then(|json| std::fs::write(&replay.file, json).map_err(|err| err.to_string()));
+    replay.status = match result {
+        Ok(()) => format!("Saved {} deals", replay.records.len()),
+        Err(err) => format!("Saving failed: {}", err),
+    };
+}
+
+fn load_records(replay: &mut Replay) {
+    let result = std::fs::read_to_string(&replay.file)
+        .map_err(|err| err.to_string())
+        .and_then(|json| {
+            serde_json::from_str::<Vec<DealRecord>>(&json).map_err(|err| err.to_string())
+        });
+    replay.status = match result {
+        Ok(records) => {
+            replay.records = records;
+            replay.deal = 0;
+            replay.step = 0;
+            format!("Loaded {} deals", replay.records.len())
+        }
+        Err(err) => format!("Loading failed: {}", err),
+    };
+}
+
+/// Screen for stepping through calls and cards of finished deals
+pub fn replay_ui(
+    client: &mut Client,
+    bid_textures: &HashMap<String, Texture2D>,
+    card_textures: &HashMap<String, Texture2D>,
+) {
+    let replay = &mut client.replay;
+    let mut back_to_lobby = false;
+
+    root_ui().window(hash!(), vec2(10.0, 10.0), vec2(300.0, 200.0), |ui| {
+        if ui.button(None, "Back to Lobby") {
+            back_to_lobby = true;
+        }
+
+        if ui.button(None, "Previous Deal") && replay.deal > 0 {
+            replay.deal -= 1;
+            replay.step = 0;
+        }
+        ui.same_line(0.0);
+        if ui.button(None, "Next Deal") && replay.deal + 1 < replay.records.len() {
+            replay.deal += 1;
+            replay.step = 0;
+        }
+
+        let steps = replay.records.get(replay.deal).map_or(0, DealRecord::steps);
+        if ui.button(None, "|<") {
+            replay.step = 0;
+        }
+        ui.same_line(0.0);
+        if ui.button(None, "<") || is_key_pressed(KeyCode::Left) {
+            replay.step = replay.step.saturating_sub(1);
+        }
+        ui.same_line(0.0);
+        if ui.button(None, ">") || is_key_pressed(KeyCode::Right) {
+            replay.step = (replay.step + 1).min(steps);
+        }
+        ui.same_line(0.0);
+        if ui.button(None, ">|") {
+            replay.step = steps;
+        }
+
+        ui.input_text(hash!(), "File", &mut replay.file);
+        if ui.button(None, "Save") {
+            save_records(replay);
+        }
+        ui.same_line(0.0);
+        if ui.button(None, "Load") {
+            load_records(replay);
+        }
+        ui.label(None, &replay.status);
+    });
+
+    if back_to_lobby {
+        client.state = ClientState::InLobby;
+        return;
+    }
+
+    let Some(record) = replay.records.get(replay.deal) else {
+        draw_text(
+            "No deals to replay, finish a deal or load a file",
+            20.0,
+            240.0,
+            30.0,
+            WHITE,
+        );
+        return;
+    };
+
+    let vulnerable: Vec<String> = Player::ALL
+        .iter()
+        .filter(|player| record.deal.vulnerable[player.to_usize()])
+        .map(|player| player.to_string())
+        .collect();
+    draw_text(
+        format!(
+            "Deal {}/{}, step {}/{}, dealer {}, vulnerable: {}",
+            replay.deal + 1,
+            replay.records.len(),
+            replay.step,
+            record.steps(),
+            record.deal.dealer,
+            if vulnerable.is_empty() {
+                String::from("none")
+            } else {
+                vulnerable.join(", ")
+            }
+        ),
+        20.0,
+        screen_height() - 20.0,
+        30.0,
+        WHITE,
+    );
+
+    // DRAW TABLE AND HANDS ------------------------------------------------------------------------
+    let table = TableRect {
+        size: 300.0,
+        x: 0.3 * screen_width() - 150.0,
+        y: 0.5 * screen_height() - 150.0,
+    };
+    draw_rectangle_lines(table.x, table.y, table.size, table.size, 5.0, WHITE);
+
+    let card_width = 120.0;
+    let hands = record.hands_at(replay.step);
+    for player in Player::ALL {
+        draw_side_hand(
+            card_textures,
+            &hands[player.to_usize()],
+            table_side(player),
+            &table,
+            card_width,
+        );
+    }
+
+    for (player, card) in record.trick_at(replay.step) {
+        let card_name = format!("{}{}", card.rank.to_str(), card.suit.to_str());
+        let Some(texture) = card_textures.get(&card_name) else {
+            continue;
+        };
+        let (x, y) = trick_position(*player);
+        draw_texture_ex(
+            texture,
+            table.x + 90.0 * (x + 1.0),
+            table.y + 90.0 * (y + 1.0),
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(Vec2::new(card_width, card_width)),
+                ..Default::default()
+            },
+        );
+    }
+
+    // DRAW AUCTION --------------------------------------------------------------------------------
+    let grid_x = 0.65 * screen_width();
+    let grid_y = 60.0;
+    let grid_cell_size = 60.0;
+    let grid_spacing = 10.0;
+
+    for player in Player::ALL {
+        draw_text(
+            player.to_str(),
+            grid_x + player.to_usize() as f32 * (grid_cell_size + grid_spacing),
+            grid_y - 10.0,
+            25.0,
+            WHITE,
+        );
+    }
+
+    // Calls are laid out in rows of four, starting at the dealer's column
+    let first_column = record.deal.dealer.to_usize();
+    for (index, bid) in record.auction_at(replay.step).iter().enumerate() {
+        let Some(texture) = bid_textures.get(&bid.to_str()) else {
+            continue;
+        };
+        let cell = first_column + index;
+        draw_texture_ex(
+            texture,
+            grid_x + (cell % 4) as f32 * (grid_cell_size + grid_spacing),
+            grid_y + (cell / 4) as f32 * (grid_cell_size + grid_spacing),
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(Vec2::new(grid_cell_size, grid_cell_size)),
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -1,21 +1,15 @@
-use common::message::MessageTrait;
 use macroquad::prelude::*;
 use macroquad::ui::{hash, root_ui};
-use serde_json::to_string;
-use std::sync::Arc;
 
-use crate::client::Client;
+use client_core::Client;
 
 use common::{
-    message::client_message::{LeaveRoomMessage, SelectPlaceMessage},
-    Player,
+    bidding_system::ConventionCard, message::client_message::SetConventionCardMessage, Player,
 };
 
-pub fn room_ui(
-    socket: Arc<rust_socketio::asynchronous::Client>,
-    runtime: &tokio::runtime::Runtime,
-    client: &mut Client,
-) {
+use crate::requests::Connection;
+
+pub fn room_ui(connection: &Connection, runtime: &tokio::runtime::Runtime, client: &mut Client) {
     clear_background(Color::from_rgba(50, 115, 85, 255));
 
     root_ui().window(hash!(), vec2(10.0, 10.0), vec2(400.0, 400.0), |ui| {
@@ -25,15 +19,9 @@ pub fn room_ui(
 
         // Buttons: Exit Room and Spectate
         if ui.button(None, "Exit Room") {
-            let socket_clone = socket.clone();
+            let connection = connection.clone();
             runtime.spawn(async move {
-                socket_clone
-                    .emit(
-                        LeaveRoomMessage::MSG_TYPE,
-                        to_string(&LeaveRoomMessage {}).unwrap(),
-                    )
-                    .await
-                    .unwrap();
+                connection.leave_room().await;
             });
         }
 
@@ -62,21 +50,27 @@ pub fn room_ui(
                     ui.label(None, &format!("{}: {}", position_name, user.get_username()));
                 } else if ui.button(None, format!("Join {}", position_name).as_str()) {
                     client.selected_seat = Some(position);
-                    let socket_clone = socket.clone();
+                    let connection = connection.clone();
                     runtime.spawn(async move {
-                        socket_clone
-                            .emit(
-                                SelectPlaceMessage::MSG_TYPE,
-                                to_string(&SelectPlaceMessage {
-                                    position: Some(position),
-                                })
-                                .unwrap(),
-                            )
-                            .await
-                            .unwrap();
+                        connection.select_place(Some(position)).await;
                     });
                 }
             });
         }
+
+        if client.selected_seat.is_some() {
+            ui.separator();
+
+            if ui.button(None, "Play Standard Convention Card") {
+                let connection = connection.clone();
+                runtime.spawn(async move {
+                    connection
+                        .set_convention_card(SetConventionCardMessage {
+                            card: Some(ConventionCard::standard()),
+                        })
+                        .await;
+                });
+            }
+        }
     });
 }
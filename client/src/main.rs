@@ -1,66 +1,47 @@
-mod client;
 mod gui;
 mod notifications;
-mod utils;
+mod requests;
 
-use client::{Client, ClientState};
-use common::message::client_message::LeaveRoomMessage;
+use client_core::handler::{connect, handshake};
+use client_core::utils::update_user_seat;
+use client_core::{add_handler, Client, ClientState};
 use common::message::server_notification::DealFinishedNotification;
 use gui::create_room::create_room_ui;
 use gui::lobby::list_rooms;
 use gui::login::login_ui;
 use gui::play::{play_ui, preload_cards, preload_textures};
+use gui::replay::replay_ui;
 use gui::room::room_ui;
 
 use common::{
-    message::{
-        client_message::{GetCardsMessage, JoinRoomMessage, ListPlacesMessage, ListRoomsMessage},
-        server_notification::{
-            AskBidNotification, AskTrickNotification, AuctionFinishedNotification,
-            DummyCardsNotification, GameFinishedNotification, GameStartedNotification,
-            JoinRoomNotification, LeaveRoomNotification, MakeBidNotification,
-            MakeTrickNotification, SelectPlaceNotification, TrickFinishedNotification,
-        },
-        server_response::{
-            GetCardsResponse, JoinRoomResponse, LeaveRoomResponse, ListPlacesResponse,
-            ListRoomsResponse, LoginResponse, MakeBidResponse, MakeTrickResponse,
-            RegisterRoomResponse, SelectPlaceResponse,
-        },
-        GetErrorMessage, MessageTrait,
+    hand::{high_card_points, shape_str},
+    message::server_notification::{
+        AskBidNotification, AskTrickNotification, AuctionFinishedNotification,
+        BroadcastDealNotification, ConventionCardNotification, DummyCardsNotification,
+        GameFinishedNotification, GameStartedNotification, JoinRoomNotification,
+        LeaveRoomNotification, MakeBidNotification, MakeTrickNotification,
+        MatchScoreboardNotification, SelectPlaceNotification, ServerMessageNotification,
+        TournamentRoundNotification, TournamentStandingsNotification, TrickFinishedNotification,
     },
-    room::RoomId,
-    Card,
+    protocol::Feature,
+    room::{SpectatorView, TimeLeft},
+    Card, Player,
 };
-use futures_util::FutureExt;
 use macroquad::prelude::*;
 use notifications::Notifier;
-use rust_socketio::{asynchronous::ClientBuilder, Payload};
-use serde_json::to_string;
+use requests::Connection;
+use rust_socketio::asynchronous::ClientBuilder;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::{runtime::Runtime, time::sleep};
-use utils::update_user_seat;
-
-macro_rules! add_handler {
-    ($builder:expr, $object:ty, $client_capture:expr, $notifier_capture:expr, |$client:ident, $notifier:ident, $msg:ident, $socket:ident| $body:block) => {
-        $builder = $builder.on(<$object>::MSG_TYPE, {
-            let $client = $client_capture.clone();
-            let $notifier = $notifier_capture.clone();
-            move |payload, $socket| {
-                let $client = $client.clone();
-                let $notifier = $notifier.clone();
-                async move {
-                    let $msg: $object = match payload {
-                        Payload::Text(text) => serde_json::from_value(text[0].clone()).unwrap(),
-                        _ => return,
-                    };
-                    $body
-                }
-                .boxed()
-            }
-        })
-    };
+
+fn time_left_info(time_left: TimeLeft) -> String {
+    format!(
+        "Your turn: {}s + {}s time bank",
+        time_left.move_ms / 1000,
+        time_left.bank_ms / 1000
+    )
 }
 
 #[macroquad::main("Bridge card game")]
@@ -110,118 +91,6 @@ async fn main() {
 
         let mut builder = ClientBuilder::new(&server_url).namespace("/");
 
-        add_handler!(
-            builder,
-            LoginResponse,
-            client,
-            notifier,
-            |client, notifier, msg, s| {
-                match msg {
-                    LoginResponse::Ok => {
-                        client.lock().await.state = ClientState::InLobby;
-
-                        s.emit(
-                            ListRoomsMessage::MSG_TYPE,
-                            to_string(&ListRoomsMessage {}).unwrap(),
-                        )
-                        .await
-                        .unwrap();
-                    }
-                    err => notifier.create_error(err.err_msg()),
-                }
-            }
-        );
-
-        add_handler!(
-            builder,
-            ListRoomsResponse,
-            client,
-            notifier,
-            |client, _notifier, msg, _s| {
-                let rooms = msg
-                    .rooms
-                    .iter()
-                    .map(|room| room.as_str().to_string())
-                    .collect();
-
-                client.lock().await.rooms = rooms;
-            }
-        );
-
-        add_handler!(
-            builder,
-            RegisterRoomResponse,
-            client,
-            notifier,
-            |client, _notifier, _msg, s| {
-                let room_id = RoomId::new(client.lock().await.selected_room_name.clone().into());
-                s.emit(
-                    JoinRoomMessage::MSG_TYPE,
-                    to_string(&JoinRoomMessage { room_id }).unwrap(),
-                )
-                .await
-                .unwrap();
-            }
-        );
-
-        add_handler!(
-            builder,
-            JoinRoomResponse,
-            client,
-            notifier,
-            |client, notifier, msg, s| {
-                match msg {
-                    JoinRoomResponse::Ok => {
-                        client.lock().await.state = ClientState::InRoom;
-
-                        s.emit(
-                            ListPlacesMessage::MSG_TYPE,
-                            to_string(&ListPlacesMessage {}).unwrap(),
-                        )
-                        .await
-                        .unwrap();
-                    }
-                    err => notifier.create_error(err.err_msg()),
-                }
-            }
-        );
-
-        add_handler!(
-            builder,
-            ListPlacesResponse,
-            client,
-            notifier,
-            |client, notifier, msg, _s| {
-                match msg {
-                    ListPlacesResponse::Ok(msg) => {
-                        let mut client_lock = client.lock().await;
-                        client_lock.seats = msg;
-                    }
-                    err => notifier.create_error(err.err_msg()),
-                }
-            }
-        );
-
-        add_handler!(
-            builder,
-            SelectPlaceResponse,
-            client,
-            notifier,
-            |_client, notifier, msg, s| {
-                match msg {
-                    SelectPlaceResponse::Ok => {
-                        s.emit(
-                            ListPlacesMessage::MSG_TYPE,
-                            to_string(&ListPlacesMessage {}).unwrap(),
-                        )
-                        .await
-                        .unwrap();
-                    }
-                    err => notifier.create_error(err.err_msg()),
-                }
-            }
-        );
-
         add_handler!(
             builder,
             SelectPlaceNotification,
@@ -249,27 +118,6 @@ async fn main() {
             }
         );
 
-        add_handler!(
-            builder,
-            LeaveRoomResponse,
-            client,
-            notifier,
-            |client, _notifier, _msg, s| {
-                {
-                    let mut client_lock = client.lock().await;
-                    client_lock.state = ClientState::InLobby;
-                    client_lock.seats = [None, None, None, None];
-                    client_lock.selected_seat = None;
-                }
-                s.emit(
-                    ListRoomsMessage::MSG_TYPE,
-                    to_string(&ListRoomsMessage {}).unwrap(),
-                )
-                .await
-                .unwrap();
-            }
-        );
-
         add_handler!(
             builder,
             LeaveRoomNotification,
@@ -290,32 +138,32 @@ async fn main() {
             GameStartedNotification,
             client,
             notifier,
-            |client, _notifier, _msg, s| {
+            |client, notifier, _msg, s| {
                 client.lock().await.state = ClientState::Playing;
-
-                s.emit(
-                    GetCardsMessage::MSG_TYPE,
-                    to_string(&GetCardsMessage {}).unwrap(),
-                )
-                .await
-                .unwrap();
+                // Requests wait for their responses, which are received by the task running the handlers
+                tokio::spawn(async move {
+                    Connection::new(s, client, notifier).request_cards().await;
+                });
             }
         );
 
         add_handler!(
             builder,
-            GetCardsResponse,
+            BroadcastDealNotification,
             client,
             notifier,
-            |client, notifier, msg, _s| {
-                match msg {
-                    GetCardsResponse::Ok { cards, position } => {
-                        let mut client_lock = client.lock().await;
-                        client_lock.card_list = Some(cards);
-                        client_lock.selected_seat = Some(position);
-                    }
-                    err => notifier.create_error(err.err_msg()),
-                }
+            |client, _notifier, msg, _s| {
+                let mut client_lock = client.lock().await;
+                client_lock.state = ClientState::Playing;
+                client_lock.seats = msg.seats;
+                client_lock.spectator_view = Some(SpectatorView::AllHands);
+                client_lock.kibitzed_hands = msg.deal.hands.map(Some);
+                client_lock.player_bids = [None, None, None, None];
+                client_lock.game_max_bid = None;
+                client_lock.game_max_bidder = None;
+                client_lock.dummy_cards = None;
+                client_lock.dummy_player = None;
+                client_lock.current_placed_cards = [None, None, None, None];
             }
         );
 
@@ -324,33 +172,65 @@ async fn main() {
             AskBidNotification,
             client,
             notifier,
-            |client, _notifier, msg, _s| {
+            |client, notifier, msg, _s| {
                 let mut client_lock = client.lock().await;
+                if client_lock.selected_seat == Some(msg.player) {
+                    if let Some(time_left) = msg.time_left {
+                        notifier.create_info(time_left_info(time_left));
+                    }
+                }
                 client_lock.game_current_player = Some(msg.player);
+                client_lock.legal_bids = if client_lock.selected_seat == Some(msg.player) {
+                    msg.legal_bids
+                } else {
+                    Vec::new()
+                };
             }
         );
 
         add_handler!(
             builder,
-            MakeBidResponse,
+            MakeBidNotification,
             client,
             notifier,
-            |_client, notifier, msg, _s| {
-                match msg {
-                    MakeBidResponse::Ok => {}
-                    err => notifier.create_error(err.err_msg()),
+            |client, notifier, msg, _s| {
+                if let Some(annotation) = &msg.annotation {
+                    if annotation.alert {
+                        notifier.create_info(format!(
+                            "Alert! {} {}: {}",
+                            msg.player,
+                            msg.bid.to_str(),
+                            annotation.meaning
+                        ));
+                    }
+                }
+                if let Some(explanation) = &msg.explanation {
+                    notifier.create_info(format!(
+                        "{} explains {}: {}",
+                        msg.player,
+                        msg.bid.to_str(),
+                        explanation
+                    ));
                 }
+                let mut client_lock = client.lock().await;
+                client_lock.player_bids[msg.player.to_usize()] = Some(msg.bid);
             }
         );
 
         add_handler!(
             builder,
-            MakeBidNotification,
+            ConventionCardNotification,
             client,
             notifier,
-            |client, _notifier, msg, _s| {
-                let mut client_lock = client.lock().await;
-                client_lock.player_bids[msg.player.to_usize()] = Some(msg.bid);
+            |_client, notifier, msg, _s| {
+                match msg.card {
+                    Some(card) => notifier
+                        .create_info(format!("Partnership of {} plays {}", msg.player, card.name)),
+                    None => notifier.create_info(format!(
+                        "Partnership of {} removed their convention card",
+                        msg.player
+                    )),
+                }
             }
         );
 
@@ -369,6 +249,7 @@ async fn main() {
                 client_lock.game_max_bidder = Some(msg.winner);
                 client_lock.game_current_player = Some(msg.winner);
                 client_lock.player_bids = [None, None, None, None];
+                client_lock.legal_bids = Vec::new();
             }
         );
 
@@ -389,30 +270,15 @@ async fn main() {
             AskTrickNotification,
             client,
             notifier,
-            |client, _notifier, msg, _s| {
-                client.lock().await.game_current_player = Some(msg.player);
-            }
-        );
-
-        add_handler!(
-            builder,
-            MakeTrickResponse,
-            client,
-            notifier,
             |client, notifier, msg, _s| {
-                match msg {
-                    MakeTrickResponse::Ok => {
-                        let mut client_lock = client.lock().await;
-
-                        let placed_trick = client_lock.placed_trick;
-                        if let Some(cards) = client_lock.card_list.as_mut() {
-                            if let Some(placed_card) = placed_trick {
-                                cards.retain(|c| *c != placed_card);
-                            }
-                        }
+                let mut client_lock = client.lock().await;
+                if client_lock.selected_seat == Some(msg.player) {
+                    if let Some(time_left) = msg.time_left {
+                        notifier.create_info(time_left_info(time_left));
                     }
-                    err => notifier.create_error(err.err_msg()),
                 }
+                client_lock.game_current_player = Some(msg.player);
+                client_lock.legal_cards = msg.legal_cards;
             }
         );
 
@@ -436,6 +302,11 @@ async fn main() {
                 if let Some(cards) = client_lock.card_list.as_mut() {
                     cards.retain(|c| *c != msg.card);
                 }
+
+                // Remove card from watched hands
+                if let Some(cards) = client_lock.kibitzed_hands[msg.player.to_usize()].as_mut() {
+                    cards.retain(|c| *c != msg.card);
+                }
             }
         );
 
@@ -471,14 +342,116 @@ async fn main() {
                 } else {
                     notifier.create_info(format!("Contract lost by {}", msg.bidder));
                 }
-                let mut client_lock = client.lock().await;
-                client_lock.points = msg.points;
-                s.emit(
-                    GetCardsMessage::MSG_TYPE,
-                    to_string(&GetCardsMessage {}).unwrap(),
-                )
-                .await
-                .unwrap();
+                if let Some(par) = msg.par {
+                    notifier.create_info(format!("Par: {}", par));
+                }
+                for (index, hand) in msg.record.deal.hands.iter().enumerate() {
+                    notifier.create_info(format!(
+                        "{}: {} HCP, {}",
+                        Player::from_usize(index).unwrap(),
+                        high_card_points(hand),
+                        shape_str(hand)
+                    ));
+                }
+                {
+                    let mut client_lock = client.lock().await;
+                    client_lock.points = msg.points;
+                    client_lock.legal_cards = Vec::new();
+                    client_lock.replay.records.push(msg.record);
+                }
+                tokio::spawn(async move {
+                    Connection::new(s, client, notifier).request_cards().await;
+                });
+            }
+        );
+
+        add_handler!(
+            builder,
+            MatchScoreboardNotification,
+            client,
+            notifier,
+            |_client, notifier, msg, _s| {
+                let score = |score: Option<isize>| {
+                    score.map_or(String::from("-"), |score| score.to_string())
+                };
+                for board in msg.boards.iter().filter(|board| board.imps().is_some()) {
+                    notifier.create_info(format!(
+                        "Board {}: open {}, closed {}, {} IMPs",
+                        board.board_no,
+                        score(board.open),
+                        score(board.closed),
+                        board.imps().unwrap()
+                    ));
+                }
+                notifier.create_info(format!("Match total: {} IMPs", msg.imps));
+            }
+        );
+
+        add_handler!(
+            builder,
+            TournamentRoundNotification,
+            client,
+            notifier,
+            |client, notifier, msg, _s| {
+                let name = client.lock().await.name.clone();
+                let pair = msg.pairs.iter().position(|pair| pair.contains(&name));
+                notifier.create_info(format!(
+                    "Round {}/{} of tournament {}, boards {}-{}",
+                    msg.round,
+                    msg.rounds,
+                    msg.tournament_id,
+                    msg.boards.start(),
+                    msg.boards.end()
+                ));
+                for table in msg.tables {
+                    let seats = if Some(table.assignment.north_south) == pair {
+                        "North-South"
+                    } else if Some(table.assignment.east_west) == pair {
+                        "East-West"
+                    } else {
+                        continue;
+                    };
+                    notifier.create_info(format!(
+                        "Join room {} and sit {}",
+                        table.room_id.as_str(),
+                        seats
+                    ));
+                }
+            }
+        );
+
+        add_handler!(
+            builder,
+            TournamentStandingsNotification,
+            client,
+            notifier,
+            |_client, notifier, msg, _s| {
+                let title = if msg.is_final() {
+                    "Final standings"
+                } else {
+                    "Standings"
+                };
+                notifier.create_info(format!(
+                    "{} of tournament {} after round {}/{}:",
+                    title, msg.tournament_id, msg.round, msg.rounds
+                ));
+                let fields = msg.fields();
+                for (field, rankings) in fields.iter().enumerate() {
+                    if fields.len() > 1 {
+                        let direction = if field == 0 { "North-South" } else { "East-West" };
+                        notifier.create_info(format!("{}:", direction));
+                    }
+                    for (place, ranking) in rankings.iter().enumerate() {
+                        let [first, second] = &msg.pairs[ranking.pair];
+                        notifier.create_info(format!(
+                            "{}. {} & {}: {:.2}%",
+                            place + 1,
+                            first,
+                            second,
+                            ranking.percentage()
+                        ));
+                    }
+                }
             }
         );
 
@@ -502,27 +475,38 @@ async fn main() {
                     client_lock.placed_trick = None;
                     client_lock.game_max_bid = None;
                     client_lock.game_current_player = None;
+                    client_lock.legal_bids = Vec::new();
+                    client_lock.legal_cards = Vec::new();
                     client_lock.dummy_cards = None;
                     client_lock.dummy_player = None;
                     client_lock.current_placed_cards = [None, None, None, None];
+                    client_lock.spectator_view = None;
+                    client_lock.kibitzed_hands = [None, None, None, None];
+                    client_lock.watching_broadcast = false;
                 }
-                s.emit(
-                    LeaveRoomMessage::MSG_TYPE,
-                    to_string(&LeaveRoomMessage {}).unwrap(),
-                )
-                .await
-                .unwrap();
-                s.emit(
-                    ListRoomsMessage::MSG_TYPE,
-                    to_string(&ListRoomsMessage {}).unwrap(),
-                )
-                .await
-                .unwrap();
+                tokio::spawn(async move {
+                    Connection::new(s, client, notifier).leave_room().await;
+                });
+            }
+        );
+
+        add_handler!(
+            builder,
+            ServerMessageNotification,
+            client,
+            notifier,
+            |_client, notifier, msg, _s| {
+                notifier.create_info(format!("Message from the server: {}", msg.message));
             }
         );
 
-        Arc::new(builder.connect().await.expect("Connection failed"))
+        let socket = connect(builder).await.expect("Connection failed");
+        handshake(&socket, Feature::ALL.to_vec())
+            .await
+            .unwrap_or_else(|err| panic!("{}", err));
+        socket
     });
+    let connection = Connection::new(socket, client.clone(), notifier.clone());
 
     loop {
         clear_background(Color::from_rgba(50, 115, 85, 255));
@@ -532,26 +516,29 @@ async fn main() {
 
         match current_state {
             ClientState::Logging => {
-                login_ui(socket.clone(), &runtime, &mut client_lock.name);
+                login_ui(&connection, &runtime, &mut client_lock.name);
             }
             ClientState::InLobby => {
-                list_rooms(socket.clone(), &runtime, &mut client_lock);
+                list_rooms(&connection, &runtime, &mut client_lock);
             }
             ClientState::CreatingRoom => {
-                create_room_ui(socket.clone(), &runtime, &mut client_lock);
+                create_room_ui(&connection, &runtime, &mut client_lock);
             }
             ClientState::InRoom => {
-                room_ui(socket.clone(), &runtime, &mut client_lock);
+                room_ui(&connection, &runtime, &mut client_lock);
             }
             ClientState::Playing => {
                 play_ui(
-                    socket.clone(),
+                    &connection,
                     &runtime,
                     &mut client_lock,
                     &bid_textures,
                     &card_textures,
                 );
             }
+            ClientState::Replaying => {
+                replay_ui(&mut client_lock, &bid_textures, &card_textures);
+            }
         }
 
         notifier.display().await;
@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use client_core::{handler::request, Client, ClientState};
+use common::{
+    message::{
+        client_message::{
+            GetCardsMessage, JoinRoomMessage, JoinTournamentMessage, KibitzMessage,
+            LeaveRoomMessage, ListPlacesMessage, ListRoomsMessage, LoginMessage, MakeBidMessage,
+            MakeTrickMessage, RegisterMatchMessage, RegisterRoomMessage, SelectPlaceMessage,
+            SetConventionCardMessage, WatchBroadcastMessage,
+        },
+        server_response::{
+            GetCardsResponse, JoinRoomResponse, JoinTournamentResponse, KibitzResponse,
+            ListPlacesResponse, LoginResponse, MakeBidResponse, MakeTrickResponse,
+            RegisterRoomResponse, SelectPlaceResponse, SetConventionCardResponse,
+            WatchBroadcastResponse,
+        },
+        GetErrorMessage, RequestTrait,
+    },
+    room::{RoomId, SpectatorView},
+    Card, Player,
+};
+use rust_socketio::asynchronous::Client as Socket;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::Mutex;
+
+use crate::notifications::Notifier;
+
+/// Sends requests to the server and handles their responses.
+/// Every request waits for its own response, so it mustn't be awaited in a handler of server messages.
+#[derive(Clone)]
+pub struct Connection {
+    socket: Socket,
+    client: Arc<Mutex<Client>>,
+    notifier: Notifier,
+}
+
+impl Connection {
+    pub fn new(socket: Socket, client: Arc<Mutex<Client>>, notifier: Notifier) -> Self {
+        Connection {
+            socket,
+            client,
+            notifier,
+        }
+    }
+
+    /// Sends `message` and waits for its response, shows an error if none came
+    async fn request<M>(&self, message: &M) -> Option<M::Response>
+    where
+        M: RequestTrait + Serialize,
+        M::Response: DeserializeOwned,
+    {
+        match request(&self.socket, message).await {
+            Ok(response) => Some(response),
+            Err(err) => {
+                self.notifier
+                    .create_error(format!("No response from the server: {:?}", err));
+                None
+            }
+        }
+    }
+
+    pub async fn login(&self, message: LoginMessage) {
+        match self.request(&message).await {
+            Some(LoginResponse::Ok) => {
+                self.client.lock().await.state = ClientState::InLobby;
+                self.list_rooms().await;
+            }
+            Some(err) => self.notifier.create_error(err.err_msg()),
+            None => {}
+        }
+    }
+
+    pub async fn list_rooms(&self) {
+        let Some(response) = self.request(&ListRoomsMessage {}).await else {
+            return;
+        };
+        self.client.lock().await.rooms = response
+            .rooms
+            .iter()
+            .map(|room| room.as_str().to_string())
+            .collect();
+    }
+
+    pub async fn register_room(&self, message: RegisterRoomMessage) {
+        let response = self.request(&message).await;
+        self.join_registered_room(response).await;
+    }
+
+    pub async fn register_match(&self, message: RegisterMatchMessage) {
+        let response = self.request(&message).await;
+        self.join_registered_room(response).await;
+    }
+
+    /// Joins the selected room once it's registered
+    async fn join_registered_room(&self, response: Option<RegisterRoomResponse>) {
+        match response {
+            Some(RegisterRoomResponse::Ok) => {
+                let room_id =
+                    RoomId::new(self.client.lock().await.selected_room_name.clone().into());
+                self.join_room(room_id).await;
+            }
+            Some(err) => self.notifier.create_error(err.err_msg()),
+            None => {}
+        }
+    }
+
+    pub async fn join_room(&self, room_id: RoomId) {
+        match self.request(&JoinRoomMessage { room_id }).await {
+            Some(JoinRoomResponse::Ok) => {
+                self.client.lock().await.state = ClientState::InRoom;
+                self.list_places().await;
+            }
+            Some(err) => self.notifier.create_error(err.err_msg()),
+            None => {}
+        }
+    }
+
+    pub async fn list_places(&self) {
+        match self.request(&ListPlacesMessage {}).await {
+            Some(ListPlacesResponse::Ok(seats)) => self.client.lock().await.seats = seats,
+            Some(err) => self.notifier.create_error(err.err_msg()),
+            None => {}
+        }
+    }
+
+    pub async fn select_place(&self, position: Option<Player>) {
+        match self.request(&SelectPlaceMessage { position }).await {
+            Some(SelectPlaceResponse::Ok) => self.list_places().await,
+            Some(err) => self.notifier.create_error(err.err_msg()),
+            None => {}
+        }
+    }
+
+    pub async fn leave_room(&self) {
+        if self.request(&LeaveRoomMessage {}).await.is_none() {
+            return;
+        }
+        {
+            let mut client_lock = self.client.lock().await;
+            client_lock.state = ClientState::InLobby;
+            client_lock.seats = [None, None, None, None];
+            client_lock.selected_seat = None;
+            client_lock.spectator_view = None;
+            client_lock.kibitzed_hands = [None, None, None, None];
+        }
+        self.list_rooms().await;
+    }
+
+    /// Asks for the cards of the player, or for the hands watched by a spectator
+    pub async fn request_cards(&self) {
+        let (watching_broadcast, selected_seat, spectator_view) = {
+            let client_lock = self.client.lock().await;
+            (
+                client_lock.watching_broadcast,
+                client_lock.selected_seat,
+                client_lock.spectator_view,
+            )
+        };
+        // Audience of a broadcast gets all hands with every deal
+        if watching_broadcast {
+            return;
+        }
+        if let (None, Some(view)) = (selected_seat, spectator_view) {
+            self.kibitz(view).await;
+            return;
+        }
+        match self.request(&GetCardsMessage {}).await {
+            Some(GetCardsResponse::Ok { cards, position }) => {
+                let mut client_lock = self.client.lock().await;
+                client_lock.card_list = Some(cards);
+                client_lock.selected_seat = Some(position);
+            }
+            Some(err) => self.notifier.create_error(err.err_msg()),
+            None => {}
+        }
+    }
+
+    pub async fn kibitz(&self, view: SpectatorView) {
+        match self.request(&KibitzMessage { view }).await {
+            Some(KibitzResponse::Ok { hands }) => self.client.lock().await.kibitzed_hands = hands,
+            Some(err) => self.notifier.create_error(err.err_msg()),
+            None => {}
+        }
+    }
+
+    pub async fn watch_broadcast(&self, message: WatchBroadcastMessage) {
+        match self.request(&message).await {
+            Some(WatchBroadcastResponse::Ok) => {
+                self.client.lock().await.watching_broadcast = true;
+                self.notifier.create_info(String::from(
+                    "Watching the broadcast, it starts with the next deal",
+                ));
+            }
+            Some(err) => self.notifier.create_error(err.err_msg()),
+            None => {}
+        }
+    }
+
+    pub async fn make_bid(&self, message: MakeBidMessage) {
+        match self.request(&message).await {
+            Some(MakeBidResponse::Ok) | None => {}
+            Some(err) => self.notifier.create_error(err.err_msg()),
+        }
+    }
+
+    pub async fn make_trick(&self, card: Card) {
+        match self.request(&MakeTrickMessage { card }).await {
+            Some(MakeTrickResponse::Ok) => {
+                if let Some(cards) = self.client.lock().await.card_list.as_mut() {
+                    cards.retain(|c| *c != card);
+                }
+            }
+            Some(err) => self.notifier.create_error(err.err_msg()),
+            None => {}
+        }
+    }
+
+    pub async fn set_convention_card(&self, message: SetConventionCardMessage) {
+        match self.request(&message).await {
+            Some(SetConventionCardResponse::Ok) | None => {}
+            Some(err) => self.notifier.create_error(err.err_msg()),
+        }
+    }
+
+    pub async fn join_tournament(&self, message: JoinTournamentMessage) {
+        match self.request(&message).await {
+            Some(JoinTournamentResponse::Ok) | None => {}
+            Some(err) => self.notifier.create_error(err.err_msg()),
+        }
+    }
+}
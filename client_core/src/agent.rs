@@ -0,0 +1,499 @@
+use std::sync::Arc;
+
+use common::{
+    message::{
+        client_message::{
+            GetCardsMessage, JoinRoomMessage, ListPlacesMessage, LoginMessage, MakeBidMessage,
+            MakeTrickMessage, RegisterRoomMessage, SelectPlaceMessage,
+        },
+        server_notification::{
+            AskBidNotification, AskTrickNotification, AuctionFinishedNotification,
+            DealFinishedNotification, DummyCardsNotification, GameFinishedNotification,
+            GameStartedNotification, LeaveRoomNotification, MakeBidNotification,
+            MakeTrickNotification, SelectPlaceNotification, ServerMessageNotification,
+            TrickFinishedNotification,
+        },
+        server_response::{
+            GetCardsResponse, JoinRoomResponse, ListPlacesResponse, LoginResponse, MakeBidResponse,
+            MakeTrickResponse, RegisterRoomResponse, SelectPlaceResponse,
+        },
+        GetErrorMessage,
+    },
+    protocol::Feature,
+    room::{RoomId, RoomInfo, Visibility},
+    user::User,
+    Bid, Card, Player,
+};
+use rust_socketio::asynchronous::{Client as Socket, ClientBuilder};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::{
+    add_handler,
+    client::{Client, ClientState},
+    handler::{connect, emit, handshake},
+    utils::update_user_seat,
+};
+
+/// Player logic of a headless client.
+/// Every callback gets the `Client` state, which is kept up to date by `run_agent`.
+pub trait BridgeAgent: Send + 'static {
+    /// Called when it's agent's turn to bid
+    fn on_ask_bid(&mut self, client: &Client, ask: &AskBidNotification) -> Bid;
+
+    /// Called when it's agent's turn to play a card
+    fn on_ask_trick(&mut self, client: &Client, ask: &AskTrickNotification) -> Card;
+
+    /// Called when all 13 tricks of a deal were played
+    fn on_deal_finished(&mut self, _client: &Client, _result: &DealFinishedNotification) {}
+}
+
+pub struct AgentConfig {
+    pub server_url: String,
+    pub username: String,
+    /// Room to join, it is registered as public room if it doesn't exist yet
+    pub room_id: RoomId,
+    /// Preferred seat. If it is `None` or already taken, the first free seat is selected.
+    pub position: Option<Player>,
+}
+
+enum PendingAsk {
+    Bid(AskBidNotification),
+    Trick(AskTrickNotification),
+}
+
+struct AgentState<A> {
+    client: Client,
+    agent: A,
+    config: AgentConfig,
+    /// Request for a move that arrived before agent's cards.
+    /// It is answered once `GetCardsResponse` arrives.
+    pending: Option<PendingAsk>,
+}
+
+impl<A: BridgeAgent> AgentState<A> {
+    async fn answer(&mut self, socket: &Socket, ask: PendingAsk) {
+        if self.client.card_list.is_none() {
+            self.pending = Some(ask);
+            return;
+        }
+
+        match ask {
+            PendingAsk::Bid(ask) => {
+                let bid = self.agent.on_ask_bid(&self.client, &ask);
+                self.client.placed_bid = Some(bid);
+                emit(
+                    socket,
+                    &MakeBidMessage {
+                        bid,
+                        explanation: None,
+                    },
+                )
+                .await;
+            }
+            PendingAsk::Trick(ask) => {
+                let card = self.agent.on_ask_trick(&self.client, &ask);
+                self.client.placed_trick = Some(card);
+                emit(socket, &MakeTrickMessage { card }).await;
+            }
+        }
+    }
+}
+
+/// Connects to the server, joins the configured room and plays with given agent until the game finishes.
+/// Returns error message if the agent couldn't take a place in the room.
+pub async fn run_agent<A: BridgeAgent>(config: AgentConfig, agent: A) -> Result<(), String> {
+    let username = config.username.clone();
+    let mut builder = ClientBuilder::new(config.server_url.as_str()).namespace("/");
+
+    let state = Arc::new(Mutex::new(AgentState {
+        client: Client::new(),
+        agent,
+        config,
+        pending: None,
+    }));
+    let (finished_tx, mut finished_rx) = mpsc::unbounded_channel::<Result<(), String>>();
+
+    add_handler!(
+        builder,
+        LoginResponse,
+        state,
+        finished_tx,
+        |state, finished_tx, msg, s| {
+            match msg {
+                LoginResponse::Ok => {
+                    let room_id = {
+                        let mut state_lock = state.lock().await;
+                        state_lock.client.state = ClientState::InLobby;
+                        state_lock.config.room_id.clone()
+                    };
+                    let room_info = RoomInfo {
+                        id: room_id,
+                        visibility: Visibility::Public,
+                        deal_constraints: None,
+                        time_control: None,
+                        allow_kibitzing: false,
+                        broadcast_delay_secs: None,
+                        seed: None,
+                    };
+                    emit(&s, &RegisterRoomMessage { room_info }).await;
+                }
+                err => {
+                    finished_tx.send(Err(err.err_msg())).ok();
+                }
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        RegisterRoomResponse,
+        state,
+        finished_tx,
+        |state, finished_tx, msg, s| {
+            match msg {
+                RegisterRoomResponse::Ok | RegisterRoomResponse::RoomIdAlreadyExists => {
+                    let room_id = state.lock().await.config.room_id.clone();
+                    emit(&s, &JoinRoomMessage { room_id }).await;
+                }
+                err => {
+                    finished_tx.send(Err(format!("{:?}", err))).ok();
+                }
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        JoinRoomResponse,
+        state,
+        finished_tx,
+        |state, finished_tx, msg, s| {
+            match msg {
+                JoinRoomResponse::Ok => {
+                    state.lock().await.client.state = ClientState::InRoom;
+                    emit(&s, &ListPlacesMessage {}).await;
+                }
+                err => {
+                    finished_tx.send(Err(err.err_msg())).ok();
+                }
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        ListPlacesResponse,
+        state,
+        finished_tx,
+        |state, finished_tx, msg, s| {
+            let seats = match msg {
+                ListPlacesResponse::Ok(seats) => seats,
+                err => {
+                    finished_tx.send(Err(err.err_msg())).ok();
+                    return;
+                }
+            };
+
+            let mut state_lock = state.lock().await;
+            state_lock.client.seats = seats;
+            if state_lock.client.state != ClientState::InRoom
+                || state_lock.client.selected_seat.is_some()
+            {
+                return;
+            }
+
+            let preferred = state_lock
+                .config
+                .position
+                .filter(|position| state_lock.client.seats[position.to_usize()].is_none());
+            let free = state_lock
+                .client
+                .seats
+                .iter()
+                .position(Option::is_none)
+                .and_then(Player::from_usize);
+            let Some(position) = preferred.or(free) else {
+                finished_tx
+                    .send(Err("There is no free place in the room".into()))
+                    .ok();
+                return;
+            };
+
+            state_lock.client.selected_seat = Some(position);
+            emit(
+                &s,
+                &SelectPlaceMessage {
+                    position: Some(position),
+                },
+            )
+            .await;
+        }
+    );
+
+    add_handler!(
+        builder,
+        SelectPlaceResponse,
+        state,
+        finished_tx,
+        |state, finished_tx, msg, s| {
+            match msg {
+                SelectPlaceResponse::Ok => {}
+                SelectPlaceResponse::PlaceAlreadyTaken => {
+                    // Someone was faster, look for another place
+                    state.lock().await.client.selected_seat = None;
+                    emit(&s, &ListPlacesMessage {}).await;
+                }
+                err => {
+                    finished_tx.send(Err(err.err_msg())).ok();
+                }
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        SelectPlaceNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, _s| {
+            update_user_seat(&mut state.lock().await.client.seats, msg.user, msg.position);
+        }
+    );
+
+    add_handler!(
+        builder,
+        LeaveRoomNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, _s| {
+            update_user_seat(&mut state.lock().await.client.seats, msg.user, None);
+        }
+    );
+
+    add_handler!(
+        builder,
+        GameStartedNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, s| {
+            {
+                let mut state_lock = state.lock().await;
+                state_lock.client.state = ClientState::Playing;
+                state_lock.client.seats = msg.player_position.map(Some);
+            }
+            emit(&s, &GetCardsMessage {}).await;
+        }
+    );
+
+    add_handler!(
+        builder,
+        GetCardsResponse,
+        state,
+        finished_tx,
+        |state, finished_tx, msg, s| {
+            match msg {
+                GetCardsResponse::Ok { cards, position } => {
+                    let mut state_lock = state.lock().await;
+                    state_lock.client.card_list = Some(cards);
+                    state_lock.client.selected_seat = Some(position);
+                    if let Some(ask) = state_lock.pending.take() {
+                        state_lock.answer(&s, ask).await;
+                    }
+                }
+                err => {
+                    finished_tx.send(Err(err.err_msg())).ok();
+                }
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        AskBidNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, s| {
+            let mut state_lock = state.lock().await;
+            state_lock.client.game_current_player = Some(msg.player);
+            if state_lock.client.selected_seat == Some(msg.player) {
+                state_lock.client.legal_bids = msg.legal_bids.clone();
+                state_lock.answer(&s, PendingAsk::Bid(msg)).await;
+            } else {
+                state_lock.client.legal_bids = Vec::new();
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        MakeBidResponse,
+        state,
+        finished_tx,
+        |_state, _finished_tx, msg, _s| {
+            match msg {
+                MakeBidResponse::Ok => {}
+                err => eprintln!("Bid rejected: {}", err.err_msg()),
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        MakeBidNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, _s| {
+            state.lock().await.client.player_bids[msg.player.to_usize()] = Some(msg.bid);
+        }
+    );
+
+    add_handler!(
+        builder,
+        AuctionFinishedNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, _s| {
+            let mut state_lock = state.lock().await;
+            if let AuctionFinishedNotification::Winner(msg) = msg {
+                state_lock.client.game_max_bid = Some(msg.max_bid);
+                state_lock.client.game_max_bidder = Some(msg.winner);
+            }
+            state_lock.client.player_bids = [None, None, None, None];
+            state_lock.client.legal_bids = Vec::new();
+        }
+    );
+
+    add_handler!(
+        builder,
+        DummyCardsNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, _s| {
+            let mut state_lock = state.lock().await;
+            state_lock.client.dummy_cards = Some(msg.cards);
+            state_lock.client.dummy_player = Some(msg.dummy);
+        }
+    );
+
+    add_handler!(
+        builder,
+        AskTrickNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, s| {
+            let mut state_lock = state.lock().await;
+            state_lock.client.game_current_player = Some(msg.player);
+            state_lock.client.legal_cards = msg.legal_cards.clone();
+            if state_lock.client.selected_seat == Some(msg.player) {
+                state_lock.answer(&s, PendingAsk::Trick(msg)).await;
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        MakeTrickResponse,
+        state,
+        finished_tx,
+        |_state, _finished_tx, msg, _s| {
+            match msg {
+                MakeTrickResponse::Ok => {}
+                err => eprintln!("Card rejected: {}", err.err_msg()),
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        MakeTrickNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, _s| {
+            let mut state_lock = state.lock().await;
+            let client = &mut state_lock.client;
+
+            client.current_placed_cards[msg.player.to_usize()] = Some(msg.card);
+            if let Some(dummy_cards) = client.dummy_cards.as_mut() {
+                dummy_cards.retain(|c| *c != msg.card);
+            }
+            if let Some(cards) = client.card_list.as_mut() {
+                cards.retain(|c| *c != msg.card);
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        TrickFinishedNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, _msg, _s| {
+            state.lock().await.client.current_placed_cards = [None, None, None, None];
+        }
+    );
+
+    add_handler!(
+        builder,
+        DealFinishedNotification,
+        state,
+        finished_tx,
+        |state, _finished_tx, msg, s| {
+            {
+                let mut state_lock = state.lock().await;
+                let state_lock = &mut *state_lock;
+                state_lock.agent.on_deal_finished(&state_lock.client, &msg);
+
+                let client = &mut state_lock.client;
+                client.points = msg.points;
+                // Cards of the next deal are requested below
+                client.card_list = None;
+                client.placed_bid = None;
+                client.placed_trick = None;
+                client.legal_cards = Vec::new();
+                client.game_max_bid = None;
+                client.game_max_bidder = None;
+                client.dummy_cards = None;
+                client.dummy_player = None;
+            }
+            emit(&s, &GetCardsMessage {}).await;
+        }
+    );
+
+    add_handler!(
+        builder,
+        GameFinishedNotification,
+        state,
+        finished_tx,
+        |_state, finished_tx, _msg, _s| {
+            finished_tx.send(Ok(())).ok();
+        }
+    );
+
+    add_handler!(
+        builder,
+        ServerMessageNotification,
+        state,
+        finished_tx,
+        |_state, _finished_tx, msg, _s| {
+            println!("Message from the server: {}", msg.message);
+        }
+    );
+
+    let socket = connect(builder).await.map_err(|err| err.to_string())?;
+    handshake(&socket, vec![Feature::Acknowledgements]).await?;
+
+    emit(
+        &socket,
+        &LoginMessage {
+            user: User::new(&username),
+        },
+    )
+    .await;
+
+    // `finished_tx` lives until the end of this function, so the channel is never closed here
+    let result = finished_rx.recv().await.unwrap();
+
+    socket.disconnect().await.ok();
+
+    result
+}
@@ -0,0 +1,125 @@
+use client_core::{run_agent, AgentConfig, BridgeAgent, Client};
+use common::{
+    message::server_notification::{
+        AskBidNotification, AskTrickNotification, DealFinishedNotification,
+    },
+    room::RoomId,
+    Bid, Card, Player,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// Bot that makes random moves allowed by the rules
+struct RandomBot {
+    rng: StdRng,
+}
+
+impl BridgeAgent for RandomBot {
+    fn on_ask_bid(&mut self, _client: &Client, ask: &AskBidNotification) -> Bid {
+        // Passing most of the time, so that the auction finishes at a reasonable level
+        if self.rng.gen_bool(0.7) {
+            return Bid::Pass;
+        }
+
+        ask.legal_bids
+            .choose(&mut self.rng)
+            .copied()
+            .unwrap_or(Bid::Pass)
+    }
+
+    fn on_ask_trick(&mut self, _client: &Client, ask: &AskTrickNotification) -> Card {
+        // Unwrap is valid, as player is asked only when he has cards left
+        *ask.legal_cards.choose(&mut self.rng).unwrap()
+    }
+
+    fn on_deal_finished(&mut self, client: &Client, result: &DealFinishedNotification) {
+        let outcome = if result.contract_succeeded {
+            "made"
+        } else {
+            "went down"
+        };
+        println!(
+            "Contract of {} {}, points: {:?}",
+            result.bidder, outcome, client.points
+        );
+        if let Some(par) = result.par {
+            println!("Par: {}", par);
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args = clap::Command::new("random-bot")
+        .arg(
+            clap::Arg::new("port")
+                .short('p')
+                .long("port")
+                .value_name("PORT")
+                .help("Port to connect to")
+                .default_value("3000"),
+        )
+        .arg(
+            clap::Arg::new("server_ip")
+                .short('s')
+                .long("server_ip")
+                .value_name("SERVER_IP")
+                .help("Server IP to connect to")
+                .default_value("localhost"),
+        )
+        .arg(
+            clap::Arg::new("protocol")
+                .short('g')
+                .long("protocol")
+                .value_name("PROTOCOL")
+                .help("Protocol to use (http or https)")
+                .default_value("http"),
+        )
+        .arg(
+            clap::Arg::new("username")
+                .short('u')
+                .long("username")
+                .value_name("USERNAME")
+                .help("Username of the bot")
+                .default_value("random_bot"),
+        )
+        .arg(
+            clap::Arg::new("room")
+                .short('r')
+                .long("room")
+                .value_name("ROOM")
+                .help("Room to join, it is created if it doesn't exist")
+                .default_value("bots"),
+        )
+        .arg(
+            clap::Arg::new("position")
+                .short('o')
+                .long("position")
+                .value_name("POSITION")
+                .help("Preferred seat (0 - North, 1 - East, 2 - South, 3 - West)"),
+        )
+        .get_matches();
+
+    let port = args.get_one::<String>("port").unwrap();
+    let host = args.get_one::<String>("server_ip").unwrap();
+    let protocol = args.get_one::<String>("protocol").unwrap();
+
+    let config = AgentConfig {
+        server_url: format!("{}://{}:{}/", protocol, host, port),
+        username: args.get_one::<String>("username").unwrap().clone(),
+        room_id: RoomId::new(args.get_one::<String>("room").unwrap().as_str().into()),
+        position: args
+            .get_one::<String>("position")
+            .and_then(|position| position.parse().ok())
+            .and_then(Player::from_u8),
+    };
+
+    println!("Connecting to {}", config.server_url);
+
+    let bot = RandomBot {
+        rng: StdRng::from_entropy(),
+    };
+    if let Err(err) = run_agent(config, bot).await {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
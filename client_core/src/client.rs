@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use common::{record::DealRecord, room::SpectatorView, user::User, Bid, Card, Player};
+
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ClientState {
+    Logging,
+    InLobby,
+    CreatingRoom,
+    InRoom,
+    Playing,
+    Replaying,
+}
+
+/// State of the replay screen
+#[derive(Default)]
+pub struct Replay {
+    /// Deals finished during the session or loaded from a file
+    pub records: Vec<DealRecord>,
+    /// Index of the replayed deal
+    pub deal: usize,
+    /// Number of calls and cards shown
+    pub step: usize,
+    /// Path of the file to save the records to or load them from
+    pub file: String,
+    /// Result of the last save or load
+    pub status: String,
+}
+
+pub struct Client {
+    pub name: String,
+    pub state: ClientState,
+    pub rooms: Vec<String>,
+    pub selected_room_name: String,
+    /// Id of the tournament typed in the lobby
+    pub tournament_id: String,
+    pub seats: [Option<User>; 4],
+    pub selected_seat: Option<Player>,
+    pub card_list: Option<Vec<Card>>,
+    pub player_bids: [Option<Bid>; 4],
+    pub placed_bid: Option<Bid>,
+    pub placed_trick: Option<Card>,
+    pub game_max_bid: Option<Bid>,
+    pub game_max_bidder: Option<Player>,
+    pub game_current_player: Option<Player>,
+    /// Bids that can be placed, non-empty only when it's this client's turn to bid
+    pub legal_bids: Vec<Bid>,
+    /// Cards that can be played, non-empty only when it's this client's turn to play
+    pub legal_cards: Vec<Card>,
+    pub dummy_cards: Option<Vec<Card>>,
+    pub dummy_player: Option<Player>,
+    pub current_placed_cards: [Option<Card>; 4],
+    pub points: [usize; 4],
+    /// Hands watched as a spectator, `None` when not kibitzing
+    pub spectator_view: Option<SpectatorView>,
+    /// Hands of the players received while kibitzing
+    pub kibitzed_hands: [Option<Vec<Card>>; 4],
+    /// Whether the room being created allows kibitzing
+    pub allow_kibitzing: bool,
+    /// Whether the room being created is broadcast
+    pub broadcast: bool,
+    /// Whether the client watches a delayed broadcast instead of sitting in a room
+    pub watching_broadcast: bool,
+    pub replay: Replay,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Client {
+    pub fn new() -> Client {
+        Client {
+            name: String::new(),
+            state: ClientState::Logging,
+            rooms: Vec::new(),
+            selected_room_name: String::new(),
+            tournament_id: String::new(),
+            seats: [None, None, None, None],
+            selected_seat: None,
+            card_list: None,
+            player_bids: [None, None, None, None],
+            placed_bid: None,
+            placed_trick: None,
+            game_max_bid: None,
+            game_max_bidder: None,
+            game_current_player: None,
+            legal_bids: Vec::new(),
+            legal_cards: Vec::new(),
+            dummy_cards: None,
+            dummy_player: None,
+            current_placed_cards: [None, None, None, None],
+            points: [0, 0, 0, 0],
+            spectator_view: None,
+            kibitzed_hands: [None, None, None, None],
+            allow_kibitzing: false,
+            broadcast: false,
+            watching_broadcast: false,
+            replay: Replay::default(),
+        }
+    }
+}
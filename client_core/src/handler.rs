@@ -0,0 +1,148 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use common::{
+    message::{
+        client_message::HelloMessage, server_response::HelloResponse, GetErrorMessage,
+        MessageTrait, RequestTrait,
+    },
+    protocol::{Feature, Protocol},
+};
+use futures_util::FutureExt;
+use rust_socketio::{
+    asynchronous::{Client as Socket, ClientBuilder},
+    Event, Payload,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{to_string, Value};
+use tokio::{
+    sync::{oneshot, Notify},
+    time::timeout,
+};
+
+/// Time to wait for the response to a request
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reason why a request got no response
+#[derive(Debug)]
+pub enum RequestError {
+    /// The request couldn't be sent
+    Emit(rust_socketio::Error),
+    /// No response came in `REQUEST_TIMEOUT`
+    Timeout,
+    /// The response couldn't be deserialized
+    InvalidResponse,
+}
+
+/// Registers handler for server message of type `$object` on given `ClientBuilder`.
+/// Both captures are cloned into the handler, so they should be cheap to clone (e.g. `Arc`).
+#[macro_export]
+macro_rules! add_handler {
+    ($builder:expr, $object:ty, $client_capture:expr, $notifier_capture:expr, |$client:ident, $notifier:ident, $msg:ident, $socket:ident| $body:block) => {
+        $builder = $builder.on(
+            <$object as $crate::common::message::MessageTrait>::MSG_TYPE,
+            {
+                let $client = $client_capture.clone();
+                let $notifier = $notifier_capture.clone();
+                move |payload, $socket| {
+                    let $client = $client.clone();
+                    let $notifier = $notifier.clone();
+                    $crate::futures_util::FutureExt::boxed(async move {
+                        let $msg: $object = match payload {
+                            $crate::rust_socketio::Payload::Text(text) => {
+                                $crate::serde_json::from_value(text[0].clone()).unwrap()
+                            }
+                            _ => return,
+                        };
+                        $body
+                    })
+                }
+            },
+        )
+    };
+}
+
+/// Sends given message to server
+pub async fn emit<M>(socket: &Socket, message: &M)
+where
+    M: MessageTrait + Serialize,
+{
+    socket
+        .emit(M::MSG_TYPE, to_string(message).unwrap())
+        .await
+        .unwrap();
+}
+
+/// Sends given request to server and waits for its response.
+/// The response comes as the acknowledgement of this request, so it can't be confused with responses to other requests,
+/// and it isn't passed to the handler of its message type.
+///
+/// Responses are received by the task running the handlers, so this must not be awaited in a handler.
+pub async fn request<M>(socket: &Socket, message: &M) -> Result<M::Response, RequestError>
+where
+    M: RequestTrait + Serialize,
+    M::Response: DeserializeOwned,
+{
+    let (sender, receiver) = oneshot::channel();
+    let sender = Arc::new(Mutex::new(Some(sender)));
+
+    socket
+        .emit_with_ack(
+            M::MSG_TYPE,
+            to_string(message).unwrap(),
+            REQUEST_TIMEOUT,
+            move |payload, _| {
+                if let Some(sender) = sender.lock().unwrap().take() {
+                    sender.send(payload).ok();
+                }
+                async {}.boxed()
+            },
+        )
+        .await
+        .map_err(RequestError::Emit)?;
+
+    let payload = timeout(REQUEST_TIMEOUT, receiver)
+        .await
+        .map_err(|_| RequestError::Timeout)?
+        .map_err(|_| RequestError::Timeout)?;
+
+    let Payload::Text(values) = payload else {
+        return Err(RequestError::InvalidResponse);
+    };
+    // Acknowledgements carry an array of values
+    let value = match values.into_iter().next() {
+        Some(Value::Array(values)) => values.into_iter().next(),
+        value => value,
+    };
+    value
+        .and_then(|value| serde_json::from_value(value).ok())
+        .ok_or(RequestError::InvalidResponse)
+}
+
+/// Connects to the server and waits until it accepts the connection,
+/// messages sent before that are dropped by the server.
+pub async fn connect(builder: ClientBuilder) -> Result<Socket, rust_socketio::Error> {
+    let connected = Arc::new(Notify::new());
+    let notify = connected.clone();
+    let socket = builder
+        .on(Event::Connect, move |_, _| {
+            notify.notify_one();
+            async {}.boxed()
+        })
+        .connect()
+        .await?;
+    connected.notified().await;
+    Ok(socket)
+}
+
+/// Agrees on the protocol with the server, should be done right after connecting.
+/// Fails with a message for the user if the server refuses the client.
+pub async fn handshake(socket: &Socket, features: Vec<Feature>) -> Result<Protocol, String> {
+    match request(socket, &HelloMessage::new(features)).await {
+        Ok(HelloResponse::Ok(protocol)) => Ok(protocol),
+        Ok(err) => Err(err.err_msg()),
+        Err(err) => Err(format!("Handshake with the server failed: {:?}", err)),
+    }
+}
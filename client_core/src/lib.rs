@@ -0,0 +1,17 @@
+pub mod agent;
+pub mod client;
+pub mod handler;
+pub mod utils;
+
+pub use agent::{run_agent, AgentConfig, BridgeAgent};
+pub use client::{Client, ClientState};
+
+// Re-exported so that `add_handler!` can be used without depending on these crates directly
+#[doc(hidden)]
+pub use common;
+#[doc(hidden)]
+pub use futures_util;
+#[doc(hidden)]
+pub use rust_socketio;
+#[doc(hidden)]
+pub use serde_json;
@@ -1,15 +1,27 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::card::Suit;
 use std::{cmp::Ordering, fmt};
 
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(
+    Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug,
+)]
 pub enum BidType {
     Trump(Suit),
     NoTrump,
 }
 
 impl BidType {
+    /// All strains in ascending order
+    pub const ALL: [BidType; 5] = [
+        BidType::Trump(Suit::Clubs),
+        BidType::Trump(Suit::Diamonds),
+        BidType::Trump(Suit::Hearts),
+        BidType::Trump(Suit::Spades),
+        BidType::NoTrump,
+    ];
+
     pub fn to_str(&self) -> &str {
         match self {
             Self::Trump(suit) => suit.to_str(),
@@ -18,7 +30,7 @@ impl BidType {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Bid {
     Pass,
     Play(u8, BidType),
@@ -0,0 +1,136 @@
+use std::ops::RangeInclusive;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Bid, BidType, Suit};
+
+/// What a call shows, as written on the convention card.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, Debug)]
+pub struct CallMeaning {
+    pub bid: Bid,
+    pub hcp: Option<RangeInclusive<usize>>,
+    pub description: String,
+    /// Artificial or unusual calls have to be alerted to the opponents
+    pub alert: bool,
+}
+
+impl CallMeaning {
+    pub fn new(
+        bid: Bid,
+        hcp: Option<RangeInclusive<usize>>,
+        description: &str,
+        alert: bool,
+    ) -> Self {
+        CallMeaning {
+            bid,
+            hcp,
+            description: description.into(),
+            alert,
+        }
+    }
+}
+
+/// Response to partner's opening bid, after the right-hand opponent passed.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, Debug)]
+pub struct ResponseMeaning {
+    pub opening: Bid,
+    pub meaning: CallMeaning,
+}
+
+/// Meaning of a call derived from the convention card of bidder's partnership.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, Debug)]
+pub struct BidAnnotation {
+    pub meaning: String,
+    pub alert: bool,
+}
+
+/// Machine-readable description of the bidding system played by a partnership.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, Debug)]
+pub struct ConventionCard {
+    pub name: String,
+    pub openings: Vec<CallMeaning>,
+    pub responses: Vec<ResponseMeaning>,
+}
+
+impl ConventionCard {
+    /// Natural system with 5-card majors, 15-17 1NT, strong 2C, weak twos, Stayman and transfers.
+    pub fn standard() -> Self {
+        let bid = |number, typ| Bid::Play(number, typ);
+        let clubs = BidType::Trump(Suit::Clubs);
+        let diamonds = BidType::Trump(Suit::Diamonds);
+        let hearts = BidType::Trump(Suit::Hearts);
+        let spades = BidType::Trump(Suit::Spades);
+        let no_trump = BidType::NoTrump;
+
+        let openings = vec![
+            CallMeaning::new(bid(1, clubs), Some(12..=21), "3+ clubs", false),
+            CallMeaning::new(bid(1, diamonds), Some(12..=21), "3+ diamonds", false),
+            CallMeaning::new(bid(1, hearts), Some(12..=21), "5+ hearts", false),
+            CallMeaning::new(bid(1, spades), Some(12..=21), "5+ spades", false),
+            CallMeaning::new(bid(1, no_trump), Some(15..=17), "balanced", false),
+            CallMeaning::new(bid(2, clubs), Some(22..=37), "strong, artificial", true),
+            CallMeaning::new(bid(2, diamonds), Some(5..=11), "weak, 6 diamonds", false),
+            CallMeaning::new(bid(2, hearts), Some(5..=11), "weak, 6 hearts", false),
+            CallMeaning::new(bid(2, spades), Some(5..=11), "weak, 6 spades", false),
+            CallMeaning::new(bid(2, no_trump), Some(20..=21), "balanced", false),
+        ];
+
+        let response = |opening, meaning| ResponseMeaning { opening, meaning };
+        let responses = vec![
+            response(
+                bid(1, no_trump),
+                CallMeaning::new(
+                    bid(2, clubs),
+                    None,
+                    "Stayman, 8+ HCP, asks for a major",
+                    true,
+                ),
+            ),
+            response(
+                bid(1, no_trump),
+                CallMeaning::new(bid(2, diamonds), None, "transfer, 5+ hearts", true),
+            ),
+            response(
+                bid(1, no_trump),
+                CallMeaning::new(bid(2, hearts), None, "transfer, 5+ spades", true),
+            ),
+            response(
+                bid(2, clubs),
+                CallMeaning::new(bid(2, diamonds), None, "waiting, artificial", true),
+            ),
+        ];
+
+        ConventionCard {
+            name: "Standard".into(),
+            openings,
+            responses,
+        }
+    }
+
+    /// Explains `bid` made after calls of `auction`, which starts with the dealer's call.
+    /// Only openings and responses to partner's opening over a pass are described.
+    pub fn annotate(&self, auction: &[Bid], bid: Bid) -> Option<BidAnnotation> {
+        let opening_index = auction.iter().position(|call| *call != Bid::Pass);
+
+        let meaning = match opening_index {
+            None => self.openings.iter().find(|meaning| meaning.bid == bid),
+            // Partner opened two calls ago and right-hand opponent passed
+            Some(index) if index + 2 == auction.len() && auction[index + 1] == Bid::Pass => self
+                .responses
+                .iter()
+                .find(|response| response.opening == auction[index] && response.meaning.bid == bid)
+                .map(|response| &response.meaning),
+            Some(_) => None,
+        }?;
+
+        let description = match &meaning.hcp {
+            Some(hcp) => format!("{}-{} HCP, {}", hcp.start(), hcp.end(), meaning.description),
+            None => meaning.description.clone(),
+        };
+        Some(BidAnnotation {
+            meaning: description,
+            alert: meaning.alert,
+        })
+    }
+}
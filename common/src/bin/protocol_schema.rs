@@ -0,0 +1,15 @@
+//! Writes the JSON Schema of the protocol to `common/protocol.schema.json`, or to the path given as argument.
+//! Run it after changing a message, `tests/schema.rs` fails until the checked-in schema is updated.
+
+use std::{env, fs};
+
+use common::schema::protocol_schema_string;
+
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/protocol.schema.json").into());
+    fs::write(&path, protocol_schema_string())
+        .unwrap_or_else(|err| panic!("Can't write schema to {}: {}", path, err));
+    println!("Protocol schema written to {}", path);
+}
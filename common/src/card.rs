@@ -1,9 +1,12 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::bid::BidType;
 use std::{cmp::Ordering, fmt::Display, str::FromStr};
 
-#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Ord, Debug)]
+#[derive(
+    Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, PartialOrd, Eq, Ord, Debug,
+)]
 pub enum Rank {
     Two = 2,
     Three = 3,
@@ -86,7 +89,9 @@ impl FromStr for Rank {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(
+    Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord, Debug,
+)]
 pub enum Suit {
     Clubs,
     Diamonds,
@@ -118,7 +123,7 @@ impl Suit {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug)]
 pub struct Card {
     pub rank: Rank,
     pub suit: Suit,
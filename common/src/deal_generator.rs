@@ -0,0 +1,172 @@
+use std::ops::RangeInclusive;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::card::{Card, Rank, Suit};
+use crate::hand::{high_card_points, is_balanced, is_semi_balanced, shape_pattern, suit_lengths};
+
+/// Number of random deals tried before the constraints are considered unsatisfiable.
+pub const MAX_ATTEMPTS: usize = 100_000;
+
+/// High card points of all four aces, kings and queens and one jack
+const MAX_HAND_HCP: usize = 37;
+/// High card points of the whole deck
+const DECK_HCP: usize = 40;
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Shape {
+    Balanced,
+    SemiBalanced,
+    /// Neither balanced nor semi-balanced
+    Unbalanced,
+    /// Suit lengths sorted from the longest, e.g. `[4, 4, 3, 2]`
+    Pattern([usize; 4]),
+}
+
+impl Shape {
+    pub fn matches(&self, cards: &[Card]) -> bool {
+        match self {
+            Shape::Balanced => is_balanced(cards),
+            Shape::SemiBalanced => is_semi_balanced(cards),
+            Shape::Unbalanced => !is_semi_balanced(cards),
+            Shape::Pattern(pattern) => shape_pattern(cards) == *pattern,
+        }
+    }
+}
+
+/// Requirements for a hand of one seat. Fields left as `None` accept any hand.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, Debug, Default)]
+pub struct SeatConstraints {
+    pub hcp: Option<RangeInclusive<usize>>,
+    /// Indexed in the order of `Suit` (clubs first)
+    pub suit_lengths: [Option<RangeInclusive<usize>>; 4],
+    pub shape: Option<Shape>,
+}
+
+impl SeatConstraints {
+    pub fn matches(&self, cards: &[Card]) -> bool {
+        if let Some(hcp) = &self.hcp {
+            if !hcp.contains(&high_card_points(cards)) {
+                return false;
+            }
+        }
+
+        let lengths = suit_lengths(cards);
+        let lengths_match = self
+            .suit_lengths
+            .iter()
+            .zip(lengths)
+            .all(|(range, length)| range.as_ref().is_none_or(|range| range.contains(&length)));
+
+        lengths_match && self.shape.is_none_or(|shape| shape.matches(cards))
+    }
+
+    /// Bounds of high card points of a matching hand
+    fn hcp_bounds(&self) -> (usize, usize) {
+        self.hcp.as_ref().map_or((0, MAX_HAND_HCP), |hcp| {
+            (*hcp.start(), (*hcp.end()).min(MAX_HAND_HCP))
+        })
+    }
+
+    /// Bounds of the length of a suit in a matching hand, narrowed by the shape pattern if there is one
+    fn length_bounds(&self, suit: usize) -> (usize, usize) {
+        let (min, max) = self.suit_lengths[suit]
+            .as_ref()
+            .map_or((0, 13), |length| (*length.start(), (*length.end()).min(13)));
+        match self.shape {
+            Some(Shape::Pattern(pattern)) => (min.max(pattern[3]), max.min(pattern[0])),
+            _ => (min, max),
+        }
+    }
+
+    fn is_possible(&self) -> bool {
+        let (min_hcp, max_hcp) = self.hcp_bounds();
+        let lengths: Vec<_> = (0..4).map(|suit| self.length_bounds(suit)).collect();
+        let pattern_possible = match self.shape {
+            Some(Shape::Pattern(pattern)) => {
+                pattern.iter().sum::<usize>() == 13 && pattern.is_sorted_by(|a, b| a >= b)
+            }
+            _ => true,
+        };
+
+        min_hcp <= max_hcp
+            && lengths.iter().all(|(min, max)| min <= max)
+            && lengths.iter().map(|(min, _)| min).sum::<usize>() <= 13
+            && lengths.iter().map(|(_, max)| max).sum::<usize>() >= 13
+            && pattern_possible
+    }
+}
+
+/// Requirements for the whole deal, indexed by `Player`.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, Debug, Default)]
+pub struct DealConstraints {
+    pub seats: [SeatConstraints; 4],
+}
+
+impl DealConstraints {
+    pub fn matches(&self, hands: &[Vec<Card>; 4]) -> bool {
+        self.seats
+            .iter()
+            .zip(hands)
+            .all(|(seat, hand)| seat.matches(hand))
+    }
+
+    /// Whether a deal matching the constraints can exist, judging by the bounds of high card points
+    /// and suit lengths of every hand, which have to add up to those of the whole deck.
+    /// It's cheap, so it's checked before trying to generate a deal.
+    pub fn is_possible(&self) -> bool {
+        let hcp: Vec<_> = self.seats.iter().map(SeatConstraints::hcp_bounds).collect();
+        let hcp_possible = hcp.iter().map(|(min, _)| min).sum::<usize>() <= DECK_HCP
+            && hcp.iter().map(|(_, max)| max).sum::<usize>() >= DECK_HCP;
+
+        let suits_possible = (0..4).all(|suit| {
+            let lengths: Vec<_> = self
+                .seats
+                .iter()
+                .map(|seat| seat.length_bounds(suit))
+                .collect();
+            lengths.iter().map(|(min, _)| min).sum::<usize>() <= 13
+                && lengths.iter().map(|(_, max)| max).sum::<usize>() >= 13
+        });
+
+        self.seats.iter().all(SeatConstraints::is_possible) && hcp_possible && suits_possible
+    }
+
+    /// Deals random hands until all constraints are met.
+    /// Returns `None` if the constraints aren't possible, or no matching deal was found in `MAX_ATTEMPTS` tries.
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Option<[Vec<Card>; 4]> {
+        if !self.is_possible() {
+            return None;
+        }
+        (0..MAX_ATTEMPTS)
+            .map(|_| random_deal(rng))
+            .find(|hands| self.matches(hands))
+    }
+
+    /// Same as `generate`, but always produces the same deal for the same seed.
+    pub fn generate_seeded(&self, seed: u64) -> Option<[Vec<Card>; 4]> {
+        self.generate(&mut StdRng::seed_from_u64(seed))
+    }
+}
+
+/// Shuffles the deck and splits it into four hands of 13 cards.
+pub fn random_deal<R: Rng>(rng: &mut R) -> [Vec<Card>; 4] {
+    let mut deck: Vec<Card> = (2..=14)
+        .filter_map(Rank::from_u8)
+        .flat_map(|rank| {
+            [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades]
+                .iter()
+                .map(move |&suit| Card::new(rank, suit))
+        })
+        .collect();
+
+    deck.shuffle(rng);
+
+    deck.chunks(13)
+        .map(|chunk| chunk.to_vec())
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap() // There's always a way to split 52 cards into 4*13
+}
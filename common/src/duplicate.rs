@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Lower bounds of score differences for 1, 2, ..., 24 IMPs
+const IMP_THRESHOLDS: [usize; 24] = [
+    20, 50, 90, 130, 170, 220, 270, 320, 370, 430, 500, 600, 750, 900, 1100, 1300, 1500, 1750,
+    2000, 2250, 2500, 3000, 3500, 4000,
+];
+
+/// Converts the difference of scores on a board into International Match Points.
+pub fn imps(difference: isize) -> isize {
+    let imps = IMP_THRESHOLDS
+        .iter()
+        .take_while(|threshold| difference.unsigned_abs() >= **threshold)
+        .count() as isize;
+    imps * difference.signum()
+}
+
+/// Vulnerability of every player on a board, numbered from 1, following the standard 16-board cycle.
+pub fn board_vulnerability(board_no: usize) -> [bool; 4] {
+    // 0 - none, 1 - North-South, 2 - East-West, 3 - both
+    const SCHEDULE: [u8; 16] = [0, 1, 2, 3, 1, 2, 3, 0, 2, 3, 0, 1, 3, 0, 1, 2];
+    let schedule = SCHEDULE[(board_no.max(1) - 1) % 16];
+    let north_south = schedule & 1 != 0;
+    let east_west = schedule & 2 != 0;
+    [north_south, east_west, north_south, east_west]
+}
+
+/// Scores of a board played at both tables of a team match, from the point of view of North-South.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BoardResult {
+    pub board_no: usize,
+    pub open: Option<isize>,
+    pub closed: Option<isize>,
+}
+
+impl BoardResult {
+    /// IMPs won by the team sitting North-South at the open table, once both tables finished the board
+    pub fn imps(&self) -> Option<isize> {
+        Some(imps(self.open? - self.closed?))
+    }
+}
@@ -1,11 +1,13 @@
 use crate::bid::Bid;
-use crate::card::{Card, Rank, Suit};
+use crate::card::{Card, Suit};
+use crate::deal_generator::random_deal;
 use crate::player::Player;
+use crate::record::DealRecord;
 use crate::BidType;
-use rand::prelude::SliceRandom;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum GameState {
     WaitingForPlayers,
     Auction,
@@ -13,7 +15,7 @@ pub enum GameState {
     Finished,
 }
 
-#[derive(Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum GameValue {
     Regular,
     Doubled,
@@ -98,7 +100,15 @@ pub enum TrickStatus {
     Error(TrickError),
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Debug)]
+/// Hands as they were dealt, together with the conditions the deal is played in.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Eq, PartialEq, Debug)]
+pub struct Deal {
+    pub dealer: Player,
+    pub hands: [Vec<Card>; 4],
+    pub vulnerable: [bool; 4],
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Eq, PartialEq, Debug)]
 pub struct GameResult {
     pub bidded: Bid,
     pub won_tricks: usize,
@@ -110,7 +120,10 @@ pub struct Game {
     pub state: GameState,
     pub max_bid: Bid,
     pub game_value: GameValue,
+    /// Player of the highest bid, the declarer once the auction is finished
     pub max_bidder: Player,
+    /// Player of the last call other than pass, the auction ends when the other three pass
+    pub last_caller: Player,
     pub first_bidder: Player,
     pub current_player: Player,
     pub player_cards: [Vec<Card>; 4],
@@ -120,6 +133,11 @@ pub struct Game {
     pub vulnerable: [bool; 4],
     pub trick_no: u8,
     pub current_trick: Vec<Card>,
+    pub deal: Deal,
+    /// Calls made in the current deal, starting with the dealer's
+    pub auction: Vec<Bid>,
+    /// Cards played in the current deal, in order of play
+    pub play: Vec<(Player, Card)>,
 }
 
 impl Default for Game {
@@ -136,6 +154,7 @@ impl Game {
             max_bid: Bid::Pass,
             game_value: GameValue::Regular,
             max_bidder: Player::North,
+            last_caller: Player::North,
             first_bidder: Player::North,
             player_cards: Default::default(),
             collected_cards: Default::default(),
@@ -144,51 +163,122 @@ impl Game {
             vulnerable: Default::default(),
             trick_no: 0,
             current_trick: Vec::new(),
+            deal: Deal {
+                dealer: Player::North,
+                hands: Default::default(),
+                vulnerable: Default::default(),
+            },
+            auction: Vec::new(),
+            play: Vec::new(),
         }
     }
 
     pub fn start(&mut self) {
+        self.reset_deal();
+        self.deal_cards(random_deal(&mut rand::thread_rng()));
+    }
+
+    /// Starts a new deal with given hands, instead of dealing random ones.
+    pub fn start_with_cards(&mut self, hands: [Vec<Card>; 4]) {
+        self.reset_deal();
+        self.deal_cards(hands);
+    }
+
+    fn reset_deal(&mut self) {
         self.max_bidder = self.first_bidder;
+        self.last_caller = self.first_bidder;
         self.current_player = self.first_bidder;
         self.trick_no = 0;
         self.state = GameState::Auction;
         self.game_value = GameValue::Regular;
         self.max_bid = Bid::Pass;
         self.first_bidder = self.first_bidder.next();
+        self.auction.clear();
+        self.play.clear();
+        self.collected_cards = Default::default();
+        self.current_trick.clear();
+    }
 
-        let mut deck: Vec<Card> = (2..=14)
-            .filter_map(Rank::from_u8)
-            .flat_map(|rank| {
-                [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades]
-                    .iter()
-                    .map(move |&suit| Card::new(rank, suit))
-            })
-            .collect();
-
-        let mut rng = rand::thread_rng();
-        deck.shuffle(&mut rng);
+    fn deal_cards(&mut self, hands: [Vec<Card>; 4]) {
+        self.player_cards = hands;
 
-        self.player_cards = deck
-            .chunks(13)
-            .map(|chunk| chunk.to_vec())
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap(); // There's always a way to split 52 cards into 4*13
+        self.deal = Deal {
+            dealer: self.current_player,
+            hands: self.player_cards.clone(),
+            vulnerable: self.vulnerable,
+        };
 
         self.state = GameState::Auction;
     }
 
-    pub fn place_bid(&mut self, player: &Player, bid: Bid) -> BidStatus {
+    /// Checks whether given player can place the bid now, without changing the game state.
+    pub fn check_bid(&self, player: &Player, bid: Bid) -> Result<(), BidError> {
         if self.state != GameState::Auction {
-            return BidStatus::Error(BidError::GameStateMismatch);
+            return Err(BidError::GameStateMismatch);
         }
         if self.current_player != *player {
-            return BidStatus::Error(BidError::PlayerOutOfTurn);
+            return Err(BidError::PlayerOutOfTurn);
+        }
+        match bid {
+            Bid::Pass => Ok(()),
+            Bid::Play(_, _) => {
+                if bid > self.max_bid {
+                    Ok(())
+                } else {
+                    Err(BidError::WrongBid)
+                }
+            }
+            Bid::Double => {
+                if self.max_bid == Bid::Pass
+                    || self.game_value != GameValue::Regular
+                    || !player.is_opponent(self.max_bidder)
+                {
+                    Err(BidError::CantDouble)
+                } else {
+                    Ok(())
+                }
+            }
+            Bid::Redouble => {
+                if self.max_bid == Bid::Pass
+                    || player.is_opponent(self.max_bidder)
+                    || self.game_value != GameValue::Doubled
+                {
+                    Err(BidError::CantRedouble)
+                } else {
+                    Ok(())
+                }
+            }
         }
+    }
+
+    /// Returns all bids that given player can place now, in ascending order.
+    /// The list is empty if it's not the player's turn to bid.
+    pub fn legal_bids(&self, player: &Player) -> Vec<Bid> {
+        let bid_types = [
+            BidType::Trump(Suit::Clubs),
+            BidType::Trump(Suit::Diamonds),
+            BidType::Trump(Suit::Hearts),
+            BidType::Trump(Suit::Spades),
+            BidType::NoTrump,
+        ];
+        let play_bids = (1..=7).flat_map(move |number| bid_types.map(|typ| Bid::Play(number, typ)));
+
+        std::iter::once(Bid::Pass)
+            .chain(play_bids)
+            .chain([Bid::Double, Bid::Redouble])
+            .filter(|bid| self.check_bid(player, *bid).is_ok())
+            .collect()
+    }
+
+    pub fn place_bid(&mut self, player: &Player, bid: Bid) -> BidStatus {
+        if let Err(bid_error) = self.check_bid(player, bid) {
+            return BidStatus::Error(bid_error);
+        }
+        self.auction.push(bid);
         match bid {
             Bid::Pass => {
                 self.current_player = self.current_player.next();
-                if self.current_player == self.max_bidder {
+                if self.current_player == self.last_caller {
                     match self.max_bid {
                         Bid::Pass => {
                             self.state = GameState::Finished;
@@ -196,8 +286,8 @@ impl Game {
                         }
                         _ => {
                             self.state = GameState::Tricking;
-                            // Calling next again, as the first player is next of max_bidder
-                            self.current_player = self.current_player.next();
+                            // The player on the left of the declarer leads
+                            self.current_player = self.max_bidder.next();
                             return BidStatus::Tricking;
                         }
                     }
@@ -205,38 +295,21 @@ impl Game {
                 BidStatus::Auction
             }
             Bid::Play(_, _) => {
-                if bid > self.max_bid {
-                    self.max_bid = bid;
-                    self.max_bidder = *player;
-                    self.current_player = self.current_player.next();
-                    BidStatus::Auction
-                } else {
-                    BidStatus::Error(BidError::WrongBid)
-                }
+                self.max_bid = bid;
+                self.max_bidder = *player;
+                self.last_caller = *player;
+                self.current_player = self.current_player.next();
+                BidStatus::Auction
             }
             Bid::Double => {
-                if self.max_bid == Bid::Pass
-                    || self.game_value != GameValue::Regular
-                    || !player.is_opponent(self.max_bidder)
-                {
-                    return BidStatus::Error(BidError::CantDouble);
-                }
-
-                self.max_bidder = self.current_player;
+                self.last_caller = self.current_player;
                 self.current_player = self.current_player.next();
                 self.game_value = GameValue::Doubled;
 
                 BidStatus::Auction
             }
             Bid::Redouble => {
-                if self.max_bid == Bid::Pass
-                    || !player.is_opponent(self.max_bidder)
-                    || self.game_value != GameValue::Doubled
-                {
-                    return BidStatus::Error(BidError::CantRedouble);
-                }
-
-                self.max_bidder = self.current_player;
+                self.last_caller = self.current_player;
                 self.current_player = self.current_player.next();
                 self.game_value = GameValue::Redoubled;
 
@@ -245,29 +318,49 @@ impl Game {
         }
     }
 
-    pub fn trick(&mut self, player: &Player, card: &Card) -> TrickStatus {
+    /// Checks whether given player can play the card now, without changing the game state.
+    pub fn check_card(&self, player: &Player, card: &Card) -> Result<(), TrickError> {
         if self.state != GameState::Tricking {
-            return TrickStatus::Error(TrickError::GameStateMismatch);
+            return Err(TrickError::GameStateMismatch);
         }
         if self.current_player != *player {
-            return TrickStatus::Error(TrickError::PlayerOutOfTurn);
+            return Err(TrickError::PlayerOutOfTurn);
         }
         if !self.has_card(player, card) {
-            return TrickStatus::Error(TrickError::CardNotFound);
+            return Err(TrickError::CardNotFound);
         }
-        let player_usize = player.to_usize();
 
         // Player played the wrong suit, while right suit cards in hand
         if !self.current_trick.is_empty()
             && card.suit != self.current_trick[0].suit
             && self.has_suit(player, &self.current_trick[0].suit)
         {
-            return TrickStatus::Error(TrickError::WrongCardSuit);
+            return Err(TrickError::WrongCardSuit);
         }
 
+        Ok(())
+    }
+
+    /// Returns cards that given player can play now.
+    /// The list is empty if it's not the player's turn to play.
+    pub fn legal_cards(&self, player: &Player) -> Vec<Card> {
+        self.get_cards(player)
+            .iter()
+            .filter(|card| self.check_card(player, card).is_ok())
+            .copied()
+            .collect()
+    }
+
+    pub fn trick(&mut self, player: &Player, card: &Card) -> TrickStatus {
+        if let Err(trick_error) = self.check_card(player, card) {
+            return TrickStatus::Error(trick_error);
+        }
+        let player_usize = player.to_usize();
+
         // Either the trick is empty, the suit is right,
         // or the player has no more cards of this suit
         self.current_trick.push(*card);
+        self.play.push((*player, *card));
         self.player_cards[player_usize].retain(|&c| c != *card);
         self.current_player = self.current_player.next();
 
@@ -316,69 +409,19 @@ impl Game {
             eprintln!("self.max_bid should always be Bid::Play when distributing points");
             return false;
         };
-        let tricks_declared = tricks_declared_value as usize + 6;
 
         let is_vulnerable = self.vulnerable[bidder_usize];
-        let contract_succeeded = tricks_earned >= tricks_declared;
+        let score = contract_score(
+            tricks_declared_value,
+            bid_type,
+            self.game_value,
+            tricks_earned,
+            is_vulnerable,
+        );
+        let contract_succeeded = score > 0;
 
         if contract_succeeded {
-            // Calculate contract points
-            let base_points = match bid_type {
-                BidType::Trump(Suit::Clubs) | BidType::Trump(Suit::Diamonds) => {
-                    20 * (tricks_declared - 6)
-                }
-                BidType::Trump(Suit::Hearts) | BidType::Trump(Suit::Spades) => {
-                    30 * (tricks_declared - 6)
-                }
-                BidType::NoTrump => {
-                    40 + 30 * (tricks_declared - 7) // First trick 40, subsequent tricks 30
-                }
-            };
-
-            let doubled_bonus = match self.game_value {
-                GameValue::Doubled => 50,
-                GameValue::Redoubled => 100,
-                _ => 0,
-            };
-
-            self.points[bidder_usize] += base_points;
-            self.points[bidder_usize] += doubled_bonus;
-
-            // Slam bonuses
-            if tricks_declared == 12 {
-                self.points[bidder_usize] += if is_vulnerable { 750 } else { 500 };
-            } else if tricks_declared == 13 {
-                self.points[bidder_usize] += if is_vulnerable { 1500 } else { 1000 };
-            }
-
-            // Overtrick points
-            let overtricks = tricks_earned - tricks_declared;
-            let overtrick_points = match self.game_value {
-                GameValue::Doubled => {
-                    if is_vulnerable {
-                        overtricks * 200
-                    } else {
-                        overtricks * 100
-                    }
-                }
-                GameValue::Redoubled => {
-                    if is_vulnerable {
-                        overtricks * 400
-                    } else {
-                        overtricks * 200
-                    }
-                }
-                _ => {
-                    overtricks
-                        * match bid_type {
-                            BidType::Trump(Suit::Clubs) | BidType::Trump(Suit::Diamonds) => 20,
-                            BidType::Trump(Suit::Hearts) | BidType::Trump(Suit::Spades) => 30,
-                            BidType::NoTrump => 30, // Overtricks in NoTrump are 30 points each
-                        }
-                }
-            };
-
-            self.points[bidder_usize] += overtrick_points;
+            self.points[bidder_usize] += score.unsigned_abs();
 
             // Mark the pair as vulnerable if they won the game
             self.vulnerable[bidder_usize] = true;
@@ -392,49 +435,13 @@ impl Game {
                 self.state = GameState::Finished;
             }
         } else {
-            // Penalty points for undertricks
-            let undertricks = tricks_declared - tricks_earned;
-            let penalty_points = match self.game_value {
-                GameValue::Regular => {
-                    if is_vulnerable {
-                        // 100 per undertrick
-                        undertricks * 100
-                    } else {
-                        // 50 per undertrick
-                        undertricks * 50
-                    }
-                }
-                GameValue::Doubled => {
-                    if is_vulnerable {
-                        // 200 for the first undertrick, 300 for each subsequent
-                        200 + (undertricks - 1) * 300
-                    } else {
-                        // 100 for the first undertrick, 200 for 2nd and 3rd undertrick, 300 for subsequent
-                        100 + ((undertricks - 1 > 0) as usize * 200)
-                            + ((undertricks - 2 > 0) as usize * 200)
-                            + ((undertricks - 3 > 0) as usize * (undertricks - 3) * 300)
-                    }
-                }
-                GameValue::Redoubled => {
-                    if is_vulnerable {
-                        // 400 for the first undertrick, 600 for each subsequent
-                        400 + (undertricks - 1) * 600
-                    } else {
-                        // 200 for the first undertrick, 400 for 2nd and 3rd undertrick, 600 for subsequent
-                        200 + ((undertricks - 1 > 0) as usize * 400)
-                            + ((undertricks - 2 > 0) as usize * 400)
-                            + ((undertricks - 3 > 0) as usize * (undertricks - 3) * 600)
-                    }
-                }
-            };
-
             let opponents = [
                 self.max_bidder.next().to_usize(),
                 self.max_bidder.skip(3).to_usize(),
             ];
 
             for &opponent in &opponents {
-                self.points[opponent] += penalty_points;
+                self.points[opponent] += score.unsigned_abs();
             }
         }
         // Make sure the partner has the same amounts of points as bidder, as the game is played in pairs.
@@ -464,6 +471,38 @@ impl Game {
         }
     }
 
+    /// Record of the current deal, complete once the deal is finished
+    pub fn record(&self) -> DealRecord {
+        DealRecord {
+            deal: self.deal.clone(),
+            auction: self.auction.clone(),
+            play: self.play.clone(),
+        }
+    }
+
+    /// Score of the finished deal from the point of view of North-South, 0 if it was passed out.
+    pub fn deal_score(&self) -> isize {
+        let Bid::Play(level, bid_type) = self.max_bid else {
+            return 0;
+        };
+        let bidder = self.max_bidder.to_usize();
+        let partner = self.max_bidder.get_partner().to_usize();
+        let tricks = (self.collected_cards[bidder].len() + self.collected_cards[partner].len()) / 4;
+
+        let score = contract_score(
+            level,
+            bid_type,
+            self.game_value,
+            tricks,
+            self.deal.vulnerable[bidder],
+        );
+        if matches!(self.max_bidder, Player::North | Player::South) {
+            score
+        } else {
+            -score
+        }
+    }
+
     pub fn get_dummy_cards(&self) -> Option<&Vec<Card>> {
         if self.state != GameState::Tricking {
             return None;
@@ -530,3 +569,116 @@ impl Game {
             .unwrap()
     }
 }
+
+/// Points of the declarer's side for a contract of `level` in `bid_type`, in which the side took `tricks`.
+/// When the contract went down, the result is negative and the opponents score the penalty.
+pub fn contract_score(
+    level: u8,
+    bid_type: BidType,
+    game_value: GameValue,
+    tricks: usize,
+    vulnerable: bool,
+) -> isize {
+    let tricks_declared = level as usize + 6;
+
+    if tricks >= tricks_declared {
+        // Calculate contract points
+        let base_points = match bid_type {
+            BidType::Trump(Suit::Clubs) | BidType::Trump(Suit::Diamonds) => {
+                20 * (tricks_declared - 6)
+            }
+            BidType::Trump(Suit::Hearts) | BidType::Trump(Suit::Spades) => {
+                30 * (tricks_declared - 6)
+            }
+            BidType::NoTrump => {
+                40 + 30 * (tricks_declared - 7) // First trick 40, subsequent tricks 30
+            }
+        };
+
+        let doubled_bonus = match game_value {
+            GameValue::Doubled => 50,
+            GameValue::Redoubled => 100,
+            _ => 0,
+        };
+
+        // Slam bonuses
+        let slam_bonus = if tricks_declared == 12 {
+            if vulnerable {
+                750
+            } else {
+                500
+            }
+        } else if tricks_declared == 13 {
+            if vulnerable {
+                1500
+            } else {
+                1000
+            }
+        } else {
+            0
+        };
+
+        // Overtrick points
+        let overtricks = tricks - tricks_declared;
+        let overtrick_points = match game_value {
+            GameValue::Doubled => {
+                if vulnerable {
+                    overtricks * 200
+                } else {
+                    overtricks * 100
+                }
+            }
+            GameValue::Redoubled => {
+                if vulnerable {
+                    overtricks * 400
+                } else {
+                    overtricks * 200
+                }
+            }
+            _ => {
+                overtricks
+                    * match bid_type {
+                        BidType::Trump(Suit::Clubs) | BidType::Trump(Suit::Diamonds) => 20,
+                        BidType::Trump(Suit::Hearts) | BidType::Trump(Suit::Spades) => 30,
+                        BidType::NoTrump => 30, // Overtricks in NoTrump are 30 points each
+                    }
+            }
+        };
+
+        (base_points + doubled_bonus + slam_bonus + overtrick_points) as isize
+    } else {
+        // Penalty points for undertricks
+        let undertricks = tricks_declared - tricks;
+        let penalty_points = match game_value {
+            GameValue::Regular => {
+                if vulnerable {
+                    // 100 per undertrick
+                    undertricks * 100
+                } else {
+                    // 50 per undertrick
+                    undertricks * 50
+                }
+            }
+            GameValue::Doubled => {
+                if vulnerable {
+                    // 200 for the first undertrick, 300 for each subsequent
+                    200 + (undertricks - 1) * 300
+                } else {
+                    // 100 for the first undertrick, 200 for 2nd and 3rd undertrick, 300 for subsequent
+                    100 + (undertricks - 1).min(2) * 200 + undertricks.saturating_sub(3) * 300
+                }
+            }
+            GameValue::Redoubled => {
+                if vulnerable {
+                    // 400 for the first undertrick, 600 for each subsequent
+                    400 + (undertricks - 1) * 600
+                } else {
+                    // 200 for the first undertrick, 400 for 2nd and 3rd undertrick, 600 for subsequent
+                    200 + (undertricks - 1).min(2) * 400 + undertricks.saturating_sub(3) * 600
+                }
+            }
+        };
+
+        -(penalty_points as isize)
+    }
+}
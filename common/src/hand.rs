@@ -0,0 +1,96 @@
+use crate::card::{Card, Rank, Suit};
+
+/// Suits from the highest, the order in which shapes are written.
+const SUITS_DESCENDING: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+/// High card points: 4 for an ace, 3 for a king, 2 for a queen and 1 for a jack.
+pub fn high_card_points(cards: &[Card]) -> usize {
+    cards
+        .iter()
+        .map(|card| match card.rank {
+            Rank::Ace => 4,
+            Rank::King => 3,
+            Rank::Queen => 2,
+            Rank::Jack => 1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Number of cards held in each suit, indexed in the order of `Suit` (clubs first).
+pub fn suit_lengths(cards: &[Card]) -> [usize; 4] {
+    let mut lengths = [0; 4];
+    for card in cards {
+        lengths[card.suit as usize] += 1;
+    }
+    lengths
+}
+
+/// Suit lengths sorted from the longest, e.g. `[4, 4, 3, 2]`.
+pub fn shape_pattern(cards: &[Card]) -> [usize; 4] {
+    let mut pattern = suit_lengths(cards);
+    pattern.sort_unstable_by(|a, b| b.cmp(a));
+    pattern
+}
+
+/// Exact shape written from spades down to clubs, e.g. `5=3=3=2`.
+pub fn shape_str(cards: &[Card]) -> String {
+    let lengths = suit_lengths(cards);
+    SUITS_DESCENDING
+        .iter()
+        .map(|suit| lengths[*suit as usize].to_string())
+        .collect::<Vec<_>>()
+        .join("=")
+}
+
+/// 4-3-3-3, 4-4-3-2 and 5-3-3-2 hands.
+pub fn is_balanced(cards: &[Card]) -> bool {
+    matches!(
+        shape_pattern(cards),
+        [4, 3, 3, 3] | [4, 4, 3, 2] | [5, 3, 3, 2]
+    )
+}
+
+/// Balanced hands, together with 5-4-2-2 and 6-3-2-2.
+pub fn is_semi_balanced(cards: &[Card]) -> bool {
+    is_balanced(cards) || matches!(shape_pattern(cards), [5, 4, 2, 2] | [6, 3, 2, 2])
+}
+
+/// Losing trick count. Up to three top cards of every suit are considered;
+/// each of them is a loser unless the suit holds the matching honour (ace, king, queen).
+pub fn losing_trick_count(cards: &[Card]) -> usize {
+    let honours = [Rank::Ace, Rank::King, Rank::Queen];
+    SUITS_DESCENDING
+        .iter()
+        .map(|suit| {
+            let considered = cards
+                .iter()
+                .filter(|card| card.suit == *suit)
+                .count()
+                .min(3);
+            let held = honours[..considered]
+                .iter()
+                .filter(|rank| cards.contains(&Card::new(**rank, *suit)))
+                .count();
+            considered - held
+        })
+        .sum()
+}
+
+/// Defensive quick tricks: AK counts 2, AQ 1.5, A or KQ 1 and a guarded king 0.5.
+pub fn quick_tricks(cards: &[Card]) -> f32 {
+    SUITS_DESCENDING
+        .iter()
+        .map(|suit| {
+            let has = |rank| cards.contains(&Card::new(rank, *suit));
+            let length = cards.iter().filter(|card| card.suit == *suit).count();
+            match (has(Rank::Ace), has(Rank::King), has(Rank::Queen)) {
+                (true, true, _) => 2.0,
+                (true, false, true) => 1.5,
+                (true, false, false) | (false, true, true) => 1.0,
+                (false, true, false) if length >= 2 => 0.5,
+                _ => 0.0,
+            }
+        })
+        .sum()
+}
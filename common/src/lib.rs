@@ -1,15 +1,25 @@
 pub mod bid;
+pub mod bidding_system;
 pub mod card;
+pub mod deal_generator;
+pub mod duplicate;
 pub mod game;
+pub mod hand;
 pub mod message;
+pub mod par;
 pub mod player;
+pub mod protocol;
+pub mod record;
 pub mod room;
+pub mod schema;
+pub mod solver;
+pub mod tournament;
 pub mod user;
 
 pub use bid::{Bid, BidType};
 pub use card::{Card, Rank, Suit};
 pub use game::{
-    BidError, BidStatus, Game, GameResult, GameState, GameValue, TrickError, TrickState,
+    BidError, BidStatus, Deal, Game, GameResult, GameState, GameValue, TrickError, TrickState,
     TrickStatus,
 };
 pub use player::Player;
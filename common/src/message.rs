@@ -1,12 +1,37 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    protocol::Feature,
     room::{RoomId, RoomInfo},
     user::User,
 };
 
+/// Message of the protocol, see `protocol` for the compatibility rules
 pub trait MessageTrait {
     const MSG_TYPE: &'static str;
+    /// Version of the protocol since which the message exists.
+    /// Server doesn't send it to clients talking an older version.
+    const SINCE_VERSION: u32 = 1;
+    /// Optional feature the notification belongs to, server sends it only to clients that negotiated the feature
+    const FEATURE: Option<Feature> = None;
+
+    /// Returns the message as clients talking given older version of the protocol know it,
+    /// e.g. with a variant added later replaced by the closest older one.
+    /// Returns `None` if they can read the message as it is.
+    fn for_version(&self, _version: u32) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// Message sent by client that server answers with a message of type `Response`.
+/// If the client asks for a socket.io acknowledgement, the response is sent as the acknowledgement,
+/// so that it can be matched with its request. Otherwise it's sent as a standalone message.
+pub trait RequestTrait: MessageTrait {
+    type Response: MessageTrait;
 }
 
 pub trait GetErrorMessage {
@@ -16,11 +41,42 @@ pub trait GetErrorMessage {
 /// Messages sent from client to server
 pub mod client_message {
     use super::*;
-    use crate::{Bid, Card, Player};
+    use crate::{
+        bidding_system::ConventionCard, protocol::PROTOCOL_VERSION, room::SpectatorView,
+        tournament::Movement, Bid, Card, Player,
+    };
+
+    /// Message sent by client right after connecting, with the newest protocol version it talks
+    /// and the optional features it supports. Clients that don't send it are treated as talking `MIN_PROTOCOL_VERSION`.
+    /// Server answers with HelloResponse message
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct HelloMessage {
+        pub protocol_version: u32,
+        #[serde(default)]
+        pub features: Vec<Feature>,
+    }
+
+    impl HelloMessage {
+        /// Handshake of a client talking the protocol of this build
+        pub fn new(features: Vec<Feature>) -> Self {
+            Self {
+                protocol_version: PROTOCOL_VERSION,
+                features,
+            }
+        }
+    }
+
+    impl MessageTrait for HelloMessage {
+        const MSG_TYPE: &'static str = "hello";
+    }
+
+    impl RequestTrait for HelloMessage {
+        type Response = server_response::HelloResponse;
+    }
 
     /// Message sent by client when attempting to login
     /// Server answers with LoginResponse message
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct LoginMessage {
         pub user: User,
     }
@@ -29,18 +85,26 @@ pub mod client_message {
         const MSG_TYPE: &'static str = "login";
     }
 
+    impl RequestTrait for LoginMessage {
+        type Response = server_response::LoginResponse;
+    }
+
     /// Message sent by client when requesting list of public rooms
     /// Server answers with ListRoomsResponse message
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct ListRoomsMessage {}
 
     impl MessageTrait for ListRoomsMessage {
         const MSG_TYPE: &'static str = "list_rooms";
     }
 
+    impl RequestTrait for ListRoomsMessage {
+        type Response = server_response::ListRoomsResponse;
+    }
+
     /// Message sent by client when attempting to register a new room
     /// Server answers with RegisterRoomResponse message
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct RegisterRoomMessage {
         pub room_info: RoomInfo,
     }
@@ -49,10 +113,33 @@ pub mod client_message {
         const MSG_TYPE: &'static str = "register_room";
     }
 
+    impl RequestTrait for RegisterRoomMessage {
+        type Response = server_response::RegisterRoomResponse;
+    }
+
+    /// Message sent by client when attempting to register a team match,
+    /// played on the same boards in two linked rooms
+    /// Server answers with RegisterRoomResponse message
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct RegisterMatchMessage {
+        pub open_room: RoomInfo,
+        pub closed_room: RoomInfo,
+        pub boards: usize,
+    }
+
+    impl MessageTrait for RegisterMatchMessage {
+        const MSG_TYPE: &'static str = "register_match";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl RequestTrait for RegisterMatchMessage {
+        type Response = server_response::RegisterRoomResponse;
+    }
+
     /// Message sent by client when attempting to join a room
     /// Server answers with JoinRoomResponse message
     /// Server sends JoinRoomNotification to all users in the room
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct JoinRoomMessage {
         pub room_id: RoomId,
     }
@@ -61,28 +148,40 @@ pub mod client_message {
         const MSG_TYPE: &'static str = "join_room";
     }
 
+    impl RequestTrait for JoinRoomMessage {
+        type Response = server_response::JoinRoomResponse;
+    }
+
     /// Message sent by client when attempting to leave a room
     /// Server answers with LeaveRoomResponse message
     /// Server sends LeaveRoomNotification to all users in the room
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct LeaveRoomMessage {}
 
     impl MessageTrait for LeaveRoomMessage {
         const MSG_TYPE: &'static str = "leave_room";
     }
 
+    impl RequestTrait for LeaveRoomMessage {
+        type Response = server_response::LeaveRoomResponse;
+    }
+
     /// Message sent by client when requesting list of places in the room
     /// Server answers with ListPlacesResponse message
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct ListPlacesMessage {}
 
     impl MessageTrait for ListPlacesMessage {
         const MSG_TYPE: &'static str = "list_places";
     }
 
+    impl RequestTrait for ListPlacesMessage {
+        type Response = server_response::ListPlacesResponse;
+    }
+
     /// Message sent by client when selecting a place in the room
     /// Server answers with UserSelectedPositionMessage message
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct SelectPlaceMessage {
         pub position: Option<Player>,
     }
@@ -91,29 +190,44 @@ pub mod client_message {
         const MSG_TYPE: &'static str = "select_place";
     }
 
+    impl RequestTrait for SelectPlaceMessage {
+        type Response = server_response::SelectPlaceResponse;
+    }
+
     /// Message sent by client when requesting his list of cards
     /// Server answers with GetCardsResponse message
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct GetCardsMessage {}
 
     impl MessageTrait for GetCardsMessage {
         const MSG_TYPE: &'static str = "get_cards";
     }
 
+    impl RequestTrait for GetCardsMessage {
+        type Response = server_response::GetCardsResponse;
+    }
+
     /// Message sent by client when making a bid
     /// Server answers with MakeBidResponse message
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct MakeBidMessage {
         pub bid: Bid,
+        /// Explanation of the call, shown to the opponents only
+        #[serde(default)]
+        pub explanation: Option<String>,
     }
 
     impl MessageTrait for MakeBidMessage {
         const MSG_TYPE: &'static str = "make_bid";
     }
 
+    impl RequestTrait for MakeBidMessage {
+        type Response = server_response::MakeBidResponse;
+    }
+
     /// Message sent by client when making a trick
     /// Server answers with MakeTrickResponse message
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct MakeTrickMessage {
         pub card: Card,
     }
@@ -121,24 +235,176 @@ pub mod client_message {
     impl MessageTrait for MakeTrickMessage {
         const MSG_TYPE: &'static str = "make_trick";
     }
+
+    impl RequestTrait for MakeTrickMessage {
+        type Response = server_response::MakeTrickResponse;
+    }
+
+    /// Message sent by client when attaching a convention card to their partnership, `None` removes it
+    /// Server answers with SetConventionCardResponse message
+    /// Server sends ConventionCardNotification to all users in the room
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct SetConventionCardMessage {
+        pub card: Option<ConventionCard>,
+    }
+
+    impl MessageTrait for SetConventionCardMessage {
+        const MSG_TYPE: &'static str = "set_convention_card";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl RequestTrait for SetConventionCardMessage {
+        type Response = server_response::SetConventionCardResponse;
+    }
+
+    /// Message sent by a spectator to look at the hands of the players, if the room allows kibitzing
+    /// Server answers with KibitzResponse message
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct KibitzMessage {
+        pub view: SpectatorView,
+    }
+
+    impl MessageTrait for KibitzMessage {
+        const MSG_TYPE: &'static str = "kibitz";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl RequestTrait for KibitzMessage {
+        type Response = server_response::KibitzResponse;
+    }
+
+    /// Message sent by client to watch the delayed broadcast of a room, without joining it
+    /// Server answers with WatchBroadcastResponse message
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct WatchBroadcastMessage {
+        pub room_id: RoomId,
+    }
+
+    impl MessageTrait for WatchBroadcastMessage {
+        const MSG_TYPE: &'static str = "watch_broadcast";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl RequestTrait for WatchBroadcastMessage {
+        type Response = server_response::WatchBroadcastResponse;
+    }
+
+    /// Message sent by client when attempting to register a pairs tournament,
+    /// the sender becomes the director of the tournament
+    /// Server answers with RegisterTournamentResponse message
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct RegisterTournamentMessage {
+        pub tournament_id: String,
+        pub movement: Movement,
+        /// Usernames of both players of every pair
+        pub pairs: Vec<[String; 2]>,
+        pub boards_per_round: usize,
+    }
+
+    impl MessageTrait for RegisterTournamentMessage {
+        const MSG_TYPE: &'static str = "register_tournament";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl RequestTrait for RegisterTournamentMessage {
+        type Response = server_response::RegisterTournamentResponse;
+    }
+
+    /// Message sent by client to follow a tournament
+    /// Server answers with JoinTournamentResponse message
+    /// Server sends TournamentRoundNotification and TournamentStandingsNotification to the client afterwards
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct JoinTournamentMessage {
+        pub tournament_id: String,
+    }
+
+    impl MessageTrait for JoinTournamentMessage {
+        const MSG_TYPE: &'static str = "join_tournament";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl RequestTrait for JoinTournamentMessage {
+        type Response = server_response::JoinTournamentResponse;
+    }
+
+    /// Message sent by the director to start the next round of a tournament
+    /// Server answers with StartRoundResponse message
+    /// Server sends TournamentRoundNotification to all users following the tournament
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct StartRoundMessage {
+        pub tournament_id: String,
+    }
+
+    impl MessageTrait for StartRoundMessage {
+        const MSG_TYPE: &'static str = "start_round";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl RequestTrait for StartRoundMessage {
+        type Response = server_response::StartRoundResponse;
+    }
 }
 
 pub mod server_response {
     use super::*;
-    use crate::{Card, Player, TrickError, TrickStatus};
+    use crate::{protocol::Protocol, Card, Player, TrickError, TrickStatus};
+
+    /// Answer from server for HelloMessage
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub enum HelloResponse {
+        /// The protocol the server will talk to the client
+        Ok(Protocol),
+        /// The client is too old, the server talks only versions in `min_version..=max_version`
+        UnsupportedVersion { min_version: u32, max_version: u32 },
+    }
+
+    impl MessageTrait for HelloResponse {
+        const MSG_TYPE: &'static str = "hello_response";
+    }
+
+    impl GetErrorMessage for HelloResponse {
+        fn err_msg(&self) -> String {
+            match self {
+                HelloResponse::UnsupportedVersion {
+                    min_version,
+                    max_version,
+                } => format!(
+                    "Client is too old, server supports protocol versions {} to {}. Please update the client",
+                    min_version, max_version
+                ),
+                _ => "OK".into(),
+            }
+        }
+    }
 
     /// Answer from server for LoginMessage
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub enum LoginResponse {
         Ok,
         UsernameAlreadyExists,
         UserAlreadyLoggedIn,
         UsernameInvalidCharacters,
         UsernameInvalidLength,
+        /// Username is shorter than `min_length` or longer than `max_length`, as configured on the server.
+        /// Since version 2, older clients get `UsernameInvalidLength`.
+        UsernameLengthOutOfRange {
+            min_length: usize,
+            max_length: usize,
+        },
     }
 
     impl MessageTrait for LoginResponse {
         const MSG_TYPE: &'static str = "login_response";
+
+        fn for_version(&self, version: u32) -> Option<Self> {
+            match self {
+                _ if version >= 2 => None,
+                LoginResponse::UsernameLengthOutOfRange { .. } => {
+                    Some(LoginResponse::UsernameInvalidLength)
+                }
+                _ => None,
+            }
+        }
     }
 
     impl GetErrorMessage for LoginResponse {
@@ -149,9 +415,14 @@ pub mod server_response {
                 LoginResponse::UsernameInvalidCharacters => {
                     "Username contains invalid characters".into()
                 }
-                LoginResponse::UsernameInvalidLength => {
-                    "Username must be between 3 and 20 characters long".into()
-                }
+                LoginResponse::UsernameInvalidLength => "Username is too short or too long".into(),
+                LoginResponse::UsernameLengthOutOfRange {
+                    min_length,
+                    max_length,
+                } => format!(
+                    "Username must be between {} and {} characters long",
+                    min_length, max_length
+                ),
                 _ => "OK".into(),
             }
         }
@@ -159,7 +430,7 @@ pub mod server_response {
 
     /// Answer from server for ListRoomsMessage
     /// Returns list of ids of public rooms
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct ListRoomsResponse {
         pub rooms: Vec<RoomId>,
     }
@@ -169,28 +440,70 @@ pub mod server_response {
     }
 
     /// Answer from server for RegisterRoomMessage
-    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
     pub enum RegisterRoomResponse {
         Ok,
         RoomIdAlreadyExists,
         Unauthenticated,
+        /// No deal matching the room's deal constraints could be generated.
+        /// Since version 2, older clients get `RoomIdAlreadyExists`.
+        UnsatisfiableDealConstraints,
+        /// The server has as many rooms as it allows.
+        /// Since version 2, older clients get `RoomIdAlreadyExists`.
+        TooManyRooms,
     }
 
     impl MessageTrait for RegisterRoomResponse {
         const MSG_TYPE: &'static str = "register_room_response";
+
+        fn for_version(&self, version: u32) -> Option<Self> {
+            match self {
+                _ if version >= 2 => None,
+                RegisterRoomResponse::UnsatisfiableDealConstraints
+                | RegisterRoomResponse::TooManyRooms => {
+                    Some(RegisterRoomResponse::RoomIdAlreadyExists)
+                }
+                _ => None,
+            }
+        }
+    }
+
+    impl GetErrorMessage for RegisterRoomResponse {
+        fn err_msg(&self) -> String {
+            match self {
+                RegisterRoomResponse::RoomIdAlreadyExists => "Room already exists".into(),
+                RegisterRoomResponse::Unauthenticated => "You are not authenticated".into(),
+                RegisterRoomResponse::UnsatisfiableDealConstraints => {
+                    "No deal matches the deal constraints of the room".into()
+                }
+                RegisterRoomResponse::TooManyRooms => "The server has too many rooms".into(),
+                _ => "OK".into(),
+            }
+        }
     }
 
     /// Answer from server for JoinRoomMessage
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub enum JoinRoomResponse {
         Ok,
         AlreadyInRoom,
         RoomNotFound,
         Unauthenticated,
+        /// The room has as many users as the server allows.
+        /// Since version 2, older clients get `RoomNotFound`.
+        RoomFull,
     }
 
     impl MessageTrait for JoinRoomResponse {
         const MSG_TYPE: &'static str = "join_room_response";
+
+        fn for_version(&self, version: u32) -> Option<Self> {
+            match self {
+                _ if version >= 2 => None,
+                JoinRoomResponse::RoomFull => Some(JoinRoomResponse::RoomNotFound),
+                _ => None,
+            }
+        }
     }
 
     impl GetErrorMessage for JoinRoomResponse {
@@ -199,13 +512,14 @@ pub mod server_response {
                 JoinRoomResponse::Unauthenticated => "You are not authenticated".into(),
                 JoinRoomResponse::AlreadyInRoom => "You are already in the room".into(),
                 JoinRoomResponse::RoomNotFound => "Room not found".into(),
+                JoinRoomResponse::RoomFull => "The room is full".into(),
                 _ => "OK".into(),
             }
         }
     }
 
     /// Answer from server for LeaveRoomMessage
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub enum LeaveRoomResponse {
         Ok,
         NotInRoom,
@@ -218,7 +532,7 @@ pub mod server_response {
 
     /// Answer from server for ListPlacesMessage
     /// Returns 4-element list of places in the room
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub enum ListPlacesResponse {
         Ok([Option<User>; 4]),
         NotInRoom,
@@ -240,16 +554,32 @@ pub mod server_response {
     }
 
     /// Answer from server for SelectPlaceMessage
-    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
     pub enum SelectPlaceResponse {
         Ok,
         NotInRoom,
         PlaceAlreadyTaken,
         Unauthenticated,
+        /// The room is a table of a tournament round and the place belongs to another pair.
+        /// Since version 2, older clients get `PlaceAlreadyTaken`.
+        NotYourSeat,
+        /// The user looked at the hands of the running deal, so can't play it.
+        /// Since version 2, older clients get `PlaceAlreadyTaken`.
+        KibitzedDeal,
     }
 
     impl MessageTrait for SelectPlaceResponse {
         const MSG_TYPE: &'static str = "select_place_response";
+
+        fn for_version(&self, version: u32) -> Option<Self> {
+            match self {
+                _ if version >= 2 => None,
+                SelectPlaceResponse::NotYourSeat | SelectPlaceResponse::KibitzedDeal => {
+                    Some(SelectPlaceResponse::PlaceAlreadyTaken)
+                }
+                _ => None,
+            }
+        }
     }
 
     impl GetErrorMessage for SelectPlaceResponse {
@@ -258,6 +588,13 @@ pub mod server_response {
                 SelectPlaceResponse::Unauthenticated => "You are not authenticated".into(),
                 SelectPlaceResponse::NotInRoom => "You are not in a room".into(),
                 SelectPlaceResponse::PlaceAlreadyTaken => "Place is already taken".into(),
+                SelectPlaceResponse::NotYourSeat => {
+                    "The place belongs to another pair of the tournament".into()
+                }
+                SelectPlaceResponse::KibitzedDeal => {
+                    "You looked at the hands of this deal, take a place once the next one starts"
+                        .into()
+                }
                 _ => "OK".into(),
             }
         }
@@ -265,7 +602,7 @@ pub mod server_response {
 
     /// Answer from server for GetCards
     /// Returns list of cards
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub enum GetCardsResponse {
         Ok { cards: Vec<Card>, position: Player },
         SpectatorNotAllowed,
@@ -288,8 +625,157 @@ pub mod server_response {
         }
     }
 
+    /// Answer from server for KibitzMessage
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub enum KibitzResponse {
+        /// Current hands of the players, `None` for seats outside of the requested view
+        Ok {
+            hands: [Option<Vec<Card>>; 4],
+        },
+        KibitzingForbidden,
+        PlayerNotAllowed,
+        NotInRoom,
+        Unauthenticated,
+    }
+
+    impl MessageTrait for KibitzResponse {
+        const MSG_TYPE: &'static str = "kibitz_response";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl GetErrorMessage for KibitzResponse {
+        fn err_msg(&self) -> String {
+            match self {
+                KibitzResponse::Unauthenticated => "You are not authenticated".into(),
+                KibitzResponse::NotInRoom => "You are not in a room".into(),
+                KibitzResponse::KibitzingForbidden => {
+                    "Looking at the hands is forbidden in this room".into()
+                }
+                KibitzResponse::PlayerNotAllowed => "Players can't look at other hands".into(),
+                _ => "OK".into(),
+            }
+        }
+    }
+
+    /// Answer from server for WatchBroadcastMessage
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub enum WatchBroadcastResponse {
+        Ok,
+        RoomNotFound,
+        /// The room isn't broadcast
+        NoBroadcast,
+        Unauthenticated,
+    }
+
+    impl MessageTrait for WatchBroadcastResponse {
+        const MSG_TYPE: &'static str = "watch_broadcast_response";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl GetErrorMessage for WatchBroadcastResponse {
+        fn err_msg(&self) -> String {
+            match self {
+                WatchBroadcastResponse::Unauthenticated => "You are not authenticated".into(),
+                WatchBroadcastResponse::RoomNotFound => "Room not found".into(),
+                WatchBroadcastResponse::NoBroadcast => "The room isn't broadcast".into(),
+                _ => "OK".into(),
+            }
+        }
+    }
+
+    /// Answer from server for RegisterTournamentMessage
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+    pub enum RegisterTournamentResponse {
+        Ok,
+        TournamentIdAlreadyExists,
+        /// The movement can't be played by given number of pairs
+        UnsupportedPairCount,
+        Unauthenticated,
+    }
+
+    impl MessageTrait for RegisterTournamentResponse {
+        const MSG_TYPE: &'static str = "register_tournament_response";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl GetErrorMessage for RegisterTournamentResponse {
+        fn err_msg(&self) -> String {
+            match self {
+                RegisterTournamentResponse::Unauthenticated => "You are not authenticated".into(),
+                RegisterTournamentResponse::TournamentIdAlreadyExists => {
+                    "Tournament already exists".into()
+                }
+                RegisterTournamentResponse::UnsupportedPairCount => {
+                    "The movement can't be played by this number of pairs".into()
+                }
+                _ => "OK".into(),
+            }
+        }
+    }
+
+    /// Answer from server for JoinTournamentMessage
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+    pub enum JoinTournamentResponse {
+        Ok,
+        TournamentNotFound,
+        Unauthenticated,
+    }
+
+    impl MessageTrait for JoinTournamentResponse {
+        const MSG_TYPE: &'static str = "join_tournament_response";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl GetErrorMessage for JoinTournamentResponse {
+        fn err_msg(&self) -> String {
+            match self {
+                JoinTournamentResponse::Unauthenticated => "You are not authenticated".into(),
+                JoinTournamentResponse::TournamentNotFound => "Tournament not found".into(),
+                _ => "OK".into(),
+            }
+        }
+    }
+
+    /// Answer from server for StartRoundMessage
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+    pub enum StartRoundResponse {
+        Ok,
+        TournamentNotFound,
+        NotDirector,
+        /// Some tables haven't finished the current round yet
+        RoundInProgress,
+        TournamentFinished,
+        /// A room of the round couldn't be created
+        RoomIdAlreadyExists,
+        Unauthenticated,
+        /// The server doesn't allow as many rooms as the round needs
+        TooManyRooms,
+    }
+
+    impl MessageTrait for StartRoundResponse {
+        const MSG_TYPE: &'static str = "start_round_response";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl GetErrorMessage for StartRoundResponse {
+        fn err_msg(&self) -> String {
+            match self {
+                StartRoundResponse::Unauthenticated => "You are not authenticated".into(),
+                StartRoundResponse::TournamentNotFound => "Tournament not found".into(),
+                StartRoundResponse::NotDirector => "Only the director can start a round".into(),
+                StartRoundResponse::RoundInProgress => "The current round isn't finished".into(),
+                StartRoundResponse::TournamentFinished => "The tournament is finished".into(),
+                StartRoundResponse::RoomIdAlreadyExists => {
+                    "A room of the round already exists".into()
+                }
+                StartRoundResponse::TooManyRooms => "The server doesn't allow more rooms".into(),
+                _ => "OK".into(),
+            }
+        }
+    }
+
     /// Answer from server for TrickMessage
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub enum MakeBidResponse {
         Ok,
         NotInRoom,
@@ -298,10 +784,30 @@ pub mod server_response {
         AuctionNotInProcess,
         InvalidBid,
         Unauthenticated,
+        /// The previous move is still being shown, the next one can be made after it.
+        /// Since version 2, older clients get `NotYourTurn`.
+        MoveInProgress,
+        /// Only the last bid of the opponents can be doubled, if it's not doubled yet.
+        /// Since version 2, older clients get `InvalidBid`.
+        CantDouble,
+        /// Only a double of the opponents can be redoubled.
+        /// Since version 2, older clients get `InvalidBid`.
+        CantRedouble,
     }
 
     impl MessageTrait for MakeBidResponse {
         const MSG_TYPE: &'static str = "make_bid_response";
+
+        fn for_version(&self, version: u32) -> Option<Self> {
+            match self {
+                _ if version >= 2 => None,
+                MakeBidResponse::MoveInProgress => Some(MakeBidResponse::NotYourTurn),
+                MakeBidResponse::CantDouble | MakeBidResponse::CantRedouble => {
+                    Some(MakeBidResponse::InvalidBid)
+                }
+                _ => None,
+            }
+        }
     }
 
     impl GetErrorMessage for MakeBidResponse {
@@ -313,13 +819,43 @@ pub mod server_response {
                 MakeBidResponse::NotYourTurn => "It's not your turn".into(),
                 MakeBidResponse::AuctionNotInProcess => "Auction is not in process".into(),
                 MakeBidResponse::InvalidBid => "This bid is not valid".into(),
+                MakeBidResponse::MoveInProgress => "Wait until the last move is shown".into(),
+                MakeBidResponse::CantDouble => "You can't double now".into(),
+                MakeBidResponse::CantRedouble => "You can't redouble now".into(),
+                _ => "OK".into(),
+            }
+        }
+    }
+
+    /// Answer from server for SetConventionCardMessage
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub enum SetConventionCardResponse {
+        Ok,
+        NotInRoom,
+        SpectatorNotAllowed,
+        Unauthenticated,
+    }
+
+    impl MessageTrait for SetConventionCardResponse {
+        const MSG_TYPE: &'static str = "set_convention_card_response";
+        const SINCE_VERSION: u32 = 2;
+    }
+
+    impl GetErrorMessage for SetConventionCardResponse {
+        fn err_msg(&self) -> String {
+            match self {
+                SetConventionCardResponse::Unauthenticated => "You are not authenticated".into(),
+                SetConventionCardResponse::NotInRoom => "You are not in a room".into(),
+                SetConventionCardResponse::SpectatorNotAllowed => {
+                    "Spectator can't have a convention card".into()
+                }
                 _ => "OK".into(),
             }
         }
     }
 
     /// Answer from server for TrickMessage
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub enum MakeTrickResponse {
         Ok,
         NotInRoom,
@@ -328,10 +864,21 @@ pub mod server_response {
         TrickNotInProcess,
         InvalidCard,
         Unauthenticated,
+        /// The previous move is still being shown, the next one can be made after it.
+        /// Since version 2, older clients get `NotYourTurn`.
+        MoveInProgress,
     }
 
     impl MessageTrait for MakeTrickResponse {
         const MSG_TYPE: &'static str = "make_trick_response";
+
+        fn for_version(&self, version: u32) -> Option<Self> {
+            match self {
+                _ if version >= 2 => None,
+                MakeTrickResponse::MoveInProgress => Some(MakeTrickResponse::NotYourTurn),
+                _ => None,
+            }
+        }
     }
 
     impl From<&TrickStatus> for MakeTrickResponse {
@@ -359,6 +906,7 @@ pub mod server_response {
                 MakeTrickResponse::NotYourTurn => "It's not your turn".into(),
                 MakeTrickResponse::TrickNotInProcess => "Trick is not in process".into(),
                 MakeTrickResponse::InvalidCard => "This card is not valid".into(),
+                MakeTrickResponse::MoveInProgress => "Wait until the last move is shown".into(),
                 _ => "OK".into(),
             }
         }
@@ -367,10 +915,20 @@ pub mod server_response {
 
 pub mod server_notification {
     use super::*;
-    use crate::{game::DealFinished, Bid, Card, GameResult, GameValue, Player, TrickState};
+    use crate::{
+        bidding_system::{BidAnnotation, ConventionCard},
+        duplicate::BoardResult,
+        game::DealFinished,
+        par::Par,
+        record::DealRecord,
+        room::TimeLeft,
+        tournament::{PairRanking, TableAssignment},
+        Bid, Card, Deal, Game, GameResult, GameValue, Player, TrickState,
+    };
+    use std::ops::RangeInclusive;
 
     /// Notification sent by server to all users in the room when a new user joins
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct JoinRoomNotification {
         pub user: User,
     }
@@ -380,7 +938,7 @@ pub mod server_notification {
     }
 
     /// Notification sent by server to all users in the room when a user leaves
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct LeaveRoomNotification {
         pub user: User,
     }
@@ -389,7 +947,7 @@ pub mod server_notification {
         const MSG_TYPE: &'static str = "leave_room_notification";
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct SelectPlaceNotification {
         pub user: User,
         pub position: Option<Player>,
@@ -399,7 +957,7 @@ pub mod server_notification {
         const MSG_TYPE: &'static str = "select_place_notification";
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct GameStartedNotification {
         pub start_position: Player,
         pub player_position: [User; 4],
@@ -409,34 +967,70 @@ pub mod server_notification {
         const MSG_TYPE: &'static str = "game_started_notification";
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct MakeBidNotification {
         pub player: Player,
         pub bid: Bid,
+        /// Meaning of the bid according to the convention card of player's partnership
+        #[serde(default)]
+        pub annotation: Option<BidAnnotation>,
+        /// Explanation written by the player, not sent to their partner
+        #[serde(default)]
+        pub explanation: Option<String>,
     }
 
     impl MessageTrait for MakeBidNotification {
         const MSG_TYPE: &'static str = "make_bid_notification";
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    /// Notification sent by server to all users in the room when a player changes the convention card
+    /// of their partnership
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct ConventionCardNotification {
+        pub player: Player,
+        pub card: Option<ConventionCard>,
+    }
+
+    impl MessageTrait for ConventionCardNotification {
+        const MSG_TYPE: &'static str = "convention_card_notification";
+        const SINCE_VERSION: u32 = 2;
+        const FEATURE: Option<Feature> = Some(Feature::ConventionCards);
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct AskBidNotification {
         pub player: Player,
         pub max_bid: Bid,
+        /// Bids that the asked player can place
+        pub legal_bids: Vec<Bid>,
+        /// Time left for the bid, `None` if the room has no time limits
+        #[serde(default)]
+        pub time_left: Option<TimeLeft>,
     }
 
     impl MessageTrait for AskBidNotification {
         const MSG_TYPE: &'static str = "ask_bid_notification";
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    impl From<&Game> for AskBidNotification {
+        fn from(game: &Game) -> Self {
+            AskBidNotification {
+                player: game.current_player,
+                max_bid: game.max_bid,
+                legal_bids: game.legal_bids(&game.current_player),
+                time_left: None,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct AuctionFinishedNotificationInner {
         pub winner: Player,
         pub max_bid: Bid,
         pub game_value: GameValue,
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub enum AuctionFinishedNotification {
         NoWinner,
         Winner(AuctionFinishedNotificationInner),
@@ -447,17 +1041,44 @@ pub mod server_notification {
     }
 
     /// Notification sent by server to all users in the room when a player is asked to make a trick
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct AskTrickNotification {
         pub player: Player,
         pub cards: Vec<Card>,
+        /// Cards that the asked player can play.
+        /// It's sent only to the asked player and is empty for everyone else, as it reveals player's hand.
+        pub legal_cards: Vec<Card>,
+        /// Time left for the card, `None` if the room has no time limits
+        #[serde(default)]
+        pub time_left: Option<TimeLeft>,
     }
 
     impl MessageTrait for AskTrickNotification {
         const MSG_TYPE: &'static str = "ask_trick_notification";
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    impl From<&Game> for AskTrickNotification {
+        fn from(game: &Game) -> Self {
+            AskTrickNotification {
+                player: game.current_player,
+                cards: game.current_trick.clone(),
+                legal_cards: Vec::new(),
+                time_left: None,
+            }
+        }
+    }
+
+    impl AskTrickNotification {
+        /// Returns the version of notification for the asked player, with `legal_cards` filled
+        pub fn for_player(&self, game: &Game) -> Self {
+            AskTrickNotification {
+                legal_cards: game.legal_cards(&self.player),
+                ..self.clone()
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct MakeTrickNotification {
         pub player: Player,
         pub card: Card,
@@ -467,7 +1088,7 @@ pub mod server_notification {
         const MSG_TYPE: &'static str = "make_trick_notification";
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct TrickFinishedNotification {
         pub taker: Player,
         pub cards: Vec<Card>,
@@ -486,7 +1107,94 @@ pub mod server_notification {
         }
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    /// Notification sent by server to both rooms of a team match when a board is finished at any table
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct MatchScoreboardNotification {
+        pub open_room: RoomId,
+        pub closed_room: RoomId,
+        pub boards: Vec<BoardResult>,
+        /// IMPs of the team sitting North-South in the open room, minus IMPs of the other team
+        pub imps: isize,
+    }
+
+    impl MessageTrait for MatchScoreboardNotification {
+        const MSG_TYPE: &'static str = "match_scoreboard_notification";
+        const SINCE_VERSION: u32 = 2;
+        const FEATURE: Option<Feature> = Some(Feature::TeamMatches);
+    }
+
+    /// Notification sent by server to the audience of a broadcast room when a deal starts, revealing all hands.
+    /// Like other notifications for the audience, it is sent with the delay of the broadcast.
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct BroadcastDealNotification {
+        pub seats: [Option<User>; 4],
+        pub deal: Deal,
+    }
+
+    impl MessageTrait for BroadcastDealNotification {
+        const MSG_TYPE: &'static str = "broadcast_deal_notification";
+        const SINCE_VERSION: u32 = 2;
+        const FEATURE: Option<Feature> = Some(Feature::Broadcasts);
+    }
+
+    /// Room in which a table of a tournament round is played
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct TournamentTable {
+        pub room_id: RoomId,
+        pub assignment: TableAssignment,
+    }
+
+    /// Notification sent by server to all users following a tournament when a round starts
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct TournamentRoundNotification {
+        pub tournament_id: String,
+        /// Round number, counted from 1
+        pub round: usize,
+        pub rounds: usize,
+        /// Usernames of both players of every pair
+        pub pairs: Vec<[String; 2]>,
+        pub tables: Vec<TournamentTable>,
+        /// Numbers of the boards played in the round, counted from 1
+        pub boards: RangeInclusive<usize>,
+    }
+
+    impl MessageTrait for TournamentRoundNotification {
+        const MSG_TYPE: &'static str = "tournament_round_notification";
+        const SINCE_VERSION: u32 = 2;
+        const FEATURE: Option<Feature> = Some(Feature::Tournaments);
+    }
+
+    /// Notification sent by server to all users following a tournament when all tables finish a round
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct TournamentStandingsNotification {
+        pub tournament_id: String,
+        /// Number of finished rounds
+        pub round: usize,
+        pub rounds: usize,
+        /// Usernames of both players of every pair
+        pub pairs: Vec<[String; 2]>,
+        /// Pairs field by field, each field ordered from the best pair
+        pub rankings: Vec<PairRanking>,
+    }
+
+    impl MessageTrait for TournamentStandingsNotification {
+        const MSG_TYPE: &'static str = "tournament_standings_notification";
+        const SINCE_VERSION: u32 = 2;
+        const FEATURE: Option<Feature> = Some(Feature::Tournaments);
+    }
+
+    impl TournamentStandingsNotification {
+        pub fn is_final(&self) -> bool {
+            self.round >= self.rounds
+        }
+
+        /// Rankings split into fields, e.g. North-South and East-West pairs of a Mitchell
+        pub fn fields(&self) -> Vec<&[PairRanking]> {
+            self.rankings.chunk_by(|a, b| a.field == b.field).collect()
+        }
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct GameFinishedNotification {
         pub result: Option<GameResult>,
     }
@@ -503,7 +1211,7 @@ pub mod server_notification {
         }
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct DummyCardsNotification {
         pub cards: Vec<Card>,
         pub dummy: Player,
@@ -519,28 +1227,45 @@ pub mod server_notification {
         }
     }
 
-    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
     pub struct DealFinishedNotification {
         pub points: [usize; 4],
         pub game_wins: [usize; 4],
         pub contract_succeeded: bool,
         pub bidder: Player,
         pub next_deal_bidder: Player,
+        /// Hands as they were dealt, the auction and the play, revealed once the deal is over
+        pub record: DealRecord,
+        /// Par of the finished deal, `None` if it couldn't be computed
+        pub par: Option<Par>,
     }
 
     impl MessageTrait for DealFinishedNotification {
         const MSG_TYPE: &'static str = "deal_finished_notification";
     }
 
-    impl From<DealFinished> for DealFinishedNotification {
-        fn from(deal_finished: DealFinished) -> Self {
+    impl DealFinishedNotification {
+        pub fn new(deal_finished: DealFinished, record: DealRecord, par: Option<Par>) -> Self {
             DealFinishedNotification {
                 points: deal_finished.points,
                 game_wins: deal_finished.game_wins,
                 contract_succeeded: deal_finished.contract_succeeded,
                 bidder: deal_finished.bidder,
                 next_deal_bidder: deal_finished.next_deal_bidder,
+                record,
+                par,
             }
         }
     }
+
+    /// Message of the server administrator, shown to users as it is
+    #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+    pub struct ServerMessageNotification {
+        pub message: String,
+    }
+
+    impl MessageTrait for ServerMessageNotification {
+        const MSG_TYPE: &'static str = "server_message_notification";
+        const SINCE_VERSION: u32 = 2;
+    }
 }
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::game::contract_score;
+use crate::solver::TrickTable;
+use crate::{Bid, BidType, GameValue, Player};
+
+/// Contract reached when both sides bid perfectly, knowing all hands.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Eq, PartialEq, Debug)]
+pub struct ParContract {
+    pub bid: Bid,
+    pub declarer: Player,
+    /// `GameValue::Doubled` for sacrifices, `GameValue::Regular` otherwise
+    pub game_value: GameValue,
+    /// Tricks taken by declarer's side
+    pub tricks: usize,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Par {
+    /// `None` when the deal should be passed out
+    pub contract: Option<ParContract>,
+    /// Score of North-South, negative when East-West score
+    pub score: isize,
+}
+
+impl fmt::Display for Par {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.contract {
+            None => return write!(f, "Passed out"),
+            Some(contract) => {
+                write!(f, "{}", contract.bid.to_str())?;
+                if contract.game_value == GameValue::Doubled {
+                    write!(f, " doubled")?;
+                }
+                write!(f, " by {}, ", contract.declarer)?;
+            }
+        }
+        if self.score >= 0 {
+            write!(f, "NS {}", self.score)
+        } else {
+            write!(f, "EW {}", -self.score)
+        }
+    }
+}
+
+/// Contract that a side can play, with its better placed declarer.
+#[derive(Clone, Copy)]
+struct Candidate {
+    level: u8,
+    strain: BidType,
+    declarer: Player,
+    tricks: usize,
+}
+
+impl Candidate {
+    fn makes(&self) -> bool {
+        self.tricks >= self.level as usize + 6
+    }
+
+    fn score(&self, game_value: GameValue, vulnerable: &[bool; 4]) -> isize {
+        contract_score(
+            self.level,
+            self.strain,
+            game_value,
+            self.tricks,
+            vulnerable[self.declarer.to_usize()],
+        )
+    }
+
+    fn contract(&self, game_value: GameValue) -> ParContract {
+        ParContract {
+            // Level is always in 1..=7
+            bid: Bid::Play(self.level, self.strain),
+            declarer: self.declarer,
+            game_value,
+            tricks: self.tricks,
+        }
+    }
+}
+
+/// All contracts of the side of `player` in bidding order.
+/// Declarer is the partner taking more tricks, or the one bidding first on a tie.
+fn candidates(table: &TrickTable, player: Player, dealer: Player) -> Vec<Candidate> {
+    let partner = player.get_partner();
+    let first = if [dealer, dealer.next()].contains(&player) {
+        player
+    } else {
+        partner
+    };
+    let second = first.get_partner();
+
+    (1..=7)
+        .flat_map(|level| {
+            BidType::ALL.into_iter().map(move |strain| {
+                let declarer = if table.get(strain, second) > table.get(strain, first) {
+                    second
+                } else {
+                    first
+                };
+                Candidate {
+                    level,
+                    strain,
+                    declarer,
+                    tricks: table.get(strain, declarer),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Computes the par contract and score from a double dummy trick table.
+/// Scores follow the rules of this game (see `game::contract_score`). Sacrifices are played doubled.
+/// When both sides can make the same contract, it goes to the side of the `dealer`, which bids first.
+pub fn par(table: &TrickTable, vulnerable: [bool; 4], dealer: Player) -> Par {
+    let dealer_side = candidates(table, dealer, dealer);
+    let other_side = candidates(table, dealer.next(), dealer);
+    let highest_make = |side: &[Candidate]| side.iter().rposition(Candidate::makes);
+
+    // Side with the highest makeable contract declares
+    let (declaring, defending, lowest) =
+        match (highest_make(&dealer_side), highest_make(&other_side)) {
+            (None, None) => {
+                return Par {
+                    contract: None,
+                    score: 0,
+                }
+            }
+            (Some(_), None) => (&dealer_side, &other_side, 0),
+            (None, Some(_)) => (&other_side, &dealer_side, 0),
+            (Some(dealer_make), Some(other_make)) if dealer_make >= other_make => {
+                (&dealer_side, &other_side, other_make)
+            }
+            (Some(dealer_make), Some(_)) => (&other_side, &dealer_side, dealer_make + 1),
+        };
+
+    // Declaring side picks the contract with the best result, given that the defenders
+    // sacrifice above it whenever going down doubled costs them less
+    let mut best: Option<(isize, ParContract)> = None;
+    for (index, candidate) in declaring.iter().enumerate().skip(lowest) {
+        if !candidate.makes() {
+            continue;
+        }
+        let made = candidate.score(GameValue::Regular, &vulnerable);
+        let sacrifice = defending[index + 1..]
+            .iter()
+            .map(|sacrifice| (-sacrifice.score(GameValue::Doubled, &vulnerable), sacrifice))
+            .min_by_key(|(penalty, _)| *penalty);
+
+        let result = match sacrifice {
+            Some((penalty, sacrifice)) if penalty < made => {
+                (penalty, sacrifice.contract(GameValue::Doubled))
+            }
+            _ => (made, candidate.contract(GameValue::Regular)),
+        };
+        if best.is_none_or(|(score, _)| result.0 > score) {
+            best = Some(result);
+        }
+    }
+
+    // There's always at least the highest makeable contract to choose from
+    let (score, contract) = best.unwrap();
+    let north_south = matches!(declaring[0].declarer, Player::North | Player::South);
+    Par {
+        contract: Some(contract),
+        score: if north_south { score } else { -score },
+    }
+}
@@ -1,9 +1,10 @@
 use std::convert::TryInto;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize, JsonSchema, Serialize)]
 pub enum Player {
     North = 0,
     East = 1,
@@ -12,6 +13,8 @@ pub enum Player {
 }
 
 impl Player {
+    pub const ALL: [Player; 4] = [Player::North, Player::East, Player::South, Player::West];
+
     pub fn next(&self) -> Player {
         self.skip(1)
     }
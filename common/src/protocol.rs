@@ -0,0 +1,89 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Version of the protocol spoken by this build.
+/// It's increased when a message changes so that older clients can't read it, e.g. gets a new enum variant.
+/// New optional fields don't need a new version, they are marked `#[serde(default)]` and ignored by older clients.
+/// Messages and variants record the version that added them, see `MessageTrait`.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest version of the protocol the server still talks.
+/// Clients that don't send a handshake are assumed to talk this version,
+/// the protocol from before the handshake and the optional features.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Optional parts of the protocol a peer can support
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// Responses are sent as socket.io acknowledgements of requests asking for them
+    Acknowledgements,
+    ConventionCards,
+    TimeControl,
+    Kibitzing,
+    Broadcasts,
+    TeamMatches,
+    Tournaments,
+    /// Finished deals come with their records and par results
+    DealRecords,
+    /// Feature of a newer peer that this build doesn't know
+    #[serde(other)]
+    Unknown,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 8] = [
+        Feature::Acknowledgements,
+        Feature::ConventionCards,
+        Feature::TimeControl,
+        Feature::Kibitzing,
+        Feature::Broadcasts,
+        Feature::TeamMatches,
+        Feature::Tournaments,
+        Feature::DealRecords,
+    ];
+}
+
+/// Protocol agreed on by a client and the server
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
+pub struct Protocol {
+    pub version: u32,
+    /// Features supported by both sides
+    pub features: Vec<Feature>,
+}
+
+impl Default for Protocol {
+    /// Protocol of clients that don't send a handshake
+    fn default() -> Self {
+        Self {
+            version: MIN_PROTOCOL_VERSION,
+            features: Vec::new(),
+        }
+    }
+}
+
+impl Protocol {
+    /// Agrees on the newest version both sides talk and the features both support.
+    /// Returns `None` if the client is older than `MIN_PROTOCOL_VERSION`.
+    pub fn negotiate(
+        client_version: u32,
+        client_features: &[Feature],
+        server_features: &[Feature],
+    ) -> Option<Self> {
+        if client_version < MIN_PROTOCOL_VERSION {
+            return None;
+        }
+        let features = server_features
+            .iter()
+            .filter(|feature| **feature != Feature::Unknown && client_features.contains(feature))
+            .copied()
+            .collect();
+        Some(Self {
+            version: client_version.min(PROTOCOL_VERSION),
+            features,
+        })
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+}
@@ -0,0 +1,238 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use std::cmp::Ordering;
+
+use crate::{Bid, BidType, Card, Deal, GameValue, Player, Rank, Suit};
+
+/// Complete record of a played deal: hands as dealt, the auction and the cards in order of play.
+///
+/// A replay goes through the calls first and then through the cards,
+/// a position in it is given by the number of steps taken.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Eq, Debug)]
+pub struct DealRecord {
+    pub deal: Deal,
+    /// Calls starting with the dealer's
+    pub auction: Vec<Bid>,
+    pub play: Vec<(Player, Card)>,
+}
+
+impl DealRecord {
+    /// Number of steps needed to replay the whole deal
+    pub fn steps(&self) -> usize {
+        self.auction.len() + self.play.len()
+    }
+
+    /// Player that made the call with given index
+    pub fn caller(&self, index: usize) -> Player {
+        self.deal.dealer.skip(index)
+    }
+
+    /// Calls made after given number of steps
+    pub fn auction_at(&self, step: usize) -> &[Bid] {
+        &self.auction[..step.min(self.auction.len())]
+    }
+
+    /// Cards played after given number of steps
+    pub fn played_at(&self, step: usize) -> &[(Player, Card)] {
+        let cards = step.saturating_sub(self.auction.len());
+        &self.play[..cards.min(self.play.len())]
+    }
+
+    /// Cards of the trick on the table after given number of steps, a complete trick stays until the next card
+    pub fn trick_at(&self, step: usize) -> &[(Player, Card)] {
+        let played = self.played_at(step);
+        let trick_start = played.len().saturating_sub(1) / 4 * 4;
+        &played[trick_start..]
+    }
+
+    /// Cards the players hold after given number of steps
+    pub fn hands_at(&self, step: usize) -> [Vec<Card>; 4] {
+        let played = self.played_at(step);
+        let mut hands = self.deal.hands.clone();
+        for (player, card) in played {
+            hands[player.to_usize()].retain(|c| c != card);
+        }
+        hands
+    }
+
+    /// Final contract with its level, strain and whether it was doubled, `None` if no bid was made
+    pub fn contract(&self) -> Option<(u8, BidType, GameValue)> {
+        let mut contract = None;
+        for bid in &self.auction {
+            match (*bid, contract.as_mut()) {
+                (Bid::Play(level, strain), _) => {
+                    contract = Some((level, strain, GameValue::Regular))
+                }
+                (Bid::Double, Some((_, _, value))) => *value = GameValue::Doubled,
+                (Bid::Redouble, Some((_, _, value))) => *value = GameValue::Redoubled,
+                _ => (),
+            }
+        }
+        contract
+    }
+
+    /// Player on the right of the opening leader, `None` before the opening lead
+    pub fn declarer(&self) -> Option<Player> {
+        self.play.first().map(|(leader, _)| leader.prev())
+    }
+
+    /// Players that took the complete tricks, in order of play
+    pub fn trick_winners(&self) -> Vec<Player> {
+        let Some((_, strain, _)) = self.contract() else {
+            return Vec::new();
+        };
+        self.play
+            .chunks_exact(4)
+            .map(|trick| {
+                let mut winner = trick[0];
+                for &(player, card) in &trick[1..] {
+                    if card.compare_with_trump(&winner.1, &strain) == Some(Ordering::Greater) {
+                        winner = (player, card);
+                    }
+                }
+                winner.0
+            })
+            .collect()
+    }
+
+    /// Whether all cards were played, or all players passed
+    pub fn is_finished(&self) -> bool {
+        let passed_out =
+            self.auction.len() == 4 && self.auction.iter().all(|bid| *bid == Bid::Pass);
+        passed_out || self.play.len() == 52
+    }
+
+    /// Record as spectators may see it: until the deal is finished, hands are hidden,
+    /// except dummy's once the opening lead was made
+    pub fn public(&self) -> DealRecord {
+        if self.is_finished() {
+            return self.clone();
+        }
+        let dummy = self.declarer().map(|declarer| declarer.get_partner());
+        let mut record = self.clone();
+        for player in Player::ALL {
+            if Some(player) != dummy {
+                record.deal.hands[player.to_usize()].clear();
+            }
+        }
+        record
+    }
+
+    /// Record in Portable Bridge Notation, as board number `board_no`
+    pub fn to_pbn(&self, board_no: usize) -> String {
+        let deal = &self.deal;
+        let vulnerable = match (deal.vulnerable[0], deal.vulnerable[1]) {
+            (false, false) => "None",
+            (true, false) => "NS",
+            (false, true) => "EW",
+            (true, true) => "All",
+        };
+        let hands = (0..4)
+            .map(|i| pbn_hand(&deal.hands[deal.dealer.skip(i).to_usize()]))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut pbn = format!("[Board \"{}\"]\n", board_no);
+        pbn += &format!("[Dealer \"{}\"]\n", pbn_player(deal.dealer));
+        pbn += &format!("[Vulnerable \"{}\"]\n", vulnerable);
+        pbn += &format!("[Deal \"{}:{}\"]\n", pbn_player(deal.dealer), hands);
+
+        let contract = match self.contract() {
+            Some((level, strain, value)) => {
+                let doubled = match value {
+                    GameValue::Regular => "",
+                    GameValue::Doubled => "X",
+                    GameValue::Redoubled => "XX",
+                };
+                format!("{}{}{}", level, strain.to_str(), doubled)
+            }
+            None => "Pass".into(),
+        };
+        let declarer = self.declarer().map_or("", pbn_player);
+        pbn += &format!("[Declarer \"{}\"]\n", declarer);
+        pbn += &format!("[Contract \"{}\"]\n", contract);
+        if let Some(declarer) = self.declarer().filter(|_| self.is_finished()) {
+            let tricks = self
+                .trick_winners()
+                .iter()
+                .filter(|winner| **winner == declarer || **winner == declarer.get_partner())
+                .count();
+            pbn += &format!("[Result \"{}\"]\n", tricks);
+        }
+
+        pbn += &format!("[Auction \"{}\"]\n", pbn_player(deal.dealer));
+        for calls in self.auction.chunks(4) {
+            let calls = calls.iter().map(pbn_call).collect::<Vec<_>>();
+            pbn += &calls.join(" ");
+            pbn.push('\n');
+        }
+
+        // Cards of every trick are in columns of the players, starting with the opening leader
+        if let Some(&(leader, _)) = self.play.first() {
+            pbn += &format!("[Play \"{}\"]\n", pbn_player(leader));
+            for trick in self.play.chunks(4) {
+                let mut columns = ["-".to_string(), "-".into(), "-".into(), "-".into()];
+                for (player, card) in trick {
+                    let column = (player.to_usize() + 4 - leader.to_usize()) % 4;
+                    columns[column] = format!("{}{}", card.suit.to_str(), pbn_rank(card.rank));
+                }
+                pbn += &columns.join(" ");
+                pbn.push('\n');
+            }
+        }
+
+        pbn
+    }
+}
+
+fn pbn_player(player: Player) -> &'static str {
+    match player {
+        Player::North => "N",
+        Player::East => "E",
+        Player::South => "S",
+        Player::West => "W",
+    }
+}
+
+fn pbn_rank(rank: Rank) -> &'static str {
+    match rank {
+        Rank::Ten => "T",
+        Rank::Two => "2",
+        Rank::Three => "3",
+        Rank::Four => "4",
+        Rank::Five => "5",
+        Rank::Six => "6",
+        Rank::Seven => "7",
+        Rank::Eight => "8",
+        Rank::Nine => "9",
+        Rank::Jack => "J",
+        Rank::Queen => "Q",
+        Rank::King => "K",
+        Rank::Ace => "A",
+    }
+}
+
+/// Suits of the hand from spades to clubs, separated by dots
+fn pbn_hand(cards: &[Card]) -> String {
+    [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs]
+        .map(|suit| {
+            let mut ranks = cards
+                .iter()
+                .filter(|card| card.suit == suit)
+                .map(|card| card.rank)
+                .collect::<Vec<_>>();
+            ranks.sort_by(|a, b| b.cmp(a));
+            ranks.into_iter().map(pbn_rank).collect::<String>()
+        })
+        .join(".")
+}
+
+fn pbn_call(bid: &Bid) -> String {
+    match bid {
+        Bid::Pass => "Pass".into(),
+        Bid::Double => "X".into(),
+        Bid::Redouble => "XX".into(),
+        Bid::Play(level, strain) => format!("{}{}", level, strain.to_str()),
+    }
+}
@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+use crate::{deal_generator::DealConstraints, Player};
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RoomId(Arc<str>);
 
 impl RoomId {
@@ -15,14 +18,61 @@ impl RoomId {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Visibility {
     Public,
     Private,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Time limits of players in a room
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    /// Seconds a player has for every call or card
+    pub per_move_secs: u64,
+    /// Seconds a player can additionally use during one deal, once the time for a move runs out
+    pub time_bank_secs: u64,
+}
+
+/// Time the asked player has left to make their move
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeLeft {
+    pub move_ms: u64,
+    pub bank_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct RoomInfo {
     pub id: RoomId,
     pub visibility: Visibility,
+    /// Every board in the room is dealt to match these constraints
+    #[serde(default)]
+    pub deal_constraints: Option<DealConstraints>,
+    /// Players without time limits when `None`
+    #[serde(default)]
+    pub time_control: Option<TimeControl>,
+    /// Whether spectators may look at the hands of the players
+    #[serde(default)]
+    pub allow_kibitzing: bool,
+    /// Game notifications are broadcast to an audience with this delay, no broadcast when `None`
+    #[serde(default)]
+    pub broadcast_delay_secs: Option<u64>,
+    /// Deals of the room are the same for the same seed, they are random when `None`
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+/// Hands a spectator watches
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectatorView {
+    Seat(Player),
+    AllHands,
+}
+
+impl SpectatorView {
+    pub fn shows(&self, player: Player) -> bool {
+        match self {
+            SpectatorView::Seat(seat) => *seat == player,
+            SpectatorView::AllHands => true,
+        }
+    }
 }
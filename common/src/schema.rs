@@ -0,0 +1,124 @@
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    JsonSchema,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    message::{client_message::*, server_notification::*, MessageTrait, RequestTrait},
+    protocol::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION},
+};
+
+/// JSON Schema of every message of the protocol, for clients not written in Rust.
+/// Messages are keyed by their `MSG_TYPE`, the name of the socket.io event they are sent as,
+/// and refer to the types they contain in `definitions`.
+/// New messages have to be added here, a test fails for a message that is missing.
+pub fn protocol_schema() -> Value {
+    let mut schema = ProtocolSchema::new();
+
+    schema.request::<HelloMessage>();
+    schema.request::<LoginMessage>();
+    schema.request::<ListRoomsMessage>();
+    schema.request::<RegisterRoomMessage>();
+    schema.request::<RegisterMatchMessage>();
+    schema.request::<JoinRoomMessage>();
+    schema.request::<LeaveRoomMessage>();
+    schema.request::<ListPlacesMessage>();
+    schema.request::<SelectPlaceMessage>();
+    schema.request::<GetCardsMessage>();
+    schema.request::<MakeBidMessage>();
+    schema.request::<MakeTrickMessage>();
+    schema.request::<SetConventionCardMessage>();
+    schema.request::<KibitzMessage>();
+    schema.request::<WatchBroadcastMessage>();
+    schema.request::<RegisterTournamentMessage>();
+    schema.request::<JoinTournamentMessage>();
+    schema.request::<StartRoundMessage>();
+
+    schema.notification::<JoinRoomNotification>();
+    schema.notification::<LeaveRoomNotification>();
+    schema.notification::<SelectPlaceNotification>();
+    schema.notification::<GameStartedNotification>();
+    schema.notification::<MakeBidNotification>();
+    schema.notification::<ConventionCardNotification>();
+    schema.notification::<AskBidNotification>();
+    schema.notification::<AuctionFinishedNotification>();
+    schema.notification::<AskTrickNotification>();
+    schema.notification::<MakeTrickNotification>();
+    schema.notification::<TrickFinishedNotification>();
+    schema.notification::<MatchScoreboardNotification>();
+    schema.notification::<BroadcastDealNotification>();
+    schema.notification::<TournamentRoundNotification>();
+    schema.notification::<TournamentStandingsNotification>();
+    schema.notification::<GameFinishedNotification>();
+    schema.notification::<DummyCardsNotification>();
+    schema.notification::<DealFinishedNotification>();
+    schema.notification::<ServerMessageNotification>();
+
+    schema.finish()
+}
+
+/// Schema of the protocol as it's checked in, see `protocol_schema`
+pub fn protocol_schema_string() -> String {
+    let mut schema = serde_json::to_string_pretty(&protocol_schema()).unwrap();
+    schema.push('\n');
+    schema
+}
+
+struct ProtocolSchema {
+    generator: SchemaGenerator,
+    client_messages: Map<String, Value>,
+    server_responses: Map<String, Value>,
+    server_notifications: Map<String, Value>,
+}
+
+impl ProtocolSchema {
+    fn new() -> Self {
+        Self {
+            generator: SchemaSettings::draft07().into_generator(),
+            client_messages: Map::new(),
+            server_responses: Map::new(),
+            server_notifications: Map::new(),
+        }
+    }
+
+    fn message<M: MessageTrait + JsonSchema>(&mut self) -> Value {
+        json!({
+            "since_version": M::SINCE_VERSION,
+            "feature": M::FEATURE,
+            "schema": self.generator.subschema_for::<M>(),
+        })
+    }
+
+    fn request<M: RequestTrait + JsonSchema>(&mut self)
+    where
+        M::Response: JsonSchema,
+    {
+        let mut message = self.message::<M>();
+        message["response"] = M::Response::MSG_TYPE.into();
+        self.client_messages.insert(M::MSG_TYPE.into(), message);
+
+        let response = self.message::<M::Response>();
+        self.server_responses
+            .insert(M::Response::MSG_TYPE.into(), response);
+    }
+
+    fn notification<M: MessageTrait + JsonSchema>(&mut self) {
+        let notification = self.message::<M>();
+        self.server_notifications
+            .insert(M::MSG_TYPE.into(), notification);
+    }
+
+    fn finish(self) -> Value {
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Bridge protocol",
+            "protocol_version": PROTOCOL_VERSION,
+            "min_protocol_version": MIN_PROTOCOL_VERSION,
+            "client_messages": self.client_messages,
+            "server_responses": self.server_responses,
+            "server_notifications": self.server_notifications,
+            "definitions": self.generator.definitions(),
+        })
+    }
+}
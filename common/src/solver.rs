@@ -0,0 +1,893 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::bid::BidType;
+use crate::card::{Card, Suit};
+use crate::player::Player;
+
+/// Number of tricks declarer's side takes with perfect play of all four players (double dummy),
+/// for every strain and declarer.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Eq, PartialEq, Debug)]
+pub struct TrickTable {
+    /// Indexed by strain (in order of `BidType::ALL`) and declarer
+    pub tricks: [[usize; 4]; 5],
+}
+
+impl TrickTable {
+    pub fn get(&self, strain: BidType, declarer: Player) -> usize {
+        self.tricks[strain_index(strain)][declarer.to_usize()]
+    }
+}
+
+/// Returns the number of tricks declarer's side takes in given strain with perfect play of all players.
+/// Player to the left of the declarer leads. All hands have to be of the same length.
+pub fn solve(hands: &[Vec<Card>; 4], strain: BidType, declarer: Player) -> usize {
+    Solver::new(hands, strain).declarer_tricks(declarer)
+}
+
+/// Solves the deal for all 20 combinations of strain and declarer.
+/// Strains are solved in parallel threads.
+pub fn solve_all(hands: &[Vec<Card>; 4]) -> TrickTable {
+    let mut tricks = [[0; 4]; 5];
+    std::thread::scope(|scope| {
+        for (strain, strain_tricks) in BidType::ALL.into_iter().zip(tricks.iter_mut()) {
+            scope.spawn(move || {
+                // Transposition table is shared between declarers of the same strain
+                let mut solver = Solver::new(hands, strain);
+                for (declarer, declarer_tricks) in strain_tricks.iter_mut().enumerate() {
+                    // Unwrap is valid, as declarer is in [0; 3]
+                    *declarer_tricks =
+                        solver.declarer_tricks(Player::from_usize(declarer).unwrap());
+                }
+            });
+        }
+    });
+    TrickTable { tricks }
+}
+
+fn strain_index(strain: BidType) -> usize {
+    match strain {
+        BidType::Trump(suit) => suit_index(suit),
+        BidType::NoTrump => 4,
+    }
+}
+
+fn suit_index(suit: Suit) -> usize {
+    match suit {
+        Suit::Clubs => 0,
+        Suit::Diamonds => 1,
+        Suit::Hearts => 2,
+        Suit::Spades => 3,
+    }
+}
+
+/// Cards are stored as bits of `u64`, 16 bits per suit, where bit 0 of a suit is rank Two.
+const SUIT_BITS: u64 = 0x1fff;
+
+/// Strain without trumps
+const NO_TRUMP: usize = 4;
+
+fn card_bit(card: &Card) -> u8 {
+    (suit_index(card.suit) * 16) as u8 + card.rank.to_u8() - 2
+}
+
+// Helpers below are called millions of times for a deal, so they are inlined even in unoptimized builds
+
+#[inline(always)]
+fn bit_suit(bit: u8) -> usize {
+    (bit / 16) as usize
+}
+
+#[inline(always)]
+fn bit_rank(bit: u8) -> u32 {
+    (bit % 16) as u32
+}
+
+/// Whether the player (or any player at an even distance from the first one) is North or South
+#[inline(always)]
+fn is_north_south(player: usize) -> bool {
+    player & 1 == 0
+}
+
+#[inline(always)]
+fn suit_cards(cards: u64, suit: usize) -> u64 {
+    (cards >> (suit * 16)) & SUIT_BITS
+}
+
+/// Number of cards of each combination of cards of a suit.
+/// Counting bits isn't a single instruction on every target, so the solver looks it up.
+static SUIT_LENGTHS: [u8; 1 << 13] = suit_lengths();
+
+const fn suit_lengths() -> [u8; 1 << 13] {
+    let mut lengths = [0; 1 << 13];
+    let mut cards = 1;
+    while cards < lengths.len() {
+        lengths[cards] = lengths[cards >> 1] + (cards & 1) as u8;
+        cards += 1;
+    }
+    lengths
+}
+
+/// Number of cards given by the bits of one suit
+#[inline(always)]
+fn count(suit_bits: u64) -> u8 {
+    SUIT_LENGTHS[suit_bits as usize]
+}
+
+/// Number of cards of the suit
+#[inline(always)]
+fn length(cards: u64, suit: usize) -> u8 {
+    count(suit_cards(cards, suit))
+}
+
+/// Cards of the suit above all the other cards, given the suit bits of the player and of all players
+#[inline(always)]
+fn top_sequence(own: u64, all: u64) -> u64 {
+    let others = !own & all;
+    if others == 0 {
+        own
+    } else {
+        own >> others.ilog2() << others.ilog2()
+    }
+}
+
+/// Hands holding the remaining cards of a suit from the highest one, 2 bits per card
+type SuitHolders = u32;
+
+/// Holders of all suits, 32 bits per suit
+type Holders = u128;
+
+/// Bounds of tricks taken by North-South from a position at the beginning of a trick.
+/// They hold for every position with the same suit lengths, in which the given number
+/// of top cards of each suit is held by the same hands. Lower cards didn't matter for the result.
+struct Entry {
+    /// Holders of the top cards, other bits are cleared
+    holders: Holders,
+    /// Bits of `Holders` covering the top cards
+    mask: Holders,
+    counts: [u8; 4],
+    lower: u8,
+    upper: u8,
+}
+
+/// Mask of `Holders` covering given number of top cards of each suit
+fn top_mask(counts: [u8; 4]) -> Holders {
+    let mut mask = 0;
+    for (suit, &count) in counts.iter().enumerate() {
+        mask |= ((1 << (count * 2)) - 1) << (suit * 32);
+    }
+    mask
+}
+
+/// Lengths of all suits in all hands, 4 bits each, and the leader.
+/// West's length of spades follows from the others, so the leader takes its place.
+type PositionKey = u64;
+
+const LEADER_SHIFT: u32 = 60;
+
+/// Free slot of the table, no position has this key
+const FREE: PositionKey = u64::MAX;
+
+/// Entries of positions with the same key
+struct Slot {
+    key: PositionKey,
+    entries: Vec<Entry>,
+}
+
+/// Open addressing hash table of the entries
+struct Table {
+    slots: Vec<Slot>,
+    /// Number of used slots
+    len: usize,
+    /// Shift of the hash giving the slot, the table has `1 << (64 - shift)` slots
+    shift: u32,
+}
+
+impl Table {
+    fn new() -> Self {
+        Self::with_slots_log(12)
+    }
+
+    fn with_slots_log(log: u32) -> Self {
+        let slots = (0..1 << log)
+            .map(|_| Slot {
+                key: FREE,
+                entries: Vec::new(),
+            })
+            .collect();
+        Table {
+            slots,
+            len: 0,
+            shift: 64 - log,
+        }
+    }
+
+    /// Slot holding the key, or the free slot where it belongs
+    fn slot(&self, key: PositionKey) -> usize {
+        let mask = self.slots.len() - 1;
+        let mut slot = (key.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> self.shift) as usize;
+        while self.slots[slot].key != key && self.slots[slot].key != FREE {
+            slot = (slot + 1) & mask;
+        }
+        slot
+    }
+
+    fn get_mut(&mut self, key: PositionKey) -> Option<&mut Vec<Entry>> {
+        let index = self.slot(key);
+        let slot = &mut self.slots[index];
+        if slot.key == FREE {
+            None
+        } else {
+            Some(&mut slot.entries)
+        }
+    }
+
+    fn get_or_insert(&mut self, key: PositionKey) -> &mut Vec<Entry> {
+        if self.len * 2 >= self.slots.len() {
+            self.grow();
+        }
+        let index = self.slot(key);
+        let slot = &mut self.slots[index];
+        if slot.key == FREE {
+            slot.key = key;
+            self.len += 1;
+        }
+        &mut slot.entries
+    }
+
+    fn grow(&mut self) {
+        let log = 64 - self.shift + 1;
+        let old = std::mem::replace(self, Table::with_slots_log(log));
+        for slot in old.slots {
+            if slot.key != FREE {
+                let index = self.slot(slot.key);
+                self.slots[index] = slot;
+                self.len += 1;
+            }
+        }
+    }
+}
+
+/// Moves of a player, ordered from the most promising one
+struct Moves {
+    cards: [u8; 13],
+    scores: [i8; 13],
+    len: usize,
+}
+
+impl Moves {
+    fn new() -> Self {
+        Moves {
+            cards: [0; 13],
+            scores: [0; 13],
+            len: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn push(&mut self, card: u8, score: i8) {
+        // Insertion sort, as there are at most 13 moves
+        let mut i = self.len;
+        while i > 0 && self.scores[i - 1] < score {
+            self.cards[i] = self.cards[i - 1];
+            self.scores[i] = self.scores[i - 1];
+            i -= 1;
+        }
+        self.cards[i] = card;
+        self.scores[i] = score;
+        self.len += 1;
+    }
+}
+
+/// State of the current trick, seen by a player who doesn't lead
+struct Follow {
+    player: usize,
+    pos: usize,
+    lead_suit: usize,
+    winning_card: u8,
+    partner_winning: bool,
+    /// Whether the next player can beat the card currently winning the trick
+    winning_beaten: bool,
+    /// Whether partner of the second player can beat the card currently winning the trick
+    partner_beats: bool,
+}
+
+/// Alpha-beta search of the trick count, done as series of null-window searches
+/// ("can North-South take at least `target` tricks?") sharing one transposition table.
+struct Solver {
+    hands: [u64; 4],
+    /// Suit of trumps, or `NO_TRUMP`
+    trump: usize,
+    table: Table,
+    /// North-South tricks of the previously solved leader
+    guess: Option<u8>,
+    /// Suit lengths of the hands, 4 bits each, kept up to date as cards are played
+    lengths: u64,
+    /// Holders of the remaining cards, kept up to date as cards are played
+    holders: [SuitHolders; 4],
+}
+
+impl Solver {
+    fn new(hands: &[Vec<Card>; 4], strain: BidType) -> Self {
+        let mut bits = [0; 4];
+        for (bits, hand) in bits.iter_mut().zip(hands) {
+            *bits = hand.iter().fold(0, |acc, card| acc | 1 << card_bit(card));
+        }
+        let mut solver = Solver {
+            hands: bits,
+            trump: match strain {
+                BidType::Trump(suit) => suit_index(suit),
+                BidType::NoTrump => NO_TRUMP,
+            },
+            table: Table::new(),
+            guess: None,
+            lengths: 0,
+            holders: [0; 4],
+        };
+        for player in 0..4 {
+            for suit in 0..4 {
+                solver.lengths |=
+                    (length(solver.hands[player], suit) as u64) << ((player * 4 + suit) * 4);
+            }
+        }
+        let present = solver.present();
+        for (suit, holders) in solver.holders.iter_mut().enumerate() {
+            let mut cards = suit_cards(present, suit);
+            let mut i = 0;
+            while cards != 0 {
+                let rank = cards.ilog2();
+                cards ^= 1 << rank;
+                let holder = (0..4)
+                    .find(|&player| suit_cards(solver.hands[player], suit) & 1 << rank != 0)
+                    .unwrap();
+                *holders |= (holder as u32) << (i * 2);
+                i += 1;
+            }
+        }
+        solver
+    }
+
+    fn declarer_tricks(&mut self, declarer: Player) -> usize {
+        let leader = declarer.next().to_usize();
+        let total = self.hands[leader].count_ones() as u8;
+        let north_south = self.north_south_tricks(leader);
+        if is_north_south(declarer.to_usize()) {
+            north_south as usize
+        } else {
+            (total - north_south) as usize
+        }
+    }
+
+    fn north_south_tricks(&mut self, leader: usize) -> u8 {
+        let mut lower = 0;
+        let mut upper = self.hands[leader].count_ones() as u8;
+        // Result with a different leader is usually close, so the search starts from it
+        let mut target = self.guess.unwrap_or(upper.div_ceil(2));
+        while lower < upper {
+            target = target.clamp(lower + 1, upper);
+            if self.can_take(leader, target).0 {
+                lower = target;
+                target += 1;
+            } else {
+                upper = target - 1;
+                target -= 1;
+            }
+        }
+        self.guess = Some(lower);
+        lower
+    }
+
+    /// Whether North-South can take at least `target` of the remaining tricks,
+    /// when `leader` is to lead to the next trick.
+    /// Also returns the cards whose ranks mattered for the result.
+    fn can_take(&mut self, leader: usize, target: u8) -> (bool, u64) {
+        let remaining = self.cards_left(leader);
+        if target == 0 {
+            return (true, 0);
+        }
+        if target > remaining {
+            return (false, 0);
+        }
+        if remaining == 1 {
+            return self.last_trick(leader);
+        }
+
+        let present = self.present();
+
+        // Winners the side on lead can cash right away
+        let (quick_tricks, winners) = self.quick_tricks(leader, present);
+        if is_north_south(leader) && quick_tricks >= target {
+            return (true, winners);
+        }
+        if !is_north_south(leader) && remaining - quick_tricks < target {
+            return (false, winners);
+        }
+
+        // Top trumps of the other side win tricks whatever happens
+        let (sure_tricks, trumps) = self.top_trumps((leader + 1) % 2, present);
+        if is_north_south(leader) && remaining - sure_tricks < target {
+            return (false, trumps);
+        }
+        if !is_north_south(leader) && sure_tricks >= target {
+            return (true, trumps);
+        }
+
+        let key = self.lengths & !(0xf << LEADER_SHIFT) | (leader as u64) << LEADER_SHIFT;
+        let holders = self.holders[0] as Holders
+            | (self.holders[1] as Holders) << 32
+            | (self.holders[2] as Holders) << 64
+            | (self.holders[3] as Holders) << 96;
+        if let Some(entries) = self.table.get_mut(key) {
+            let mut index = 0;
+            while index < entries.len() {
+                let entry = &entries[index];
+                if (entry.lower >= target || entry.upper < target)
+                    && holders & entry.mask == entry.holders
+                {
+                    // Entries which are hit are likely to be hit again
+                    entries[..=index].rotate_right(1);
+                    let entry = &entries[0];
+                    return (entry.lower >= target, top_cards(entry.counts, present));
+                }
+                index += 1;
+            }
+        }
+
+        let (result, relevant) = self.play(leader, 0, [0; 4], present, target);
+
+        let counts = self.relevant_counts(relevant, present);
+        let mask = top_mask(counts);
+        let masked = holders & mask;
+        let entries = self.table.get_or_insert(key);
+        let mut index = 0;
+        while index < entries.len()
+            && !(entries[index].counts == counts && entries[index].holders == masked)
+        {
+            index += 1;
+        }
+        if index == entries.len() {
+            entries.push(Entry {
+                holders: masked,
+                mask,
+                counts,
+                lower: 0,
+                upper: remaining,
+            });
+        }
+        let entry = &mut entries[index];
+        if result {
+            entry.lower = entry.lower.max(target);
+        } else {
+            entry.upper = entry.upper.min(target - 1);
+        }
+
+        (result, top_cards(counts, present))
+    }
+
+    /// Tries all moves of the player at position `pos` of the current trick
+    fn play(
+        &mut self,
+        leader: usize,
+        pos: usize,
+        trick: [u8; 4],
+        present: u64,
+        target: u8,
+    ) -> (bool, u64) {
+        let player = (leader + pos) % 4;
+        let north_south = is_north_south(player);
+        let moves = self.moves(leader, pos, &trick, present);
+
+        let mut relevant = 0;
+        let mut index = 0;
+        while index < moves.len {
+            let card = moves.cards[index];
+            index += 1;
+            let mut trick = trick;
+            trick[pos] = card;
+            let holders = self.remove(player, card);
+
+            let (result, card_relevant) = if pos == 3 {
+                let winning = self.winning_pos(&trick, 4);
+                let winner = (leader + winning) % 4;
+                let target = if is_north_south(winner) {
+                    target - 1
+                } else {
+                    target
+                };
+                let (result, relevant) = self.can_take(winner, target);
+                (result, relevant | self.trick_relevant(&trick, winning))
+            } else {
+                self.play(leader, pos + 1, trick, present, target)
+            };
+
+            self.restore(player, card, holders);
+
+            // North-South look for any move reaching the target, East-West for any move preventing it
+            if result == north_south {
+                return (result, card_relevant);
+            }
+            relevant |= card_relevant;
+        }
+
+        (!north_south, relevant)
+    }
+
+    /// Takes the card from the hand of the player. Returns holders of its suit, so that it can be put back.
+    #[inline(always)]
+    fn remove(&mut self, player: usize, card: u8) -> SuitHolders {
+        let suit = bit_suit(card);
+        let higher = suit_cards(self.present(), suit) >> bit_rank(card) >> 1;
+        let holders = self.holders[suit];
+        let kept = (1 << (count(higher) * 2)) - 1;
+        self.holders[suit] = holders & kept | (holders >> 2) & !kept;
+        self.lengths -= 1 << ((player * 4 + suit) * 4);
+        self.hands[player] ^= 1 << card;
+        holders
+    }
+
+    /// Puts the card taken by `remove` back to the hand of the player
+    #[inline(always)]
+    fn restore(&mut self, player: usize, card: u8, holders: SuitHolders) {
+        let suit = bit_suit(card);
+        self.holders[suit] = holders;
+        self.lengths += 1 << ((player * 4 + suit) * 4);
+        self.hands[player] ^= 1 << card;
+    }
+
+    /// Rank of the winning card matters only when it beat another card of the same suit
+    #[inline(always)]
+    fn trick_relevant(&self, trick: &[u8; 4], winning: usize) -> u64 {
+        let card = trick[winning];
+        let mut pos = 0;
+        while pos < 4 {
+            if pos != winning && bit_suit(trick[pos]) == bit_suit(card) {
+                return 1 << card;
+            }
+            pos += 1;
+        }
+        0
+    }
+
+    fn last_trick(&self, leader: usize) -> (bool, u64) {
+        let mut trick = [0; 4];
+        for (pos, card) in trick.iter_mut().enumerate() {
+            *card = self.hands[(leader + pos) % 4].trailing_zeros() as u8;
+        }
+        let winning = self.winning_pos(&trick, 4);
+        (
+            is_north_south(leader + winning),
+            self.trick_relevant(&trick, winning),
+        )
+    }
+
+    /// Lower bound of tricks the side on lead takes by cashing top cards: the leader cashes his winners,
+    /// then leads to a winner of his partner, if there is one, and the partner cashes his winners.
+    /// Also returns the cashed cards.
+    fn quick_tricks(&self, leader: usize, present: u64) -> (u8, u64) {
+        let partner = (leader + 2) % 4;
+        let (own, own_cards) = self.cashable(leader, present);
+        let (partner_tricks, partner_cards) = self.cashable(partner, present);
+
+        let mut own_total = 0;
+        let mut partner_total = 0;
+        let mut entry = false;
+        // Partner discards on the winners of the leader in suits he doesn't hold any more,
+        // cards of suits he doesn't cash are spared for that
+        let mut discards = 0;
+        let mut spare = 0;
+        let mut suit = 0;
+        while suit < 4 {
+            let partner_length = length(self.hands[partner], suit);
+            own_total += own[suit];
+            partner_total += partner_tricks[suit];
+            if partner_tricks[suit] > 0 && suit_cards(self.hands[leader], suit) != 0 {
+                entry = true;
+            }
+            if own[suit] > 0 {
+                discards += own[suit].saturating_sub(partner_length);
+            } else if partner_tricks[suit] == 0 {
+                spare += partner_length;
+            }
+            suit += 1;
+        }
+        if !entry {
+            (own_total, own_cards)
+        } else if discards <= spare {
+            (own_total + partner_total, own_cards | partner_cards)
+        } else if partner_total > own_total {
+            (partner_total, partner_cards)
+        } else {
+            (own_total, own_cards)
+        }
+    }
+
+    /// Highest number of top trumps held by one player of the side, and these trumps
+    fn top_trumps(&self, side: usize, present: u64) -> (u8, u64) {
+        if self.trump == NO_TRUMP {
+            return (0, 0);
+        }
+        let all = suit_cards(present, self.trump);
+        let mut best = (0, 0);
+        for player in [side, side + 2] {
+            let top = top_sequence(suit_cards(self.hands[player], self.trump), all);
+            let tricks = count(top);
+            if tricks > best.0 {
+                best = (tricks, top << (self.trump * 16));
+            }
+        }
+        best
+    }
+
+    /// Top cards the player can cash being on lead, limited by the length of opponents that could
+    /// ruff them. When the top cards exhaust the suit in the other hands, the low cards are cashed too.
+    /// Returns the number of tricks in each suit and the top cards.
+    fn cashable(&self, player: usize, present: u64) -> ([u8; 4], u64) {
+        let left = self.hands[(player + 1) % 4];
+        let partner = self.hands[(player + 2) % 4];
+        let right = self.hands[(player + 3) % 4];
+        // Opponents can ruff only if they have trumps
+        let (left_ruffs, right_ruffs) = if self.trump == NO_TRUMP {
+            (false, false)
+        } else {
+            (
+                suit_cards(left, self.trump) != 0,
+                suit_cards(right, self.trump) != 0,
+            )
+        };
+
+        let mut tricks = [0; 4];
+        let mut cards = 0;
+        // Plain loops keep the search fast in unoptimized builds too
+        let mut suit = 0;
+        while suit < 4 {
+            let own = suit_cards(self.hands[player], suit);
+            let top = top_sequence(own, suit_cards(present, suit));
+            let mut winners = count(top);
+            if winners > 0 {
+                let left_length = length(left, suit);
+                let right_length = length(right, suit);
+                if winners >= left_length
+                    && winners >= right_length
+                    && winners >= length(partner, suit)
+                {
+                    winners = count(own);
+                }
+                if suit != self.trump {
+                    if left_ruffs && left_length < winners {
+                        winners = left_length;
+                    }
+                    if right_ruffs && right_length < winners {
+                        winners = right_length;
+                    }
+                }
+                tricks[suit] = winners;
+                if winners > 0 {
+                    cards |= top << (suit * 16);
+                }
+            }
+            suit += 1;
+        }
+        (tricks, cards)
+    }
+
+    /// Position of the card that wins the trick so far, out of the first `len` cards
+    #[inline(always)]
+    fn winning_pos(&self, trick: &[u8; 4], len: usize) -> usize {
+        let mut winner = 0;
+        let mut pos = 1;
+        while pos < len {
+            if self.beats(trick[pos], trick[winner]) {
+                winner = pos;
+            }
+            pos += 1;
+        }
+        winner
+    }
+
+    #[inline(always)]
+    fn beats(&self, card: u8, other: u8) -> bool {
+        if bit_suit(card) == bit_suit(other) {
+            bit_rank(card) > bit_rank(other)
+        } else {
+            bit_suit(card) == self.trump
+        }
+    }
+
+    /// Generates moves of the player at position `pos` of the current trick.
+    /// Out of cards that are equivalent (no other remaining card of the suit lies between them) only one is generated.
+    /// Cards of the current trick are still included in `present`, as they separate the cards in hands.
+    fn moves(&self, leader: usize, pos: usize, trick: &[u8; 4], present: u64) -> Moves {
+        let player = (leader + pos) % 4;
+        let hand = self.hands[player];
+
+        let lead_suit = bit_suit(trick[0]);
+        let (first_suit, last_suit) = if pos > 0 && suit_cards(hand, lead_suit) != 0 {
+            (lead_suit, lead_suit)
+        } else {
+            (0, 3)
+        };
+        let follow = if pos > 0 {
+            Some(self.follow(player, pos, trick))
+        } else {
+            None
+        };
+
+        let mut moves = Moves::new();
+        let mut suit = first_suit;
+        while suit <= last_suit {
+            let held = suit_cards(hand, suit);
+            let mut own = held;
+            let all = suit_cards(present, suit);
+            while own != 0 {
+                let rank = own.ilog2();
+                own ^= 1 << rank;
+                // Only the highest card of a sequence is generated
+                let higher = all >> rank >> 1;
+                if higher & higher.wrapping_neg() & held >> rank >> 1 != 0 {
+                    continue;
+                }
+                let card = (suit * 16) as u8 + rank as u8;
+                let score = match &follow {
+                    Some(follow) => self.follow_score(follow, card),
+                    None => self.lead_score(player, card, all),
+                };
+                moves.push(card, score);
+            }
+            suit += 1;
+        }
+        moves
+    }
+
+    /// State of the trick relevant for ordering moves of a player who doesn't lead
+    fn follow(&self, player: usize, pos: usize, trick: &[u8; 4]) -> Follow {
+        let lead_suit = bit_suit(trick[0]);
+        let winning = self.winning_pos(trick, pos);
+        let winning_card = trick[winning];
+        let next = (player + 1) % 4;
+        Follow {
+            player,
+            pos,
+            lead_suit,
+            winning_card,
+            partner_winning: is_north_south(pos - winning),
+            // Next player is an opponent, unless this player is the last one
+            winning_beaten: pos < 3 && self.can_beat(next, winning_card, lead_suit),
+            partner_beats: pos == 1 && self.can_beat((player + 2) % 4, winning_card, lead_suit),
+        }
+    }
+
+    /// Heuristic estimate of how good is the move, so that the best moves are tried first
+    #[inline(always)]
+    fn follow_score(&self, follow: &Follow, card: u8) -> i8 {
+        let rank = bit_rank(card) as i8;
+        let wins = self.beats(card, follow.winning_card);
+        let beaten =
+            follow.pos < 3 && self.can_beat((follow.player + 1) % 4, card, follow.lead_suit);
+
+        if follow.partner_winning && !follow.winning_beaten {
+            // Don't waste high cards or trumps
+            -rank - if bit_suit(card) == self.trump { 20 } else { 0 }
+        } else if wins && !beaten {
+            // Win as cheaply as possible
+            80 - rank
+        } else if wins && follow.partner_beats {
+            // Partner in the last position will win the trick anyway
+            -rank - 20
+        } else if wins {
+            // Force the next player to spend a high card
+            30 - rank
+        } else {
+            -rank
+        }
+    }
+
+    #[inline(always)]
+    fn lead_score(&self, player: usize, card: u8, all: u64) -> i8 {
+        let suit = bit_suit(card);
+        let rank = bit_rank(card) as i8;
+        let partner = (player + 2) % 4;
+        let top = all.ilog2();
+
+        if self.ruffs((player + 1) % 4, suit) || self.ruffs((player + 3) % 4, suit) {
+            // Opponents would ruff
+            -10 - rank
+        } else if bit_rank(card) == top {
+            // Cash a winner
+            60
+        } else if self.ruffs(partner, suit) {
+            // Partner ruffs
+            50 - rank
+        } else if suit_cards(self.hands[partner], suit) & 1 << top != 0 {
+            // Lead low towards partner's winner
+            40 - rank
+        } else if suit_cards(self.hands[(player + 3) % 4], suit) & 1 << top != 0 {
+            // Last player would win cheaply
+            10 - rank
+        } else {
+            20 - rank
+        }
+    }
+
+    /// Whether the player can ruff a lead of the suit
+    #[inline(always)]
+    fn ruffs(&self, player: usize, suit: usize) -> bool {
+        self.trump != NO_TRUMP
+            && self.trump != suit
+            && suit_cards(self.hands[player], suit) == 0
+            && suit_cards(self.hands[player], self.trump) != 0
+    }
+
+    /// Whether the player can beat the card in a trick led in `lead_suit`
+    #[inline(always)]
+    fn can_beat(&self, player: usize, card: u8, lead_suit: usize) -> bool {
+        let hand = self.hands[player];
+        let suit = bit_suit(card);
+        if suit_cards(hand, lead_suit) != 0 {
+            suit == lead_suit && suit_cards(hand, suit) >> bit_rank(card) >> 1 != 0
+        } else if self.trump == NO_TRUMP {
+            false
+        } else if self.trump == suit {
+            suit_cards(hand, suit) >> bit_rank(card) >> 1 != 0
+        } else {
+            suit_cards(hand, self.trump) != 0
+        }
+    }
+
+    /// Number of cards in the hand of the player
+    #[inline(always)]
+    fn cards_left(&self, player: usize) -> u8 {
+        let lengths = self.lengths >> (player * 16);
+        ((lengths & 0xf) + (lengths >> 4 & 0xf) + (lengths >> 8 & 0xf) + (lengths >> 12 & 0xf))
+            as u8
+    }
+
+    #[inline(always)]
+    fn present(&self) -> u64 {
+        self.hands[0] | self.hands[1] | self.hands[2] | self.hands[3]
+    }
+
+    /// Number of top cards of each suit down to the lowest relevant card
+    fn relevant_counts(&self, relevant: u64, present: u64) -> [u8; 4] {
+        let mut counts = [0; 4];
+        for (suit, count) in counts.iter_mut().enumerate() {
+            let relevant = suit_cards(relevant, suit);
+            if relevant != 0 {
+                // Cards in sequence with the lowest relevant card are equivalent to it in the search,
+                // so they have to be covered as well
+                let present = suit_cards(present, suit);
+                let mut lowest = relevant.trailing_zeros();
+                let holder = self
+                    .hands
+                    .iter()
+                    .find(|hand| suit_cards(**hand, suit) & 1 << lowest != 0);
+                while let Some(hand) = holder {
+                    let below = present & ((1 << lowest) - 1);
+                    if below == 0 || suit_cards(*hand, suit) & 1 << below.ilog2() == 0 {
+                        break;
+                    }
+                    lowest = below.ilog2();
+                }
+                *count = (present >> lowest).count_ones() as u8;
+            }
+        }
+        counts
+    }
+}
+
+/// Given number of the highest cards of each suit
+fn top_cards(counts: [u8; 4], present: u64) -> u64 {
+    let mut cards = 0;
+    let mut suit = 0;
+    while suit < 4 {
+        let mut rest = suit_cards(present, suit);
+        let mut taken = 0;
+        while taken < counts[suit] {
+            let card = 1 << rest.ilog2();
+            cards |= card << (suit * 16);
+            rest ^= card;
+            taken += 1;
+        }
+        suit += 1;
+    }
+    cards
+}
@@ -0,0 +1,166 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Movement deciding which pairs meet at which table in every round of a pairs tournament.
+/// Pairs are numbered from 0.
+///
+/// Boards are dealt online, so all tables play the same boards in a round and no board relay is needed.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    /// North-South pairs stay at their tables, East-West pairs move up one table every round.
+    /// Pairs `0..tables` sit North-South, pairs `tables..2 * tables` sit East-West.
+    Mitchell,
+    /// Every pair meets every other pair once.
+    Howell,
+}
+
+/// Pairs sitting at a table during one round
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableAssignment {
+    pub table: usize,
+    pub north_south: usize,
+    pub east_west: usize,
+}
+
+impl Movement {
+    /// Checks whether the movement can be played by given number of pairs
+    pub fn supports(&self, pairs: usize) -> bool {
+        match self {
+            Movement::Mitchell => pairs >= 2 && pairs.is_multiple_of(2),
+            Movement::Howell => pairs >= 4 && pairs.is_multiple_of(2),
+        }
+    }
+
+    /// Field in which given pair is ranked: pairs are only compared with pairs sitting in the same direction,
+    /// so a Mitchell has a North-South field 0 and an East-West field 1, while a Howell has just field 0.
+    pub fn field(&self, pairs: usize, pair: usize) -> usize {
+        match self {
+            Movement::Mitchell => usize::from(pair >= self.tables(pairs)),
+            Movement::Howell => 0,
+        }
+    }
+
+    pub fn tables(&self, pairs: usize) -> usize {
+        pairs / 2
+    }
+
+    pub fn rounds(&self, pairs: usize) -> usize {
+        match self {
+            Movement::Mitchell => pairs / 2,
+            Movement::Howell => pairs.saturating_sub(1),
+        }
+    }
+
+    /// Returns assignments of all tables in the round with given index
+    pub fn round(&self, pairs: usize, round: usize) -> Vec<TableAssignment> {
+        let tables = self.tables(pairs);
+        match self {
+            Movement::Mitchell => (0..tables)
+                .map(|table| TableAssignment {
+                    table,
+                    north_south: table,
+                    east_west: tables + (table + tables - round % tables) % tables,
+                })
+                .collect(),
+            Movement::Howell => {
+                // Circle method: the last pair stays at the first table, the others rotate
+                let rotating = pairs - 1;
+                (0..tables)
+                    .map(|table| {
+                        let (north_south, east_west) = if table == 0 {
+                            (pairs - 1, round % rotating)
+                        } else {
+                            (
+                                (round + table) % rotating,
+                                (round + rotating - table) % rotating,
+                            )
+                        };
+                        TableAssignment {
+                            table,
+                            north_south,
+                            east_west,
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// North-South score of a board played by two pairs
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardScore {
+    pub board_no: usize,
+    pub north_south: usize,
+    pub east_west: usize,
+    pub score: isize,
+}
+
+/// Matchpoints of a pair, counting 2 points for every score beaten and 1 for every score tied
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairRanking {
+    pub pair: usize,
+    /// Field in which the pair is ranked, see `Movement::field`
+    #[serde(default)]
+    pub field: usize,
+    pub matchpoints: usize,
+    /// Maximal number of matchpoints the pair could get on the boards it played
+    pub top: usize,
+}
+
+impl PairRanking {
+    pub fn percentage(&self) -> f64 {
+        if self.top == 0 {
+            return 0.0;
+        }
+        self.matchpoints as f64 * 100.0 / self.top as f64
+    }
+}
+
+/// Computes matchpoints of all pairs and returns them field by field, each field ordered from the best pair.
+pub fn rankings(movement: Movement, pairs: usize, scores: &[BoardScore]) -> Vec<PairRanking> {
+    let mut rankings: Vec<_> = (0..pairs)
+        .map(|pair| PairRanking {
+            pair,
+            field: movement.field(pairs, pair),
+            matchpoints: 0,
+            top: 0,
+        })
+        .collect();
+
+    for score in scores {
+        let others = scores
+            .iter()
+            .filter(|other| other.board_no == score.board_no)
+            .count()
+            - 1;
+        let beaten = scores
+            .iter()
+            .filter(|other| other.board_no == score.board_no && other.score < score.score)
+            .count();
+        let tied = scores
+            .iter()
+            .filter(|other| other.board_no == score.board_no && other.score == score.score)
+            .count()
+            - 1;
+
+        let top = 2 * others;
+        let north_south = 2 * beaten + tied;
+        for (pair, matchpoints) in [
+            (score.north_south, north_south),
+            (score.east_west, top - north_south),
+        ] {
+            if let Some(ranking) = rankings.get_mut(pair) {
+                ranking.matchpoints += matchpoints;
+                ranking.top += top;
+            }
+        }
+    }
+
+    rankings.sort_by(|a, b| {
+        a.field
+            .cmp(&b.field)
+            .then(b.percentage().total_cmp(&a.percentage()))
+    });
+    rankings
+}
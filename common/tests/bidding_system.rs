@@ -0,0 +1,50 @@
+use common::bidding_system::ConventionCard;
+use common::*;
+
+fn bid(number: u8, typ: BidType) -> Bid {
+    Bid::new(number, typ).unwrap()
+}
+
+#[test]
+fn convention_card_openings() {
+    let card = ConventionCard::standard();
+
+    let one_no_trump = card.annotate(&[], bid(1, BidType::NoTrump)).unwrap();
+    assert_eq!(one_no_trump.meaning, "15-17 HCP, balanced");
+    assert!(!one_no_trump.alert);
+
+    // Still an opening after passes
+    let strong = card
+        .annotate(&[Bid::Pass, Bid::Pass], bid(2, BidType::Trump(Suit::Clubs)))
+        .unwrap();
+    assert!(strong.alert);
+
+    assert_eq!(card.annotate(&[], bid(3, BidType::NoTrump)), None);
+    assert_eq!(card.annotate(&[], Bid::Pass), None);
+}
+
+#[test]
+fn convention_card_responses() {
+    let card = ConventionCard::standard();
+    let stayman = bid(2, BidType::Trump(Suit::Clubs));
+
+    let annotation = card
+        .annotate(&[bid(1, BidType::NoTrump), Bid::Pass], stayman)
+        .unwrap();
+    assert!(annotation.alert);
+    assert!(annotation.meaning.starts_with("Stayman"));
+
+    // Overcall by the opponent
+    assert_eq!(card.annotate(&[bid(1, BidType::NoTrump)], stayman), None);
+    // Response after interference
+    assert_eq!(
+        card.annotate(
+            &[
+                bid(1, BidType::NoTrump),
+                bid(2, BidType::Trump(Suit::Clubs))
+            ],
+            bid(2, BidType::Trump(Suit::Diamonds))
+        ),
+        None
+    );
+}
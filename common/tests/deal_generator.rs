@@ -0,0 +1,94 @@
+use common::deal_generator::*;
+use common::hand::{high_card_points, is_balanced, suit_lengths};
+use common::*;
+
+/// North 15-17 balanced, South 5+ hearts
+fn constraints() -> DealConstraints {
+    let mut constraints = DealConstraints::default();
+    constraints.seats[Player::North.to_usize()] = SeatConstraints {
+        hcp: Some(15..=17),
+        shape: Some(Shape::Balanced),
+        ..Default::default()
+    };
+    constraints.seats[Player::South.to_usize()].suit_lengths[Suit::Hearts as usize] = Some(5..=13);
+    constraints
+}
+
+#[test]
+fn deal_generator_matches_constraints() {
+    let constraints = constraints();
+    let hands = constraints.generate_seeded(7).unwrap();
+
+    let north = &hands[Player::North.to_usize()];
+    assert!((15..=17).contains(&high_card_points(north)));
+    assert!(is_balanced(north));
+    assert!(suit_lengths(&hands[Player::South.to_usize()])[Suit::Hearts as usize] >= 5);
+    assert!(constraints.matches(&hands));
+
+    // Every card is dealt exactly once
+    let mut cards: Vec<Card> = hands.iter().flatten().copied().collect();
+    assert!(hands.iter().all(|hand| hand.len() == 13));
+    cards.sort_by_key(|card| (card.suit, card.rank));
+    cards.dedup();
+    assert_eq!(cards.len(), 52);
+}
+
+#[test]
+fn deal_generator_seeded() {
+    let constraints = constraints();
+    assert_eq!(
+        constraints.generate_seeded(42),
+        constraints.generate_seeded(42)
+    );
+    assert_ne!(
+        constraints.generate_seeded(42),
+        constraints.generate_seeded(43)
+    );
+}
+
+#[test]
+fn deal_generator_shape_pattern() {
+    let mut constraints = DealConstraints::default();
+    constraints.seats[Player::East.to_usize()].shape = Some(Shape::Pattern([5, 4, 3, 1]));
+    constraints.seats[Player::West.to_usize()].shape = Some(Shape::Pattern([4, 4, 3, 2]));
+
+    for seed in 0..5 {
+        let hands = constraints.generate_seeded(seed).unwrap();
+        for (player, pattern) in [(Player::East, [5, 4, 3, 1]), (Player::West, [4, 4, 3, 2])] {
+            let mut lengths = suit_lengths(&hands[player.to_usize()]);
+            lengths.sort_unstable_by(|a, b| b.cmp(a));
+            assert_eq!(lengths, pattern);
+        }
+    }
+}
+
+#[test]
+fn deal_generator_impossible_constraints() {
+    assert!(constraints().is_possible());
+    assert!(DealConstraints::default().is_possible());
+
+    // 22 + 20 high card points are more than the deck has
+    let mut constraints = DealConstraints::default();
+    constraints.seats[Player::North.to_usize()].hcp = Some(22..=40);
+    constraints.seats[Player::South.to_usize()].hcp = Some(20..=40);
+    assert!(!constraints.is_possible());
+    assert_eq!(constraints.generate_seeded(0), None);
+
+    // Two hands with 7 hearts
+    let mut constraints = DealConstraints::default();
+    constraints.seats[Player::East.to_usize()].suit_lengths[Suit::Hearts as usize] = Some(7..=13);
+    constraints.seats[Player::West.to_usize()].suit_lengths[Suit::Hearts as usize] = Some(7..=13);
+    assert!(!constraints.is_possible());
+
+    // The pattern has 14 cards
+    let mut constraints = DealConstraints::default();
+    constraints.seats[Player::North.to_usize()].shape = Some(Shape::Pattern([5, 5, 3, 1]));
+    assert!(!constraints.is_possible());
+
+    // Three hands without points leave all 40 to the fourth one, which can hold at most 37
+    let mut constraints = DealConstraints::default();
+    for player in [Player::North, Player::East, Player::South] {
+        constraints.seats[player.to_usize()].hcp = Some(0..=0);
+    }
+    assert!(!constraints.is_possible());
+}
@@ -0,0 +1,118 @@
+use common::deal_generator::random_deal;
+use common::duplicate::{board_vulnerability, imps, BoardResult};
+use common::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn imps_thresholds() {
+    assert_eq!(imps(0), 0);
+    assert_eq!(imps(10), 0);
+    assert_eq!(imps(20), 1);
+    assert_eq!(imps(-20), -1);
+    assert_eq!(imps(420), 9);
+    assert_eq!(imps(430), 10);
+    assert_eq!(imps(-620), -12);
+    assert_eq!(imps(3990), 23);
+    assert_eq!(imps(4000), 24);
+    assert_eq!(imps(7600), 24);
+}
+
+#[test]
+fn board_vulnerability_schedule() {
+    assert_eq!(board_vulnerability(1), [false; 4]);
+    assert_eq!(board_vulnerability(2), [true, false, true, false]);
+    assert_eq!(board_vulnerability(3), [false, true, false, true]);
+    assert_eq!(board_vulnerability(4), [true; 4]);
+    assert_eq!(board_vulnerability(7), [true; 4]);
+    assert_eq!(board_vulnerability(16), [false, true, false, true]);
+    assert_eq!(board_vulnerability(17), board_vulnerability(1));
+}
+
+#[test]
+fn board_result_imps() {
+    let pending = BoardResult {
+        board_no: 1,
+        open: Some(420),
+        closed: None,
+    };
+    assert_eq!(pending.imps(), None);
+
+    let game_swing = BoardResult {
+        board_no: 1,
+        open: Some(420),
+        closed: Some(-50),
+    };
+    assert_eq!(game_swing.imps(), Some(10));
+
+    let flat = BoardResult {
+        board_no: 2,
+        open: Some(-110),
+        closed: Some(-110),
+    };
+    assert_eq!(flat.imps(), Some(0));
+}
+
+#[test]
+fn game_start_with_cards() {
+    let hands = random_deal(&mut StdRng::seed_from_u64(7));
+    let mut game = Game::new();
+    game.start_with_cards(hands.clone());
+
+    assert_eq!(game.state, GameState::Auction);
+    assert_eq!(game.player_cards, hands);
+    assert_eq!(game.deal.hands, hands);
+}
+
+#[test]
+fn game_deal_score() {
+    let mut game = Game::new();
+    game.start();
+    assert_eq!(game.deal_score(), 0);
+
+    // East makes 4 spades with an overtrick, not vulnerable
+    game.max_bid = Bid::new(4, BidType::Trump(Suit::Spades)).unwrap();
+    game.max_bidder = Player::East;
+    game.deal.vulnerable = [false; 4];
+    let cards = game.deal.hands.concat();
+    game.collected_cards = [
+        Vec::new(),
+        cards[..24].to_vec(),
+        cards[24..32].to_vec(),
+        cards[32..].to_vec(),
+    ];
+    assert_eq!(game.deal_score(), -150);
+}
+
+#[test]
+fn game_deal_score_doubled() {
+    let mut game = Game::new();
+    game.start();
+
+    // East opens 1NT, South doubles and everyone passes
+    for (player, bid) in [
+        (Player::North, Bid::Pass),
+        (Player::East, Bid::new(1, BidType::NoTrump).unwrap()),
+        (Player::South, Bid::Double),
+        (Player::West, Bid::Pass),
+        (Player::North, Bid::Pass),
+    ] {
+        assert_eq!(game.place_bid(&player, bid), BidStatus::Auction);
+    }
+    assert_eq!(
+        game.place_bid(&Player::East, Bid::Pass),
+        BidStatus::Tricking
+    );
+    assert_eq!(game.max_bidder, Player::East);
+    assert_eq!(game.game_value, GameValue::Doubled);
+    assert_eq!(game.current_player, Player::South);
+
+    // East goes two down, not vulnerable
+    let cards = game.deal.hands.concat();
+    game.collected_cards = [
+        cards[20..].to_vec(),
+        cards[..20].to_vec(),
+        Vec::new(),
+        Vec::new(),
+    ];
+    assert_eq!(game.deal_score(), 300);
+}
@@ -589,3 +589,174 @@ fn test_distribute_points_overtricks() {
     assert_eq!(game.points[bidder_usize], 410); // Base points + overtricks
     assert!(game.vulnerable[bidder_usize]); // Pair becomes vulnerable
 }
+
+#[test]
+fn test_contract_score() {
+    use game::contract_score;
+    let spades = BidType::Trump(Suit::Spades);
+
+    assert_eq!(
+        contract_score(4, spades, GameValue::Regular, 10, false),
+        120
+    );
+    assert_eq!(
+        contract_score(2, spades, GameValue::Regular, 10, false),
+        120
+    );
+    assert_eq!(
+        contract_score(3, BidType::NoTrump, GameValue::Regular, 9, true),
+        100
+    );
+    assert_eq!(contract_score(6, spades, GameValue::Regular, 12, true), 930);
+    assert_eq!(contract_score(4, spades, GameValue::Doubled, 11, true), 370);
+
+    assert_eq!(contract_score(4, spades, GameValue::Regular, 8, true), -200);
+    assert_eq!(
+        contract_score(4, spades, GameValue::Doubled, 9, false),
+        -100
+    );
+    assert_eq!(
+        contract_score(4, spades, GameValue::Doubled, 8, false),
+        -300
+    );
+    assert_eq!(
+        contract_score(4, spades, GameValue::Doubled, 6, false),
+        -800
+    );
+    assert_eq!(contract_score(4, spades, GameValue::Doubled, 7, true), -800);
+    assert_eq!(
+        contract_score(4, spades, GameValue::Redoubled, 9, false),
+        -200
+    );
+    assert_eq!(
+        contract_score(4, spades, GameValue::Redoubled, 6, false),
+        -1600
+    );
+}
+
+#[test]
+fn game_legal_bids() {
+    let mut game = Game::new();
+    assert!(game.legal_bids(&Player::North).is_empty());
+
+    game.state = GameState::Auction;
+
+    // Opening bid - pass and all 35 contracts
+    let bids = game.legal_bids(&Player::North);
+    assert_eq!(bids.len(), 36);
+    assert_eq!(bids[0], Bid::Pass);
+    assert_eq!(bids[1], Bid::new(1, BidType::Trump(Suit::Clubs)).unwrap());
+    assert_eq!(bids[35], Bid::new(7, BidType::NoTrump).unwrap());
+
+    // Only the current player can bid
+    assert!(game.legal_bids(&Player::East).is_empty());
+
+    game.place_bid(
+        &Player::North,
+        Bid::new(6, BidType::Trump(Suit::Spades)).unwrap(),
+    );
+
+    // Opponent can double
+    let bids = game.legal_bids(&Player::East);
+    assert_eq!(
+        bids,
+        vec![
+            Bid::Pass,
+            Bid::new(6, BidType::NoTrump).unwrap(),
+            Bid::new(7, BidType::Trump(Suit::Clubs)).unwrap(),
+            Bid::new(7, BidType::Trump(Suit::Diamonds)).unwrap(),
+            Bid::new(7, BidType::Trump(Suit::Hearts)).unwrap(),
+            Bid::new(7, BidType::Trump(Suit::Spades)).unwrap(),
+            Bid::new(7, BidType::NoTrump).unwrap(),
+            Bid::Double,
+        ]
+    );
+    for bid in &bids {
+        assert_eq!(Ok(()), game.check_bid(&Player::East, *bid));
+    }
+
+    game.place_bid(&Player::East, Bid::Double);
+
+    // Redouble is legal, double is not
+    let bids = game.legal_bids(&Player::South);
+    assert!(bids.contains(&Bid::Redouble));
+    assert!(!bids.contains(&Bid::Double));
+    assert_eq!(
+        Err(BidError::CantDouble),
+        game.check_bid(&Player::South, Bid::Double)
+    );
+    assert_eq!(
+        Err(BidError::WrongBid),
+        game.check_bid(
+            &Player::South,
+            Bid::new(6, BidType::Trump(Suit::Spades)).unwrap()
+        )
+    );
+}
+
+#[test]
+fn game_legal_cards() {
+    let mut game = Game::new();
+    game.max_bid = Bid::new(3, BidType::NoTrump).unwrap();
+
+    game.player_cards = [
+        vec![
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::Two, Suit::Hearts),
+        ],
+        vec![
+            Card::new(Rank::King, Suit::Spades),
+            Card::new(Rank::Three, Suit::Hearts),
+            Card::new(Rank::Four, Suit::Spades),
+        ],
+        vec![
+            Card::new(Rank::Two, Suit::Clubs),
+            Card::new(Rank::Three, Suit::Clubs),
+        ],
+        vec![Card::new(Rank::Two, Suit::Diamonds)],
+    ];
+    assert!(game.legal_cards(&Player::North).is_empty());
+
+    game.state = GameState::Tricking;
+
+    // Leader can play any card
+    assert_eq!(game.legal_cards(&Player::North), game.player_cards[0]);
+    assert!(game.legal_cards(&Player::East).is_empty());
+
+    game.trick(&Player::North, &Card::new(Rank::Ace, Suit::Spades));
+
+    // East has to follow suit
+    assert_eq!(
+        game.legal_cards(&Player::East),
+        vec![
+            Card::new(Rank::King, Suit::Spades),
+            Card::new(Rank::Four, Suit::Spades),
+        ]
+    );
+    assert_eq!(
+        Err(TrickError::WrongCardSuit),
+        game.check_card(&Player::East, &Card::new(Rank::Three, Suit::Hearts))
+    );
+
+    game.trick(&Player::East, &Card::new(Rank::Four, Suit::Spades));
+
+    // South has no spades, so any card is fine
+    assert_eq!(game.legal_cards(&Player::South), game.player_cards[2]);
+}
+
+#[test]
+fn game_records_auction() {
+    let one_no_trump = Bid::new(1, BidType::NoTrump).unwrap();
+    let mut game = Game::new();
+    game.start();
+    let dealer = game.current_player;
+
+    game.place_bid(&dealer, one_no_trump);
+    // Out of turn bid is not recorded
+    game.place_bid(&dealer, Bid::Pass);
+    game.place_bid(&dealer.next(), Bid::Pass);
+    assert_eq!(game.auction, vec![one_no_trump, Bid::Pass]);
+
+    game.start();
+    assert!(game.auction.is_empty());
+}
@@ -0,0 +1,50 @@
+use common::hand::*;
+use common::*;
+
+fn hand(suits: [&[u8]; 4]) -> Vec<Card> {
+    [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs]
+        .into_iter()
+        .zip(suits)
+        .flat_map(|(suit, ranks)| {
+            ranks
+                .iter()
+                .map(move |rank| Card::new(Rank::from_u8(*rank).unwrap(), suit))
+        })
+        .collect()
+}
+
+#[test]
+fn hand_balanced() {
+    let cards = hand([&[14, 13, 11, 5, 2], &[12, 3], &[13, 7, 4], &[8, 7, 2]]);
+
+    assert_eq!(high_card_points(&cards), 13);
+    assert_eq!(suit_lengths(&cards), [3, 3, 2, 5]);
+    assert_eq!(shape_pattern(&cards), [5, 3, 3, 2]);
+    assert_eq!(shape_str(&cards), "5=2=3=3");
+    assert!(is_balanced(&cards));
+    assert!(is_semi_balanced(&cards));
+    assert_eq!(losing_trick_count(&cards), 8);
+    assert_eq!(quick_tricks(&cards), 2.5);
+}
+
+#[test]
+fn hand_semi_balanced() {
+    let cards = hand([&[14, 13, 5, 2], &[12, 3], &[13, 7], &[8, 7, 4, 3, 2]]);
+
+    assert_eq!(shape_pattern(&cards), [5, 4, 2, 2]);
+    assert!(!is_balanced(&cards));
+    assert!(is_semi_balanced(&cards));
+}
+
+#[test]
+fn hand_unbalanced() {
+    let cards = hand([&[], &[14, 12, 9, 8, 6, 5], &[13, 12], &[14, 7, 4, 3, 2]]);
+
+    assert_eq!(high_card_points(&cards), 15);
+    assert_eq!(shape_pattern(&cards), [6, 5, 2, 0]);
+    assert_eq!(shape_str(&cards), "0=6=2=5");
+    assert!(!is_balanced(&cards));
+    assert!(!is_semi_balanced(&cards));
+    assert_eq!(losing_trick_count(&cards), 4);
+    assert_eq!(quick_tricks(&cards), 3.5);
+}
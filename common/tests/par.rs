@@ -0,0 +1,83 @@
+use common::par::par;
+use common::solver::TrickTable;
+use common::*;
+
+/// Trick table in which both partners take the same number of tricks.
+/// `north_south` and `east_west` are given in order of `BidType::ALL`.
+fn table(north_south: [usize; 5], east_west: [usize; 5]) -> TrickTable {
+    let mut tricks = [[0; 4]; 5];
+    for strain in 0..5 {
+        tricks[strain] = [
+            north_south[strain],
+            east_west[strain],
+            north_south[strain],
+            east_west[strain],
+        ];
+    }
+    TrickTable { tricks }
+}
+
+#[test]
+fn par_passed_out() {
+    let table = table([6; 5], [6; 5]);
+    let par = par(&table, [false; 4], Player::North);
+    assert_eq!(par.contract, None);
+    assert_eq!(par.score, 0);
+}
+
+#[test]
+fn par_outbids_cheap_sacrifice() {
+    let table = table([6, 6, 6, 10, 7], [7, 7, 7, 3, 6]);
+    let par = par(&table, [false; 4], Player::North);
+
+    // 1 Spade would be outbid by 1 No Trump doubled going one down
+    let contract = par.contract.unwrap();
+    assert_eq!(
+        contract.bid,
+        Bid::new(2, BidType::Trump(Suit::Spades)).unwrap()
+    );
+    assert_eq!(contract.declarer, Player::North);
+    assert_eq!(contract.game_value, GameValue::Regular);
+    assert_eq!(contract.tricks, 10);
+    assert_eq!(par.score, 120);
+    assert_eq!(par.to_string(), "2S by North, NS 120");
+}
+
+#[test]
+fn par_sacrifice() {
+    let table = table([6, 6, 6, 12, 6], [1, 1, 10, 1, 1]);
+    let par = par(&table, [false; 4], Player::North);
+
+    // 7 Hearts doubled three down is cheaper than the small slam
+    let contract = par.contract.unwrap();
+    assert_eq!(
+        contract.bid,
+        Bid::new(7, BidType::Trump(Suit::Hearts)).unwrap()
+    );
+    assert_eq!(contract.declarer, Player::East);
+    assert_eq!(contract.game_value, GameValue::Doubled);
+    assert_eq!(par.score, 500);
+
+    // Vulnerable sacrifice costs more than the slam
+    let par = common::par::par(&table, [false, true, false, true], Player::North);
+    let contract = par.contract.unwrap();
+    assert_eq!(
+        contract.bid,
+        Bid::new(6, BidType::Trump(Suit::Spades)).unwrap()
+    );
+    assert_eq!(contract.declarer, Player::North);
+    assert_eq!(par.score, 680);
+}
+
+#[test]
+fn par_dealer_bids_first() {
+    let table = table([0, 0, 0, 0, 7], [0, 0, 0, 0, 7]);
+
+    let par_north = par(&table, [false; 4], Player::North);
+    assert_eq!(par_north.contract.unwrap().declarer, Player::North);
+    assert_eq!(par_north.score, 40);
+
+    let par_west = par(&table, [false; 4], Player::West);
+    assert_eq!(par_west.contract.unwrap().declarer, Player::West);
+    assert_eq!(par_west.score, -40);
+}
@@ -53,3 +53,10 @@ fn player_skip() {
     assert_eq!(player2.skip(3), player1);
     assert_eq!(player0.skip(4), player0);
 }
+
+#[test]
+fn player_all() {
+    for (index, player) in Player::ALL.iter().enumerate() {
+        assert_eq!(player.to_usize(), index);
+    }
+}
@@ -0,0 +1,33 @@
+use common::protocol::*;
+
+#[test]
+fn negotiate_rejects_old_clients() {
+    assert_eq!(
+        Protocol::negotiate(MIN_PROTOCOL_VERSION - 1, &Feature::ALL, &Feature::ALL),
+        None
+    );
+}
+
+#[test]
+fn negotiate_downgrades_newer_clients() {
+    let protocol = Protocol::negotiate(PROTOCOL_VERSION + 1, &[], &Feature::ALL).unwrap();
+    assert_eq!(protocol.version, PROTOCOL_VERSION);
+    assert!(protocol.features.is_empty());
+}
+
+#[test]
+fn negotiate_keeps_common_features() {
+    let client = [Feature::Kibitzing, Feature::Tournaments, Feature::Unknown];
+    let server = [Feature::Tournaments, Feature::TimeControl, Feature::Unknown];
+    let protocol = Protocol::negotiate(PROTOCOL_VERSION, &client, &server).unwrap();
+    assert_eq!(protocol.features, vec![Feature::Tournaments]);
+    assert!(protocol.supports(Feature::Tournaments));
+    assert!(!protocol.supports(Feature::Kibitzing));
+}
+
+#[test]
+fn default_protocol_is_oldest() {
+    let protocol = Protocol::default();
+    assert_eq!(protocol.version, MIN_PROTOCOL_VERSION);
+    assert!(protocol.features.is_empty());
+}
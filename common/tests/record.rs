@@ -0,0 +1,135 @@
+use common::record::DealRecord;
+use common::*;
+
+fn card(rank: u8, suit: Suit) -> Card {
+    Card::new(Rank::from_u8(rank).unwrap(), suit)
+}
+
+/// Two-card ending played in no trump after a short auction with East as the dealer
+fn record() -> DealRecord {
+    let hands = [
+        vec![card(14, Suit::Spades), card(2, Suit::Hearts)],
+        vec![card(13, Suit::Spades), card(3, Suit::Hearts)],
+        vec![card(12, Suit::Spades), card(4, Suit::Hearts)],
+        vec![card(11, Suit::Spades), card(5, Suit::Hearts)],
+    ];
+    DealRecord {
+        deal: Deal {
+            dealer: Player::East,
+            hands: hands.clone(),
+            vulnerable: [false; 4],
+        },
+        auction: vec![
+            Bid::new(1, BidType::NoTrump).unwrap(),
+            Bid::Pass,
+            Bid::Pass,
+            Bid::Pass,
+        ],
+        play: vec![
+            (Player::South, hands[2][0]),
+            (Player::West, hands[3][0]),
+            (Player::North, hands[0][0]),
+            (Player::East, hands[1][0]),
+            (Player::North, hands[0][1]),
+        ],
+    }
+}
+
+#[test]
+fn record_steps() {
+    let record = record();
+    assert_eq!(record.steps(), 9);
+    assert_eq!(record.caller(0), Player::East);
+    assert_eq!(record.caller(3), Player::North);
+}
+
+#[test]
+fn record_auction_and_play() {
+    let record = record();
+
+    assert!(record.auction_at(0).is_empty());
+    assert_eq!(record.auction_at(2), &record.auction[..2]);
+    assert_eq!(record.auction_at(9), &record.auction[..]);
+
+    assert!(record.played_at(4).is_empty());
+    assert_eq!(record.played_at(6), &record.play[..2]);
+    assert_eq!(record.played_at(100), &record.play[..]);
+}
+
+#[test]
+fn record_trick_at() {
+    let record = record();
+
+    assert!(record.trick_at(4).is_empty());
+    assert_eq!(record.trick_at(5), &record.play[..1]);
+    // A complete trick stays on the table until the next card is played
+    assert_eq!(record.trick_at(8), &record.play[..4]);
+    assert_eq!(record.trick_at(9), &record.play[4..]);
+}
+
+#[test]
+fn record_hands_at() {
+    let record = record();
+
+    assert_eq!(record.hands_at(4), record.deal.hands);
+
+    let hands = record.hands_at(6);
+    assert_eq!(hands[0].len(), 2);
+    assert_eq!(hands[2], vec![card(4, Suit::Hearts)]);
+    assert_eq!(hands[3], vec![card(5, Suit::Hearts)]);
+
+    let hands = record.hands_at(9);
+    assert!(hands[0].is_empty());
+    assert_eq!(hands[1], vec![card(3, Suit::Hearts)]);
+}
+
+#[test]
+fn game_records_play() {
+    let mut game = Game::new();
+    game.max_bid = Bid::new(1, BidType::NoTrump).unwrap();
+    game.state = GameState::Tricking;
+    game.player_cards = record().deal.hands;
+
+    let card = card(14, Suit::Spades);
+    game.trick(&Player::North, &card);
+
+    assert_eq!(game.record().play, vec![(Player::North, card)]);
+}
+
+#[test]
+fn record_contract_and_tricks() {
+    let record = record();
+
+    assert_eq!(
+        record.contract(),
+        Some((1, BidType::NoTrump, GameValue::Regular))
+    );
+    assert_eq!(record.declarer(), Some(Player::East));
+    // North's ace of spades takes the only complete trick
+    assert_eq!(record.trick_winners(), vec![Player::North]);
+}
+
+#[test]
+fn public_record_hides_hands_in_progress() {
+    let record = record();
+    let public = record.public();
+
+    assert!(!record.is_finished());
+    // Only the hand of West, the dummy, is shown after the opening lead
+    assert_eq!(public.deal.hands[3], record.deal.hands[3]);
+    for player in [Player::North, Player::East, Player::South] {
+        assert!(public.deal.hands[player.to_usize()].is_empty());
+    }
+    assert_eq!(public.play, record.play);
+}
+
+#[test]
+fn record_to_pbn() {
+    let pbn = record().to_pbn(3);
+
+    assert!(pbn.contains("[Board \"3\"]\n[Dealer \"E\"]\n[Vulnerable \"None\"]\n"));
+    assert!(pbn.contains("[Deal \"E:K.3.. Q.4.. J.5.. A.2..\"]\n"));
+    assert!(pbn.contains("[Declarer \"E\"]\n[Contract \"1NT\"]\n"));
+    assert!(pbn.contains("[Auction \"E\"]\n1NT Pass Pass Pass\n"));
+    assert!(pbn.contains("[Play \"S\"]\nSQ SJ SA SK\n- - H2 -\n"));
+}
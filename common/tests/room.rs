@@ -0,0 +1,13 @@
+use common::room::SpectatorView;
+use common::*;
+
+#[test]
+fn spectator_view_shows() {
+    let view = SpectatorView::Seat(Player::East);
+    assert!(view.shows(Player::East));
+    assert!(!view.shows(Player::West));
+
+    for player in Player::ALL {
+        assert!(SpectatorView::AllHands.shows(player));
+    }
+}
@@ -0,0 +1,58 @@
+use common::schema::{protocol_schema, protocol_schema_string};
+
+#[test]
+fn checked_in_schema_is_up_to_date() {
+    let checked_in = include_str!("../protocol.schema.json");
+    assert!(
+        checked_in == protocol_schema_string(),
+        "protocol.schema.json is out of date, run `cargo run -p common --bin protocol_schema`"
+    );
+}
+
+#[test]
+fn every_request_has_its_response() {
+    let schema = protocol_schema();
+    let responses = schema["server_responses"].as_object().unwrap();
+    for (msg_type, message) in schema["client_messages"].as_object().unwrap() {
+        let response = message["response"].as_str().unwrap();
+        assert!(
+            responses.contains_key(response),
+            "{} has no response {}",
+            msg_type,
+            response
+        );
+    }
+}
+
+#[test]
+fn every_message_is_in_schema() {
+    let schema = protocol_schema();
+    let in_schema = |msg_type: &str| {
+        [
+            "client_messages",
+            "server_responses",
+            "server_notifications",
+        ]
+        .iter()
+        .any(|group| schema[group].as_object().unwrap().contains_key(msg_type))
+    };
+
+    // Every `MessageTrait` implementation is in message.rs, with its `MSG_TYPE` on one line
+    let source = include_str!("../src/message.rs");
+    let msg_types: Vec<_> = source
+        .lines()
+        .filter_map(|line| {
+            line.trim()
+                .strip_prefix("const MSG_TYPE: &'static str = \"")
+        })
+        .map(|line| line.trim_end_matches("\";"))
+        .collect();
+    assert!(!msg_types.is_empty());
+    for msg_type in msg_types {
+        assert!(
+            in_schema(msg_type),
+            "{} is missing in `protocol_schema`",
+            msg_type
+        );
+    }
+}
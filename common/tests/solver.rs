@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use common::deal_generator::random_deal;
+use common::solver::{solve, solve_all};
+use common::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Held by the long tests, so that the timed one doesn't share the CPU with the others
+static LONG_TEST: Mutex<()> = Mutex::new(());
+
+fn long_test() -> MutexGuard<'static, ()> {
+    LONG_TEST.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn suit_cards(suit: Suit, ranks: &[u8]) -> Vec<Card> {
+    ranks
+        .iter()
+        .map(|rank| Card::new(Rank::from_u8(*rank).unwrap(), suit))
+        .collect()
+}
+
+#[test]
+fn solver_finesse() {
+    let hands = [
+        suit_cards(Suit::Spades, &[14, 12]),
+        suit_cards(Suit::Spades, &[13, 3]),
+        suit_cards(Suit::Spades, &[5, 4]),
+        suit_cards(Suit::Spades, &[7, 6]),
+    ];
+
+    for strain in BidType::ALL {
+        // King is caught only when East leads
+        assert_eq!(solve(&hands, strain, Player::North), 2);
+        assert_eq!(solve(&hands, strain, Player::East), 1);
+        assert_eq!(solve(&hands, strain, Player::South), 1);
+        assert_eq!(solve(&hands, strain, Player::West), 1);
+    }
+
+    let table = solve_all(&hands);
+    for strain in BidType::ALL {
+        for declarer in [Player::North, Player::East, Player::South, Player::West] {
+            assert_eq!(table.get(strain, declarer), solve(&hands, strain, declarer));
+        }
+    }
+}
+
+#[test]
+fn solver_ruff() {
+    let hands = [
+        vec![
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::Two, Suit::Hearts),
+        ],
+        suit_cards(Suit::Hearts, &[14, 13]),
+        suit_cards(Suit::Clubs, &[3, 2]),
+        suit_cards(Suit::Diamonds, &[3, 2]),
+    ];
+
+    assert_eq!(solve(&hands, BidType::NoTrump, Player::North), 0);
+    // North has to follow the first heart, but ruffs the second one
+    assert_eq!(
+        solve(&hands, BidType::Trump(Suit::Spades), Player::North),
+        1
+    );
+    assert_eq!(solve(&hands, BidType::Trump(Suit::Hearts), Player::East), 2);
+}
+
+#[test]
+fn solver_full_deal() {
+    // Every player holds a whole suit
+    let hands = [
+        suit_cards(Suit::Spades, &[2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]),
+        suit_cards(Suit::Hearts, &[2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]),
+        suit_cards(
+            Suit::Diamonds,
+            &[2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+        ),
+        suit_cards(Suit::Clubs, &[2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]),
+    ];
+
+    let table = solve_all(&hands);
+    // Opening leader runs his suit
+    for declarer in [Player::North, Player::East, Player::South, Player::West] {
+        assert_eq!(table.get(BidType::NoTrump, declarer), 0);
+    }
+    // Side holding trumps ruffs everything
+    assert_eq!(table.tricks[3], [13, 0, 13, 0]);
+    assert_eq!(table.tricks[2], [0, 13, 0, 13]);
+    assert_eq!(table.tricks[1], [13, 0, 13, 0]);
+    assert_eq!(table.tricks[0], [0, 13, 0, 13]);
+}
+
+/// Plays out every line of play, remembering results of positions at the start of a trick.
+/// Returns tricks taken by North-South.
+fn brute_force(
+    hands: &mut [Vec<Card>; 4],
+    trump: Option<Suit>,
+    leader: usize,
+    memo: &mut HashMap<([u64; 4], usize), usize>,
+) -> usize {
+    if hands[leader].is_empty() {
+        return 0;
+    }
+    let key = (
+        hands.each_ref().map(|hand| {
+            hand.iter().fold(0, |acc, card| {
+                acc | 1 << (card.suit as u64 * 16 + card.rank.to_u8() as u64)
+            })
+        }),
+        leader,
+    );
+    if let Some(&tricks) = memo.get(&key) {
+        return tricks;
+    }
+    let tricks = play_trick(hands, trump, leader, &mut Vec::new(), memo);
+    memo.insert(key, tricks);
+    tricks
+}
+
+fn play_trick(
+    hands: &mut [Vec<Card>; 4],
+    trump: Option<Suit>,
+    leader: usize,
+    trick: &mut Vec<Card>,
+    memo: &mut HashMap<([u64; 4], usize), usize>,
+) -> usize {
+    if trick.len() == 4 {
+        let beats = |card: &Card, other: &Card| {
+            if card.suit == other.suit {
+                card.rank.to_u8() > other.rank.to_u8()
+            } else {
+                Some(card.suit) == trump
+            }
+        };
+        let winning = (1..4).fold(0, |winning, pos| {
+            if beats(&trick[pos], &trick[winning]) {
+                pos
+            } else {
+                winning
+            }
+        });
+        let winner = (leader + winning) % 4;
+        return brute_force(hands, trump, winner, memo) + usize::from(winner.is_multiple_of(2));
+    }
+
+    let player = (leader + trick.len()) % 4;
+    let lead_suit = trick.first().map(|lead| lead.suit);
+    let follows = |card: &Card| lead_suit.is_none_or(|suit| card.suit == suit);
+    let can_follow = hands[player].iter().any(follows);
+    let mut best: Option<usize> = None;
+    for index in 0..hands[player].len() {
+        if can_follow && !follows(&hands[player][index]) {
+            continue;
+        }
+        let card = hands[player].remove(index);
+        trick.push(card);
+        let tricks = play_trick(hands, trump, leader, trick, memo);
+        trick.pop();
+        hands[player].insert(index, card);
+
+        best = Some(match best {
+            None => tricks,
+            Some(best) if player.is_multiple_of(2) => best.max(tricks),
+            Some(best) => best.min(tricks),
+        });
+    }
+    // Unwrap is valid, as the player has cards left
+    best.unwrap()
+}
+
+#[test]
+fn solver_matches_brute_force() {
+    let _serial = long_test();
+    let mut rng = StdRng::seed_from_u64(28);
+    for cards in 3..=5 {
+        for _ in 0..30 {
+            let deal = random_deal(&mut rng);
+            let mut hands = deal.map(|hand| hand[..cards].to_vec());
+            let table = solve_all(&hands);
+
+            for strain in BidType::ALL {
+                let trump = match strain {
+                    BidType::Trump(suit) => Some(suit),
+                    BidType::NoTrump => None,
+                };
+                let mut memo = HashMap::new();
+                for declarer in [Player::North, Player::East, Player::South, Player::West] {
+                    let leader = declarer.next().to_usize();
+                    let north_south = brute_force(&mut hands, trump, leader, &mut memo);
+                    let expected = if declarer.to_usize().is_multiple_of(2) {
+                        north_south
+                    } else {
+                        cards - north_south
+                    };
+                    assert_eq!(
+                        table.get(strain, declarer),
+                        expected,
+                        "{:?} declaring {:?} in {:?}",
+                        declarer,
+                        strain,
+                        hands
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn solver_full_deal_time() {
+    let _serial = long_test();
+    // Par is solved while the deal is played and has to be ready when it ends, also in unoptimized builds
+    let mut rng = StdRng::seed_from_u64(13);
+    for _ in 0..3 {
+        let hands = random_deal(&mut rng);
+        let start = Instant::now();
+        solve_all(&hands);
+        assert!(
+            start.elapsed() < Duration::from_secs(20),
+            "solving {:?} took {:?}",
+            hands,
+            start.elapsed()
+        );
+    }
+}
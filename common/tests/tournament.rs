@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use common::tournament::*;
+
+/// Checks that every pair plays exactly once in every round and returns all pairs of opponents.
+fn meetings(movement: Movement, pairs: usize) -> Vec<(usize, usize)> {
+    let mut meetings = Vec::new();
+    for round in 0..movement.rounds(pairs) {
+        let tables = movement.round(pairs, round);
+        assert_eq!(tables.len(), movement.tables(pairs));
+
+        let mut seated = HashSet::new();
+        for table in tables {
+            assert!(seated.insert(table.north_south));
+            assert!(seated.insert(table.east_west));
+            let pair = (
+                table.north_south.min(table.east_west),
+                table.north_south.max(table.east_west),
+            );
+            meetings.push(pair);
+        }
+        assert_eq!(seated.len(), pairs);
+    }
+    meetings
+}
+
+#[test]
+fn movement_supports() {
+    assert!(Movement::Mitchell.supports(2));
+    assert!(Movement::Mitchell.supports(10));
+    assert!(!Movement::Mitchell.supports(7));
+    assert!(!Movement::Howell.supports(2));
+    assert!(Movement::Howell.supports(8));
+    assert!(!Movement::Howell.supports(9));
+}
+
+#[test]
+fn mitchell_meets_every_opponent() {
+    for tables in 1..=6 {
+        let pairs = 2 * tables;
+        let meetings = meetings(Movement::Mitchell, pairs);
+        assert_eq!(meetings.len(), tables * tables);
+
+        let unique: HashSet<_> = meetings.iter().collect();
+        assert_eq!(unique.len(), meetings.len());
+        for (north_south, east_west) in meetings {
+            assert!(north_south < tables);
+            assert!(east_west >= tables);
+        }
+    }
+}
+
+#[test]
+fn mitchell_north_south_stays() {
+    for round in 0..4 {
+        for table in Movement::Mitchell.round(8, round) {
+            assert_eq!(table.north_south, table.table);
+        }
+    }
+}
+
+#[test]
+fn howell_meets_every_pair_once() {
+    for pairs in [4, 6, 8, 10] {
+        let meetings = meetings(Movement::Howell, pairs);
+        assert_eq!(meetings.len(), pairs * (pairs - 1) / 2);
+
+        let unique: HashSet<_> = meetings.iter().collect();
+        assert_eq!(unique.len(), meetings.len());
+    }
+}
+
+#[test]
+fn rankings_matchpoints() {
+    let score = |board_no, north_south, east_west, score| BoardScore {
+        board_no,
+        north_south,
+        east_west,
+        score,
+    };
+    let scores = [
+        score(1, 0, 2, 420),
+        score(1, 1, 3, 170),
+        score(2, 0, 2, -100),
+        score(2, 1, 3, -100),
+    ];
+
+    let rankings = rankings(Movement::Howell, 4, &scores);
+    let find = |pair| {
+        *rankings
+            .iter()
+            .find(|ranking| ranking.pair == pair)
+            .unwrap()
+    };
+
+    assert_eq!(find(0).matchpoints, 3);
+    assert_eq!(find(1).matchpoints, 1);
+    assert_eq!(find(2).matchpoints, 1);
+    assert_eq!(find(3).matchpoints, 3);
+    assert_eq!(find(0).top, 4);
+    assert_eq!(find(0).percentage(), 75.0);
+
+    assert!(rankings[0].pair == 0 || rankings[0].pair == 3);
+    assert!(rankings
+        .windows(2)
+        .all(|pair| pair[0].percentage() >= pair[1].percentage()));
+}
+
+#[test]
+fn rankings_mitchell_fields() {
+    let score = |board_no, north_south, east_west, score| BoardScore {
+        board_no,
+        north_south,
+        east_west,
+        score,
+    };
+    // Pairs 0 and 1 sit North-South, pairs 2 and 3 East-West
+    let scores = [
+        score(1, 0, 2, 420),
+        score(1, 1, 3, 170),
+        score(2, 0, 3, 50),
+        score(2, 1, 2, 100),
+    ];
+
+    let rankings = rankings(Movement::Mitchell, 4, &scores);
+    let order: Vec<_> = rankings
+        .iter()
+        .map(|ranking| (ranking.field, ranking.pair))
+        .collect();
+    // North-South pairs are ranked first, then East-West pairs
+    assert_eq!(order, [(0, 0), (0, 1), (1, 3), (1, 2)]);
+    assert_eq!(rankings[0].percentage(), 50.0);
+    assert_eq!(rankings[2].percentage(), 100.0);
+}
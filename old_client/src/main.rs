@@ -525,6 +525,11 @@ async fn main() {
                     room_info: RoomInfo {
                         id: RoomId::new(room_name.into()),
                         visibility: Visibility::Public,
+                        deal_constraints: None,
+                        time_control: None,
+                        allow_kibitzing: false,
+                        broadcast_delay_secs: None,
+                        seed: None,
                     },
                 };
 
@@ -660,7 +665,11 @@ async fn main() {
                     socket
                         .emit(
                             MakeBidMessage::MSG_TYPE,
-                            to_string(&MakeBidMessage { bid: Bid::Pass }).unwrap(),
+                            to_string(&MakeBidMessage {
+                                bid: Bid::Pass,
+                                explanation: None,
+                            })
+                            .unwrap(),
                         )
                         .await
                         .unwrap();
@@ -669,7 +678,11 @@ async fn main() {
                     socket
                         .emit(
                             MakeBidMessage::MSG_TYPE,
-                            to_string(&MakeBidMessage { bid: Bid::Double }).unwrap(),
+                            to_string(&MakeBidMessage {
+                                bid: Bid::Double,
+                                explanation: None,
+                            })
+                            .unwrap(),
                         )
                         .await
                         .unwrap();
@@ -678,7 +691,11 @@ async fn main() {
                     socket
                         .emit(
                             MakeBidMessage::MSG_TYPE,
-                            to_string(&MakeBidMessage { bid: Bid::Redouble }).unwrap(),
+                            to_string(&MakeBidMessage {
+                                bid: Bid::Redouble,
+                                explanation: None,
+                            })
+                            .unwrap(),
                         )
                         .await
                         .unwrap();
@@ -711,7 +728,11 @@ async fn main() {
                 socket
                     .emit(
                         MakeBidMessage::MSG_TYPE,
-                        to_string(&MakeBidMessage { bid }).unwrap(),
+                        to_string(&MakeBidMessage {
+                            bid,
+                            explanation: None,
+                        })
+                        .unwrap(),
                     )
                     .await
                     .unwrap();
@@ -0,0 +1,243 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::Response,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use common::{
+    message::server_notification::ServerMessageNotification,
+    room::{RoomId, Visibility},
+    GameState,
+};
+use serde::{Deserialize, Serialize};
+use socketioxide::{extract::SocketRef, SocketIo};
+use subtle::ConstantTimeEq;
+use tokio::time::sleep;
+use tracing::info;
+
+use crate::{
+    api::RoomListing,
+    state::ServerState,
+    utils::{notify_all, send},
+    ClientData,
+};
+
+/// Time the games in progress get to finish after draining starts, unless the request gives another
+const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 30 * 60;
+
+#[derive(Clone)]
+struct Admin {
+    state: ServerState,
+    io: SocketIo,
+}
+
+/// Connected user
+#[derive(Serialize)]
+struct UserListing {
+    username: String,
+    /// Room the user is in, `None` in the lobby
+    room: Option<RoomId>,
+}
+
+#[derive(Serialize)]
+struct AdminRoomListing {
+    #[serde(flatten)]
+    room: RoomListing,
+    visibility: Visibility,
+}
+
+#[derive(Deserialize)]
+struct DrainOptions {
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct DrainStatus {
+    /// Rooms whose games are still played, the server shuts down once they finish
+    rooms_left: usize,
+    /// Time after which the rooms left are closed
+    timeout_secs: u64,
+}
+
+/// Admin HTTP API, nested under `/admin`. Every request needs the `Authorization: Bearer <token>` header:
+/// - `GET /users` lists connected users with the rooms they are in
+/// - `GET /rooms` lists all rooms, private ones too
+/// - `DELETE /rooms/:id` closes a room, the game finishes for its users
+/// - `POST /users/:username/kick` disconnects a user
+/// - `POST /broadcast` sends a `ServerMessageNotification` given as the body to every client
+/// - `POST /drain?timeout_secs=<secs>` stops accepting new users and games, the server shuts down once the games
+///   in progress finish. Rooms left after the timeout, 30 minutes by default, are closed.
+pub fn router(state: ServerState, io: SocketIo, token: String) -> Router {
+    Router::new()
+        .route("/users", get(list_users))
+        .route("/rooms", get(list_rooms))
+        .route("/rooms/:id", delete(close_room))
+        .route("/users/:username/kick", post(kick_user))
+        .route("/broadcast", post(broadcast))
+        .route("/drain", post(drain))
+        .layer(middleware::from_fn_with_state(
+            Arc::<str>::from(token),
+            authorize,
+        ))
+        .with_state(Admin { state, io })
+}
+
+async fn authorize(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        // Compared in constant time, so that the time of the comparison doesn't reveal the token
+        .is_some_and(|given| bool::from(given.as_bytes().ct_eq(token.as_bytes())));
+    if !authorized {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(next.run(request).await)
+}
+
+/// Sockets of logged in users with their data
+fn logged_in(io: &SocketIo) -> Vec<(SocketRef, ClientData)> {
+    io.sockets()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|s| {
+            let client_data = s.extensions.get::<ClientData>()?;
+            Some((s, client_data))
+        })
+        .collect()
+}
+
+async fn list_users(State(admin): State<Admin>) -> Json<Vec<UserListing>> {
+    let users = logged_in(&admin.io)
+        .into_iter()
+        .map(|(_, client_data)| UserListing {
+            username: client_data.user.get_username().into(),
+            room: client_data.room.map(|room| room.info.id),
+        })
+        .collect();
+    Json(users)
+}
+
+async fn list_rooms(State(admin): State<Admin>) -> Json<Vec<AdminRoomListing>> {
+    let rooms = admin
+        .state
+        .read()
+        .await
+        .get_rooms()
+        .iter()
+        .map(|room| AdminRoomListing {
+            room: RoomListing::new(room),
+            visibility: room.info.visibility.clone(),
+        })
+        .collect();
+    Json(rooms)
+}
+
+async fn close_room(State(admin): State<Admin>, Path(id): Path<String>) -> StatusCode {
+    let room_id = RoomId::new(id.into());
+    let Some(room) = admin.state.read().await.get_room(&room_id) else {
+        return StatusCode::NOT_FOUND;
+    };
+
+    room.close(
+        admin.io.clone(),
+        admin.state.clone(),
+        "The room was closed by the administrator".into(),
+    );
+    info!(
+        "Room \"{}\" was closed by the administrator",
+        room_id.as_str()
+    );
+    StatusCode::NO_CONTENT
+}
+
+async fn kick_user(State(admin): State<Admin>, Path(username): Path<String>) -> StatusCode {
+    let Some((s, _)) = logged_in(&admin.io)
+        .into_iter()
+        .find(|(_, client_data)| client_data.user.get_username() == username)
+    else {
+        return StatusCode::NOT_FOUND;
+    };
+
+    send(
+        &s,
+        &ServerMessageNotification {
+            message: "You were disconnected by the administrator".into(),
+        },
+    );
+    s.disconnect().ok();
+    info!("User \"{}\" was kicked by the administrator", username);
+    StatusCode::NO_CONTENT
+}
+
+async fn broadcast(
+    State(admin): State<Admin>,
+    Json(message): Json<ServerMessageNotification>,
+) -> StatusCode {
+    notify_all(&admin.io, &message);
+    info!("Administrator broadcast \"{}\"", message.message);
+    StatusCode::NO_CONTENT
+}
+
+async fn drain(
+    State(admin): State<Admin>,
+    Query(options): Query<DrainOptions>,
+) -> Json<DrainStatus> {
+    let timeout_secs = options.timeout_secs.unwrap_or(DEFAULT_DRAIN_TIMEOUT_SECS);
+    notify_all(
+        &admin.io,
+        &ServerMessageNotification {
+            message: "The server is shutting down once the games in progress finish".into(),
+        },
+    );
+
+    // Rooms still waiting for players would never finish
+    let rooms = {
+        let mut state = admin.state.write().await;
+        state.start_draining();
+        state.get_rooms()
+    };
+    let (waiting, playing): (Vec<_>, Vec<_>) = rooms
+        .into_iter()
+        .partition(|room| room.public_state().state == GameState::WaitingForPlayers);
+    for room in waiting {
+        room.close(
+            admin.io.clone(),
+            admin.state.clone(),
+            "The server is shutting down".into(),
+        );
+    }
+
+    // Games abandoned by their players would never finish either
+    let Admin { state, io } = admin;
+    tokio::spawn(async move {
+        sleep(Duration::from_secs(timeout_secs)).await;
+        let rooms = state.read().await.get_rooms();
+        for room in rooms {
+            room.close(
+                io.clone(),
+                state.clone(),
+                "The server is shutting down".into(),
+            );
+        }
+    });
+
+    info!(
+        "Server is draining, {} rooms left, closing them in {} seconds",
+        playing.len(),
+        timeout_secs
+    );
+    Json(DrainStatus {
+        rooms_left: playing.len(),
+        timeout_secs,
+    })
+}
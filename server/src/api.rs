@@ -0,0 +1,187 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use common::{
+    record::DealRecord,
+    room::{RoomId, RoomInfo, Visibility},
+    user::User,
+    GameState, Player,
+};
+use serde::Serialize;
+
+use crate::{
+    room_actor::RoomHandle,
+    state::{RoomState, ServerState},
+};
+
+/// Public part of the state of a room, published by its actor after every command
+#[derive(Serialize, Debug, Clone)]
+pub struct PublicRoomState {
+    pub info: RoomInfo,
+    pub seats: [Option<User>; 4],
+    /// Number of users in the room, players and spectators
+    pub users: usize,
+    pub state: GameState,
+    pub current_player: Player,
+    pub points: [usize; 4],
+    pub game_wins: [usize; 4],
+    /// Current deal with the hidden hands left out, `None` before the first deal
+    pub deal: Option<DealRecord>,
+}
+
+impl PublicRoomState {
+    pub fn new(room: &RoomState) -> Self {
+        let game = &room.game;
+        let deal = match game.state {
+            GameState::WaitingForPlayers => None,
+            _ => Some(game.record().public()),
+        };
+        Self {
+            info: room.info.clone(),
+            seats: room.get_player_positions(),
+            users: room.user_count(),
+            state: game.state,
+            current_player: game.current_player,
+            points: game.points,
+            game_wins: game.game_wins,
+            deal,
+        }
+    }
+}
+
+/// Entry of the room list
+#[derive(Serialize)]
+pub struct RoomListing {
+    id: RoomId,
+    seats: [Option<User>; 4],
+    users: usize,
+    state: GameState,
+}
+
+impl RoomListing {
+    pub fn new(room: &RoomHandle) -> Self {
+        let public = room.public_state();
+        Self {
+            id: public.info.id,
+            seats: public.seats,
+            users: public.users,
+            state: public.state,
+        }
+    }
+}
+
+/// Read-only HTTP API for scripts, nested under `/api`:
+/// - `GET /rooms` lists public rooms with their occupancy
+/// - `GET /rooms/:id` returns the public state of a public room, with the delay of its broadcast if it has one
+/// - `GET /rooms/:id/deals` returns records of the deals finished in a room, `/rooms/:id/deals.pbn` the same in PBN
+/// - `GET /rooms/:id/deals/:no` returns a single record, counted from 1, `/rooms/:id/deals/:no/pbn` in PBN
+///
+/// Records of boards of a team match or a tournament are left out until every table played the board.
+pub fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/rooms", get(list_rooms))
+        .route("/rooms/:id", get(get_room))
+        .route("/rooms/:id/deals", get(get_deals))
+        .route("/rooms/:id/deals.pbn", get(get_deals_pbn))
+        .route("/rooms/:id/deals/:no", get(get_deal))
+        .route("/rooms/:id/deals/:no/pbn", get(get_deal_pbn))
+        .with_state(state)
+}
+
+fn room_id(id: String) -> RoomId {
+    RoomId::new(id.into())
+}
+
+fn pbn_response(pbn: String) -> Response {
+    (
+        [(header::CONTENT_TYPE, "application/x-pbn; charset=utf-8")],
+        pbn,
+    )
+        .into_response()
+}
+
+async fn list_rooms(State(state): State<ServerState>) -> Json<Vec<RoomListing>> {
+    let state = state.read().await;
+    let rooms = state
+        .get_room_list()
+        .iter()
+        .filter_map(|id| state.get_room(id))
+        .map(|room| RoomListing::new(&room))
+        .collect();
+    Json(rooms)
+}
+
+async fn get_room(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> Result<Json<PublicRoomState>, StatusCode> {
+    let room = state
+        .read()
+        .await
+        .get_room(&room_id(id))
+        .filter(|room| room.info.visibility == Visibility::Public)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(room.published_state()))
+}
+
+async fn deal_records(
+    state: &ServerState,
+    id: String,
+) -> Result<Vec<(usize, DealRecord)>, StatusCode> {
+    state
+        .read()
+        .await
+        .get_deal_records(&room_id(id))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn get_deals(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<DealRecord>>, StatusCode> {
+    let records = deal_records(&state, id).await?;
+    Ok(Json(
+        records.into_iter().map(|(_, record)| record).collect(),
+    ))
+}
+
+async fn get_deals_pbn(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> Result<Response, StatusCode> {
+    let records = deal_records(&state, id).await?;
+    let pbn = records
+        .iter()
+        .map(|(no, record)| record.to_pbn(*no))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(pbn_response(pbn))
+}
+
+async fn deal_record(state: &ServerState, id: String, no: usize) -> Result<DealRecord, StatusCode> {
+    let records = deal_records(state, id).await?;
+    records
+        .into_iter()
+        .find(|(record_no, _)| *record_no == no)
+        .map(|(_, record)| record)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn get_deal(
+    State(state): State<ServerState>,
+    Path((id, no)): Path<(String, usize)>,
+) -> Result<Json<DealRecord>, StatusCode> {
+    deal_record(&state, id, no).await.map(Json)
+}
+
+async fn get_deal_pbn(
+    State(state): State<ServerState>,
+    Path((id, no)): Path<(String, usize)>,
+) -> Result<Response, StatusCode> {
+    let record = deal_record(&state, id, no).await?;
+    Ok(pbn_response(record.to_pbn(no)))
+}
@@ -0,0 +1,156 @@
+//! Command line client of the admin API of a running server, started with `--admin-token`.
+
+use std::process::ExitCode;
+
+use clap::{Arg, ArgMatches, Command};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+
+fn cli() -> Command {
+    Command::new("bridge-admin")
+        .arg(
+            Arg::new("port")
+                .short('p')
+                .long("port")
+                .value_name("PORT")
+                .help("Port of the server")
+                .default_value("3000"),
+        )
+        .arg(
+            Arg::new("server_ip")
+                .short('s')
+                .long("server_ip")
+                .value_name("SERVER_IP")
+                .help("Server IP to connect to")
+                .default_value("localhost"),
+        )
+        .arg(
+            Arg::new("protocol")
+                .short('g')
+                .long("protocol")
+                .value_name("PROTOCOL")
+                .help("Protocol to use (http or https)")
+                .default_value("http"),
+        )
+        .arg(
+            Arg::new("token")
+                .short('t')
+                .long("token")
+                .value_name("TOKEN")
+                .env("BRIDGE_ADMIN_TOKEN")
+                .help("Admin token the server was started with")
+                .required(true),
+        )
+        .subcommand_required(true)
+        .subcommand(Command::new("users").about("List connected users"))
+        .subcommand(Command::new("rooms").about("List all rooms"))
+        .subcommand(
+            Command::new("close-room")
+                .about("Close a room, the game finishes for its users")
+                .arg(Arg::new("room").required(true)),
+        )
+        .subcommand(
+            Command::new("kick")
+                .about("Disconnect a user")
+                .arg(Arg::new("username").required(true)),
+        )
+        .subcommand(
+            Command::new("broadcast")
+                .about("Send a message to every connected user")
+                .arg(Arg::new("message").required(true)),
+        )
+        .subcommand(
+            Command::new("drain")
+                .about("Stop accepting new users and games, shut down once the games in progress finish")
+                .arg(
+                    Arg::new("timeout")
+                        .long("timeout")
+                        .value_name("SECS")
+                        .help("Close the rooms left after this time [default: 1800]"),
+                ),
+        )
+}
+
+/// Method, path under `/admin` and body of the request of a subcommand
+fn request(command: &str, args: &ArgMatches) -> (Method, String, Option<Value>) {
+    let arg = |name: &str| args.get_one::<String>(name).unwrap();
+    match command {
+        "users" => (Method::GET, "users".into(), None),
+        "rooms" => (Method::GET, "rooms".into(), None),
+        "close-room" => (Method::DELETE, format!("rooms/{}", arg("room")), None),
+        "kick" => (
+            Method::POST,
+            format!("users/{}/kick", arg("username")),
+            None,
+        ),
+        "broadcast" => (
+            Method::POST,
+            "broadcast".into(),
+            Some(json!({ "message": arg("message") })),
+        ),
+        "drain" => match args.get_one::<String>("timeout") {
+            Some(timeout) => (
+                Method::POST,
+                format!("drain?timeout_secs={}", timeout),
+                None,
+            ),
+            None => (Method::POST, "drain".into(), None),
+        },
+        _ => unreachable!("subcommand not defined in cli()"),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = cli().get_matches();
+
+    let port = args.get_one::<String>("port").unwrap();
+    let host = args.get_one::<String>("server_ip").unwrap();
+    let protocol = args.get_one::<String>("protocol").unwrap();
+    let token = args.get_one::<String>("token").unwrap();
+
+    let (command, command_args) = args.subcommand().unwrap();
+    let (method, path, body) = request(command, command_args);
+    let url = format!("{}://{}:{}/admin/{}", protocol, host, port, path);
+
+    let mut request = reqwest::Client::new()
+        .request(method, &url)
+        .bearer_auth(token);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(err) => {
+            eprintln!("Can't reach the server: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match response.status() {
+        StatusCode::NO_CONTENT => ExitCode::SUCCESS,
+        status if status.is_success() => match response.json::<Value>().await {
+            Ok(json) => {
+                println!("{}", serde_json::to_string_pretty(&json).unwrap());
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("Invalid response: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+        StatusCode::UNAUTHORIZED => {
+            eprintln!("Wrong admin token");
+            ExitCode::FAILURE
+        }
+        StatusCode::NOT_FOUND => {
+            eprintln!("Not found, or the server runs without an admin token");
+            ExitCode::FAILURE
+        }
+        status => {
+            eprintln!("Request failed: {}", status);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use common::room::RoomId;
+use socketioxide::SocketIo;
+use tokio::{
+    sync::{
+        mpsc::{unbounded_channel, UnboundedSender},
+        watch,
+    },
+    time::{sleep_until, Instant},
+};
+
+use crate::{handlers::Group, utils::SendableNotification};
+
+type DelayedNotification = (Instant, Box<dyn SendableNotification + Send + Sync>);
+
+/// Group of the audience watching the broadcast of a room
+pub fn audience_group(room_id: &RoomId) -> Group {
+    Group(format!("broadcast:{}", room_id.as_str()))
+}
+
+/// Copy of a watched value that lags `delay` behind it, e.g. the public state of a broadcast room
+pub fn delayed<T>(mut receiver: watch::Receiver<T>, delay: Duration) -> watch::Receiver<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let (delayed_sender, delayed) = watch::channel(receiver.borrow().clone());
+    let (sender, mut queue) = unbounded_channel::<(Instant, T)>();
+
+    tokio::spawn(async move {
+        while receiver.changed().await.is_ok() {
+            let value = receiver.borrow_and_update().clone();
+            sender.send((Instant::now() + delay, value)).ok();
+        }
+    });
+    tokio::spawn(async move {
+        while let Some((due, value)) = queue.recv().await {
+            sleep_until(due).await;
+            delayed_sender.send_replace(value);
+        }
+    });
+
+    delayed
+}
+
+/// Re-emits game notifications of a room to its audience after a delay, so that the audience can't help the players.
+/// Notifications keep their order, the audience gets the remaining ones even after the room is removed.
+pub struct Broadcast {
+    sender: UnboundedSender<DelayedNotification>,
+    delay: Duration,
+}
+
+impl Broadcast {
+    pub fn new(io: SocketIo, room_id: &RoomId, delay: Duration) -> Self {
+        let audience = audience_group(room_id);
+        let (sender, mut receiver) = unbounded_channel::<DelayedNotification>();
+
+        tokio::spawn(async move {
+            while let Some((due, notification)) = receiver.recv().await {
+                sleep_until(due).await;
+                notification.emit_to(io.within(audience.clone()));
+            }
+        });
+
+        Self { sender, delay }
+    }
+
+    /// Schedules the notification to be sent to the audience
+    pub fn push(&self, notification: Box<dyn SendableNotification + Send + Sync>) {
+        self.sender
+            .send((Instant::now() + self.delay, notification))
+            .ok();
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+
+use common::{
+    room::{TimeControl, TimeLeft},
+    Player,
+};
+
+/// Tracks time of players in a room with time limits.
+pub struct Clock {
+    control: TimeControl,
+    /// Time bank left for each player in the current deal
+    banks: [Duration; 4],
+    /// Player asked to move and the moment they were asked
+    turn: Option<(Player, Instant)>,
+    /// Incremented with every started move, so that a timer can tell whether its move is still pending
+    move_no: u64,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        Self {
+            control,
+            banks: [Duration::from_secs(control.time_bank_secs); 4],
+            turn: None,
+            move_no: 0,
+        }
+    }
+
+    fn per_move(&self) -> Duration {
+        Duration::from_secs(self.control.per_move_secs)
+    }
+
+    /// Refills time banks of all players, should be called at the start of every deal
+    pub fn reset_banks(&mut self) {
+        self.banks = [Duration::from_secs(self.control.time_bank_secs); 4];
+        self.turn = None;
+    }
+
+    /// Starts timing the move of `player`.
+    /// Returns the number of the move, time left for the player and the time after which the move times out.
+    pub fn start_move(&mut self, player: Player) -> (u64, TimeLeft, Duration) {
+        self.move_no += 1;
+        self.turn = Some((player, Instant::now()));
+
+        let bank = self.banks[player.to_usize()];
+        let time_left = TimeLeft {
+            move_ms: self.per_move().as_millis() as u64,
+            bank_ms: bank.as_millis() as u64,
+        };
+        (self.move_no, time_left, self.per_move() + bank)
+    }
+
+    /// Stops timing the current move, time over the per-move limit is taken from the player's bank
+    pub fn finish_move(&mut self) {
+        let Some((player, started)) = self.turn.take() else {
+            return;
+        };
+        let overtime = started.elapsed().saturating_sub(self.per_move());
+        let bank = &mut self.banks[player.to_usize()];
+        *bank = bank.saturating_sub(overtime);
+    }
+
+    /// Checks whether the move with given number is still waiting for the player
+    pub fn is_pending(&self, move_no: u64) -> bool {
+        self.turn.is_some() && self.move_no == move_no
+    }
+}
@@ -0,0 +1,287 @@
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use axum::http::HeaderValue;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use common::message::server_response::LoginResponse;
+use serde::Deserialize;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Settings of the server, read from a TOML file, see `server/config.example.toml`.
+/// Every setting has a default, so the file only needs the changed ones.
+/// Top-level settings can be overridden on the command line or by environment variables.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub bind_address: IpAddr,
+    pub port: u16,
+    /// Port of the newline-delimited JSON transport, disabled when `None`
+    pub tcp_port: Option<u16>,
+    /// Directory of the browser client
+    pub static_dir: PathBuf,
+    /// Origins allowed to make cross-origin requests, any origin when `None`
+    pub cors_origins: Option<Vec<String>>,
+    /// Token of the admin API under `/admin`, the API is disabled when `None`
+    pub admin_token: Option<String>,
+    /// Directory where records of finished deals are kept across restarts, they are kept in memory only when `None`
+    pub data_dir: Option<PathBuf>,
+    pub pacing: Pacing,
+    pub usernames: UsernameRules,
+    pub rooms: RoomLimits,
+    pub gateway: GatewayLimits,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bind_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            port: 3000,
+            tcp_port: None,
+            static_dir: "dist".into(),
+            cors_origins: None,
+            admin_token: None,
+            data_dir: None,
+            pacing: Pacing::default(),
+            usernames: UsernameRules::default(),
+            rooms: RoomLimits::default(),
+            gateway: GatewayLimits::default(),
+        }
+    }
+}
+
+/// Command line of the server, every option can also be given by its environment variable
+pub fn cli() -> Command {
+    Command::new("bridge-server")
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .value_name("FILE")
+                .env("BRIDGE_CONFIG")
+                .help("TOML configuration file, the defaults are used without it")
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("bind")
+                .long("bind")
+                .value_name("ADDRESS")
+                .env("BRIDGE_BIND")
+                .help("Address to listen on [default: 0.0.0.0]")
+                .value_parser(value_parser!(IpAddr)),
+        )
+        .arg(
+            Arg::new("port")
+                .short('p')
+                .long("port")
+                .value_name("PORT")
+                .env("BRIDGE_PORT")
+                .help("Port to listen on [default: 3000]")
+                .value_parser(value_parser!(u16)),
+        )
+        .arg(
+            Arg::new("tcp-port")
+                .long("tcp-port")
+                .value_name("PORT")
+                .env("BRIDGE_TCP_PORT")
+                .help("Port to listen on for clients sending newline-delimited JSON over TCP")
+                .value_parser(value_parser!(u16)),
+        )
+        .arg(
+            Arg::new("static-dir")
+                .long("static-dir")
+                .value_name("DIR")
+                .env("BRIDGE_STATIC_DIR")
+                .help("Directory of the browser client [default: dist]")
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("cors-origin")
+                .long("cors-origin")
+                .value_name("ORIGIN")
+                .env("BRIDGE_CORS_ORIGINS")
+                .help(
+                    "Origin allowed to make cross-origin requests, can be repeated [default: any]",
+                )
+                .action(ArgAction::Append)
+                .value_delimiter(','),
+        )
+        .arg(
+            Arg::new("admin-token")
+                .long("admin-token")
+                .value_name("TOKEN")
+                .env("BRIDGE_ADMIN_TOKEN")
+                .help("Token of the admin API under /admin, the API is disabled without it"),
+        )
+        .arg(
+            Arg::new("data-dir")
+                .long("data-dir")
+                .value_name("DIR")
+                .env("BRIDGE_DATA_DIR")
+                .help("Directory where deal records are kept across restarts")
+                .value_parser(value_parser!(PathBuf)),
+        )
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("Can't read {}: {}", path.display(), err))?;
+        toml::from_str(&text).map_err(|err| format!("Invalid {}: {}", path.display(), err))
+    }
+
+    /// Loads the file given by `--config` and applies the options given on the command line on top of it
+    pub fn from_args(args: &ArgMatches) -> Result<Self, String> {
+        let mut config = match args.get_one::<PathBuf>("config") {
+            Some(path) => Self::load(path)?,
+            None => Self::default(),
+        };
+
+        if let Some(bind_address) = args.get_one::<IpAddr>("bind") {
+            config.bind_address = *bind_address;
+        }
+        if let Some(port) = args.get_one::<u16>("port") {
+            config.port = *port;
+        }
+        if let Some(tcp_port) = args.get_one::<u16>("tcp-port") {
+            config.tcp_port = Some(*tcp_port);
+        }
+        if let Some(static_dir) = args.get_one::<PathBuf>("static-dir") {
+            config.static_dir = static_dir.clone();
+        }
+        if let Some(origins) = args.get_many::<String>("cors-origin") {
+            config.cors_origins = Some(origins.cloned().collect());
+        }
+        if let Some(admin_token) = args.get_one::<String>("admin-token") {
+            config.admin_token = Some(admin_token.clone());
+        }
+        if let Some(data_dir) = args.get_one::<PathBuf>("data-dir") {
+            config.data_dir = Some(data_dir.clone());
+        }
+
+        if config.usernames.min_length > config.usernames.max_length {
+            return Err("usernames.min_length is greater than usernames.max_length".into());
+        }
+        Ok(config)
+    }
+
+    pub fn cors_layer(&self) -> Result<CorsLayer, String> {
+        let Some(origins) = &self.cors_origins else {
+            return Ok(CorsLayer::permissive());
+        };
+        let origins = origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin)
+                    .map_err(|_| format!("Invalid CORS origin \"{}\"", origin))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CorsLayer::permissive().allow_origin(AllowOrigin::list(origins)))
+    }
+}
+
+/// Pauses of the game, so that players can follow it
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Pacing {
+    /// Pause before a transition, so that players can see the last call or card
+    pub transition_delay_ms: u64,
+    /// Pause between the notifications of a running game replayed to a user that joined it
+    pub replay_delay_ms: u64,
+}
+
+impl Default for Pacing {
+    fn default() -> Self {
+        Self {
+            transition_delay_ms: 2000,
+            replay_delay_ms: 2000,
+        }
+    }
+}
+
+impl Pacing {
+    pub fn transition_delay(&self) -> Duration {
+        Duration::from_millis(self.transition_delay_ms)
+    }
+
+    pub fn replay_delay(&self) -> Duration {
+        Duration::from_millis(self.replay_delay_ms)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct UsernameRules {
+    /// Minimum length in bytes
+    pub min_length: usize,
+    /// Maximum length in bytes
+    pub max_length: usize,
+    /// Characters allowed besides letters and digits
+    pub extra_characters: String,
+}
+
+impl Default for UsernameRules {
+    fn default() -> Self {
+        Self {
+            min_length: 3,
+            max_length: 20,
+            extra_characters: "_".into(),
+        }
+    }
+}
+
+impl UsernameRules {
+    /// Checks the username, returns the response refusing it if it breaks the rules
+    pub fn check(&self, username: &str) -> Result<(), LoginResponse> {
+        if !(self.min_length..=self.max_length).contains(&username.len()) {
+            return Err(LoginResponse::UsernameLengthOutOfRange {
+                min_length: self.min_length,
+                max_length: self.max_length,
+            });
+        }
+        if !username
+            .chars()
+            .all(|c| c.is_alphanumeric() || self.extra_characters.contains(c))
+        {
+            return Err(LoginResponse::UsernameInvalidCharacters);
+        }
+        Ok(())
+    }
+}
+
+/// Limits of the rooms, none when `None`
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct RoomLimits {
+    /// Rooms existing at the same time, tables of team matches and tournaments included
+    pub max_rooms: Option<usize>,
+    /// Users in a room, players and spectators
+    pub max_users: Option<usize>,
+}
+
+/// Limits of the plain WebSocket and TCP transports, each of their connections holds a socket.io client of its own
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GatewayLimits {
+    /// Connections open at the same time, no limit when `None`
+    pub max_connections: Option<usize>,
+    /// Connections that neither send nor receive a message for this long are closed, never when `None`
+    pub idle_timeout_secs: Option<u64>,
+}
+
+impl Default for GatewayLimits {
+    fn default() -> Self {
+        Self {
+            max_connections: None,
+            idle_timeout_secs: Some(300),
+        }
+    }
+}
+
+impl GatewayLimits {
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout_secs.map(Duration::from_secs)
+    }
+}
@@ -0,0 +1,473 @@
+use common::{
+    game::DealFinished,
+    message::server_notification::{
+        AskBidNotification, AskTrickNotification, AuctionFinishedNotification,
+        AuctionFinishedNotificationInner, DealFinishedNotification, DummyCardsNotification,
+        GameFinishedNotification, GameStartedNotification, MakeBidNotification,
+        MakeTrickNotification, MatchScoreboardNotification, TrickFinishedNotification,
+    },
+    par::Par,
+    record::DealRecord,
+    room::{RoomId, TimeLeft},
+    user::User,
+    Bid, BidStatus, Card, GameState, Player, TrickState, TrickStatus,
+};
+use futures::future::join;
+use socketioxide::{extract::SocketRef, SocketIo};
+use tokio::time::sleep;
+use tracing::info;
+
+use crate::{
+    room_actor::{RoomCommand, RoomHandle},
+    state::{BoardOutcome, RoomState, ServerState},
+    utils::{notify, notify_group, notify_personal, notify_redacted, SendableNotification},
+};
+
+/// Handles needed to continue the game of a room, also outside of a message handler.
+#[derive(Clone)]
+pub struct RoomContext {
+    /// Socket of the user whose request is handled
+    pub socket: SocketRef,
+    /// Room notifications are sent through the namespace, so that they reach the requesting socket too
+    pub io: SocketIo,
+    pub state: ServerState,
+    pub room: RoomHandle,
+}
+
+/// Starts the clock of the player to move and schedules the automatic move on timeout.
+/// Returns `None` for rooms without time limits.
+fn start_clock(ctx: &RoomContext, room: &mut RoomState, player: Player) -> Option<TimeLeft> {
+    let (move_no, time_left, timeout) = room.clock.as_mut()?.start_move(player);
+
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        sleep(timeout).await;
+        ctx.send(RoomCommand::Timeout(move_no));
+    });
+
+    Some(time_left)
+}
+
+/// Makes the move for a player that ran out of time: passes in the auction, or plays the lowest legal card.
+pub fn move_on_timeout(ctx: &RoomContext, room: &mut RoomState, move_no: u64) {
+    let is_pending = room
+        .clock
+        .as_ref()
+        .is_some_and(|clock| clock.is_pending(move_no));
+    if !is_pending {
+        return;
+    }
+
+    let player = room.game.current_player;
+    info!(
+        "Player {} ran out of time in room \"{}\"",
+        player,
+        room.info.id.as_str()
+    );
+
+    match room.game.state {
+        GameState::Auction => {
+            let next_state = room.game.place_bid(&player, Bid::Pass);
+            let notification = MakeBidNotification {
+                player,
+                bid: Bid::Pass,
+                annotation: None,
+                explanation: None,
+            };
+            bid_placed(ctx, room, notification, next_state);
+        }
+        GameState::Tricking => {
+            let Some(card) = room
+                .game
+                .legal_cards(&player)
+                .into_iter()
+                .min_by_key(|card| (card.rank, card.suit))
+            else {
+                return;
+            };
+            let trick_result = room.game.trick(&player, &card);
+            card_played(ctx, room, player, card, trick_result);
+        }
+        _ => (),
+    }
+}
+
+/// Ask current player of the room to place a bid.
+pub fn ask_bid(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+) -> Box<dyn SendableNotification + Send + Sync> {
+    let time_left = start_clock(ctx, room, room.game.current_player);
+    let msg = AskBidNotification {
+        time_left,
+        ..AskBidNotification::from(&room.game)
+    };
+    notify(&ctx.io, &room.info.id, msg)
+}
+
+/// Ask current player of the room to play a card.
+/// Legal cards are sent only to the asked player.
+pub fn ask_trick(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+) -> Box<dyn SendableNotification + Send + Sync> {
+    let time_left = start_clock(ctx, room, room.game.current_player);
+    let msg = AskTrickNotification {
+        time_left,
+        ..AskTrickNotification::from(&room.game)
+    };
+    let personal = msg.for_player(&room.game);
+    let sid = room.get_player_socket(msg.player);
+    notify_personal(&ctx.io, &room.info.id, sid, msg, &personal)
+}
+
+/// Notifies both tables of a team match about the scoreboard.
+/// Returns the notification of `room_id`, to be stored in the room.
+fn notify_scoreboard(
+    ctx: &RoomContext,
+    room_id: &RoomId,
+    scoreboard: MatchScoreboardNotification,
+) -> Box<dyn SendableNotification + Send + Sync> {
+    for other_room in [&scoreboard.open_room, &scoreboard.closed_room] {
+        if other_room != room_id {
+            notify(&ctx.io, other_room, scoreboard.clone());
+        }
+    }
+    notify(&ctx.io, room_id, scoreboard)
+}
+
+/// Records the finished deal. In a room of a team match or a tournament, also records the score of the board
+/// and notifies about the results.
+/// Returns whether the room played all its boards, or `None` if the room is a part of neither.
+async fn finish_board(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+    record: DealRecord,
+    score: isize,
+    notifications: &mut Vec<Box<dyn SendableNotification + Send + Sync>>,
+) -> Option<bool> {
+    let Some(finished) = room.finish_board(score) else {
+        ctx.state
+            .write()
+            .await
+            .add_deal_record(&room.info.id, record);
+        return None;
+    };
+    ctx.state.write().await.add_shared_deal_record(
+        &room.info.id,
+        record,
+        finished.board,
+        finished.board_complete,
+    );
+
+    match finished.outcome {
+        BoardOutcome::Match(scoreboard) => {
+            notifications.push(notify_scoreboard(ctx, &room.info.id, scoreboard));
+        }
+        BoardOutcome::Tournament(Some((group, standings))) => {
+            notify_group(&ctx.io, &group, &standings);
+        }
+        BoardOutcome::Tournament(None) => (),
+    }
+    Some(finished.all_boards_played)
+}
+
+/// Step of the game that is shown after a pause, so that the players can see the last call or card
+pub enum Transition {
+    /// Starts the game with the hands of the first deal once all places are taken
+    StartGame(Option<[Vec<Card>; 4]>),
+    /// Announces the end of the auction with given result and asks for the opening lead,
+    /// or moves to the next deal after four passes
+    FinishAuction {
+        next_state: BidStatus,
+        /// Hands of the next deal after four passes
+        next_deal: Option<[Vec<Card>; 4]>,
+    },
+    /// Shows the finished trick and asks its winner to lead
+    FinishTrick(TrickState),
+    /// Shows the last trick of the deal
+    ShowLastTrick(TrickState),
+    /// Shows the result of the deal and starts the next one, or finishes the game
+    FinishDeal {
+        deal_finished: DealFinished,
+        record: Box<DealRecord>,
+        par: Option<Par>,
+        next_deal: Option<[Vec<Card>; 4]>,
+    },
+}
+
+/// Runs a transition scheduled by the pacer of the room
+pub async fn run_transition(ctx: &RoomContext, room: &mut RoomState, transition: Transition) {
+    room.pacer.finish();
+    match transition {
+        Transition::StartGame(dealt) => start_game(ctx, room, dealt),
+        Transition::FinishAuction {
+            next_state,
+            next_deal,
+        } => finish_auction(ctx, room, next_state, next_deal).await,
+        Transition::FinishTrick(trick_state) => {
+            let notifications = vec![
+                notify(
+                    &ctx.io,
+                    &room.info.id,
+                    TrickFinishedNotification::from(trick_state),
+                ),
+                ask_trick(ctx, room),
+            ];
+            room.append_notifications(notifications);
+        }
+        Transition::ShowLastTrick(trick_state) => {
+            let notification = notify(
+                &ctx.io,
+                &room.info.id,
+                TrickFinishedNotification::from(trick_state),
+            );
+            room.append_notifications(vec![notification]);
+        }
+        Transition::FinishDeal {
+            deal_finished,
+            record,
+            par,
+            next_deal,
+        } => finish_deal(ctx, room, deal_finished, *record, par, next_deal).await,
+    }
+}
+
+/// Starts the game, if all places are still taken
+fn start_game(ctx: &RoomContext, room: &mut RoomState, dealt: Option<[Vec<Card>; 4]>) {
+    if room.game.state != GameState::WaitingForPlayers {
+        return;
+    }
+    let Some(player_position) = room
+        .get_player_positions()
+        .into_iter()
+        .collect::<Option<Vec<User>>>()
+        .map(|v| <[User; 4]>::try_from(v).unwrap())
+    else {
+        // A player left while the first deal was dealt
+        return;
+    };
+
+    info!("Game started in room \"{}\"", room.info.id.as_str());
+
+    room.start_deal(dealt);
+
+    let notifications = vec![
+        notify(
+            &ctx.io,
+            &room.info.id,
+            GameStartedNotification {
+                start_position: room.game.current_player,
+                player_position,
+            },
+        ),
+        ask_bid(ctx, room),
+    ];
+    room.append_notifications(notifications);
+}
+
+/// Notifies the room about a bid accepted by the game and asks for the next move.
+pub fn bid_placed(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+    notification: MakeBidNotification,
+    next_state: BidStatus,
+) {
+    if let Some(clock) = room.clock.as_mut() {
+        clock.finish_move();
+    }
+    let room_id = room.info.id.clone();
+
+    let mut notifications = Vec::new();
+    // Partner must not see the explanation
+    let redacted = MakeBidNotification {
+        explanation: None,
+        ..notification.clone()
+    };
+    let partner = notification.player.get_partner();
+    let partner_sid = room.get_player_socket(partner);
+    notifications.push(notify_redacted(
+        &ctx.io,
+        &room_id,
+        partner_sid,
+        partner,
+        notification,
+        redacted,
+    ));
+
+    if next_state == BidStatus::Auction {
+        notifications.push(ask_bid(ctx, room));
+    } else if next_state == BidStatus::Finished {
+        // 4 passes, the next board is dealt while they are shown
+        let next_deal = room.deal(room.board_index() + 1);
+        room.pacer.schedule_after(ctx, async move {
+            Transition::FinishAuction {
+                next_state,
+                next_deal: next_deal.await,
+            }
+        });
+    } else {
+        room.pacer.schedule(
+            ctx,
+            Transition::FinishAuction {
+                next_state,
+                next_deal: None,
+            },
+        );
+    }
+    room.append_notifications(notifications);
+}
+
+async fn finish_auction(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+    next_state: BidStatus,
+    next_deal: Option<[Vec<Card>; 4]>,
+) {
+    let room_id = room.info.id.clone();
+
+    let mut notifications = vec![notify(
+        &ctx.io,
+        &room_id,
+        AuctionFinishedNotification::Winner(AuctionFinishedNotificationInner {
+            winner: room.game.max_bidder,
+            max_bid: room.game.max_bid,
+            game_value: room.game.game_value,
+        }),
+    )];
+
+    if next_state == BidStatus::Finished {
+        // 4 passes
+        let record = room.game.record();
+        if let Some(all_boards_played) =
+            finish_board(ctx, room, record, 0, &mut notifications).await
+        {
+            if !all_boards_played {
+                room.start_deal(next_deal);
+                notifications.push(ask_bid(ctx, room));
+                room.append_notifications(notifications);
+                return;
+            }
+        }
+
+        notifications.push(notify(
+            &ctx.io,
+            &room_id,
+            GameFinishedNotification { result: None },
+        ));
+        room.append_notifications(notifications);
+
+        ctx.state.write().await.remove_room(&room_id);
+    } else {
+        // The deal is solved while it is played, so that its result isn't held up by the solver
+        room.solve_par();
+        notifications.push(ask_trick(ctx, room));
+        room.append_notifications(notifications);
+    }
+}
+
+/// Notifies the room about a card accepted by the game and asks for the next move.
+pub fn card_played(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+    player: Player,
+    card: Card,
+    trick_result: TrickStatus,
+) {
+    if let TrickStatus::Error(_) = trick_result {
+        return;
+    }
+    if let Some(clock) = room.clock.as_mut() {
+        clock.finish_move();
+    }
+    let room_id = room.info.id.clone();
+
+    let mut notifications = Vec::new();
+
+    notifications.push(notify(
+        &ctx.io,
+        &room_id,
+        MakeTrickNotification { player, card },
+    ));
+
+    match trick_result {
+        TrickStatus::TrickInProgress => {
+            if room.game.trick_no == 0 && room.game.current_trick.len() == 1 {
+                let msg = DummyCardsNotification::new(
+                    room.game.get_dummy_cards().unwrap().clone(),
+                    room.game.get_dummy_player().unwrap(),
+                );
+                notifications.push(notify(&ctx.io, &room_id, msg));
+            }
+            notifications.push(ask_trick(ctx, room));
+        }
+        TrickStatus::TrickFinished(trick_state) => {
+            room.pacer
+                .schedule(ctx, Transition::FinishTrick(trick_state));
+        }
+        TrickStatus::DealFinished(deal_finished) => {
+            // The next board is dealt while the last trick is shown
+            let record = room.game.record();
+            let par = room.take_par();
+            let next_deal = room.deal(room.board_index() + 1);
+
+            room.pacer.schedule(
+                ctx,
+                Transition::ShowLastTrick(deal_finished.trick_state.clone()),
+            );
+            room.pacer.schedule_after(ctx, async move {
+                let (par, next_deal) = join(par, next_deal).await;
+                Transition::FinishDeal {
+                    deal_finished,
+                    record: Box::new(record),
+                    par,
+                    next_deal,
+                }
+            });
+        }
+        TrickStatus::Error(_) => (),
+    }
+
+    room.append_notifications(notifications);
+}
+
+async fn finish_deal(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+    deal_finished: DealFinished,
+    record: DealRecord,
+    par: Option<Par>,
+    next_deal: Option<[Vec<Card>; 4]>,
+) {
+    let room_id = room.info.id.clone();
+
+    let mut notifications = vec![notify(
+        &ctx.io,
+        &room_id,
+        DealFinishedNotification::new(deal_finished.clone(), record.clone(), par),
+    )];
+
+    let score = room.game.deal_score();
+    let is_game_finished = finish_board(ctx, room, record, score, &mut notifications)
+        .await
+        .unwrap_or(deal_finished.is_game_finished);
+
+    if is_game_finished {
+        notifications.push(notify(
+            &ctx.io,
+            &room_id,
+            GameFinishedNotification { result: None },
+        ));
+        // The audience of the broadcast gets the result too
+        room.append_notifications(notifications);
+
+        ctx.state.write().await.remove_room(&room_id);
+
+        return;
+    }
+
+    room.start_deal(next_deal);
+
+    notifications.push(ask_bid(ctx, room));
+    room.append_notifications(notifications);
+}
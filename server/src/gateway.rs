@@ -0,0 +1,286 @@
+use std::{
+    future::pending,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::pin,
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        State, WebSocketUpgrade,
+    },
+    response::Response,
+};
+use common::message::{server_notification::ServerMessageNotification, MessageTrait};
+use futures::{sink, stream, FutureExt, Sink, SinkExt, Stream, StreamExt};
+use rust_socketio::{
+    asynchronous::{Client, ClientBuilder},
+    Event, Payload,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{to_string, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{unbounded_channel, UnboundedReceiver},
+        Notify, OwnedSemaphorePermit, Semaphore,
+    },
+    time::sleep,
+};
+use tracing::{info, warn};
+
+use crate::config::GatewayLimits;
+
+/// Message sent over the plain transports: `MSG_TYPE` of a message and the message itself
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Envelope {
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub data: Value,
+}
+
+/// Type of the envelope sent back for a line that isn't a valid envelope, with the reason as `data`
+const INVALID_ENVELOPE: &str = "invalid_envelope";
+
+/// Transports for clients without a socket.io library: plain WebSocket and newline-delimited JSON over TCP.
+/// Every connection is relayed to the socket.io namespace of the server through its own loopback socket.io client,
+/// so it goes through the same handlers and room broadcasts as socket.io clients.
+/// Responses come as standalone messages, as for socket.io clients that don't ask for acknowledgements.
+#[derive(Clone)]
+pub struct Gateway {
+    /// Address of the socket.io server of this process
+    url: String,
+    /// Places for open connections, no limit when `None`
+    connections: Option<Arc<Semaphore>>,
+    idle_timeout: Option<Duration>,
+}
+
+impl Gateway {
+    /// Creates the gateway of the socket.io server listening on `addr`
+    pub fn new(addr: SocketAddr, limits: &GatewayLimits) -> Self {
+        let mut addr = addr;
+        match addr.ip() {
+            IpAddr::V4(ip) if ip.is_unspecified() => addr.set_ip(Ipv4Addr::LOCALHOST.into()),
+            IpAddr::V6(ip) if ip.is_unspecified() => addr.set_ip(Ipv6Addr::LOCALHOST.into()),
+            _ => {}
+        }
+        Self {
+            url: format!("http://{}", addr),
+            connections: limits
+                .max_connections
+                .map(|max_connections| Arc::new(Semaphore::new(max_connections))),
+            idle_timeout: limits.idle_timeout(),
+        }
+    }
+
+    /// Takes a place for a new connection. Fails if the gateway has as many connections as it allows.
+    fn admit(&self) -> Result<Option<OwnedSemaphorePermit>, ()> {
+        match &self.connections {
+            Some(connections) => connections
+                .clone()
+                .try_acquire_owned()
+                .map(Some)
+                .map_err(|_| ()),
+            None => Ok(None),
+        }
+    }
+
+    /// Accepts connections of the TCP transport, one envelope per line in both directions
+    pub async fn serve_tcp(self, listener: TcpListener) {
+        loop {
+            let (stream, addr) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(err) => {
+                    warn!("Can't accept TCP connection: {}", err);
+                    continue;
+                }
+            };
+            info!("TCP client connected from {}", addr);
+            tokio::spawn(self.clone().serve_tcp_stream(stream));
+        }
+    }
+
+    async fn serve_tcp_stream(self, stream: TcpStream) {
+        let (read, write) = stream.into_split();
+
+        let incoming = stream::unfold(BufReader::new(read).lines(), |mut lines| async move {
+            let line = lines.next_line().await.ok().flatten()?;
+            Some((line, lines))
+        });
+        let outgoing = sink::unfold(write, |mut write, envelope: String| async move {
+            write.write_all(envelope.as_bytes()).await?;
+            write.write_all(b"\n").await?;
+            Ok::<_, std::io::Error>(write)
+        });
+
+        self.relay(pin!(incoming), pin!(outgoing)).await;
+    }
+
+    async fn serve_websocket(self, socket: WebSocket) {
+        let (outgoing, incoming) = socket.split();
+
+        let incoming = incoming
+            .take_while(|message| std::future::ready(message.is_ok()))
+            .filter_map(|message| {
+                std::future::ready(match message {
+                    Ok(Message::Text(text)) => Some(text),
+                    _ => None,
+                })
+            });
+        let outgoing = outgoing.with(|envelope: String| {
+            std::future::ready(Ok::<_, axum::Error>(Message::Text(envelope)))
+        });
+
+        self.relay(pin!(incoming), pin!(outgoing)).await;
+    }
+
+    /// Passes envelopes between the plain connection and its socket.io client until either side closes
+    /// or the connection is idle for too long
+    async fn relay<I, O>(&self, mut incoming: I, mut outgoing: O)
+    where
+        I: Stream<Item = String> + Unpin,
+        O: Sink<String> + Unpin,
+    {
+        let Ok(_permit) = self.admit() else {
+            warn!("Refusing plain connection, the gateway has too many connections");
+            outgoing
+                .send(server_message("The server has too many connections"))
+                .await
+                .ok();
+            return;
+        };
+
+        let (client, mut envelopes) = match self.connect().await {
+            Ok(connection) => connection,
+            Err(err) => {
+                warn!("Can't relay plain connection to socket.io: {}", err);
+                return;
+            }
+        };
+
+        let mut idle = pin!(idle_deadline(self.idle_timeout));
+        loop {
+            tokio::select! {
+                envelope = envelopes.recv() => {
+                    let Some(Some(envelope)) = envelope else { break };
+                    if outgoing.send(envelope).await.is_err() {
+                        break;
+                    }
+                }
+                text = incoming.next() => {
+                    let Some(text) = text else { break };
+                    if let Err(reply) = forward(&client, &text).await {
+                        if outgoing.send(reply).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                () = &mut idle => {
+                    info!("Closing idle plain connection");
+                    outgoing
+                        .send(server_message("The connection was idle for too long"))
+                        .await
+                        .ok();
+                    break;
+                }
+            }
+            idle.set(idle_deadline(self.idle_timeout));
+        }
+
+        client.disconnect().await.ok();
+    }
+
+    /// Connects a socket.io client for a plain connection.
+    /// Returns the client and the envelopes it receives, `None` once the server closed the connection.
+    async fn connect(
+        &self,
+    ) -> Result<(Client, UnboundedReceiver<Option<String>>), rust_socketio::Error> {
+        let (sender, receiver) = unbounded_channel();
+        let close_sender = sender.clone();
+        let connected = Arc::new(Notify::new());
+        let notify = connected.clone();
+
+        let client = ClientBuilder::new(self.url.as_str())
+            .namespace("/")
+            .reconnect(false)
+            .on_any(move |event, payload, _| {
+                let sender = sender.clone();
+                async move {
+                    let (Event::Custom(msg_type), Payload::Text(mut values)) = (event, payload)
+                    else {
+                        return;
+                    };
+                    let data = if values.is_empty() {
+                        Value::Null
+                    } else {
+                        values.swap_remove(0)
+                    };
+                    let envelope = to_string(&Envelope { msg_type, data }).unwrap();
+                    sender.send(Some(envelope)).ok();
+                }
+                .boxed()
+            })
+            .on(Event::Connect, move |_, _| {
+                notify.notify_one();
+                async {}.boxed()
+            })
+            .on(Event::Close, move |_, _| {
+                let sender = close_sender.clone();
+                async move {
+                    sender.send(None).ok();
+                }
+                .boxed()
+            })
+            .connect()
+            .await?;
+        // Messages sent before the server accepts the connection are dropped
+        connected.notified().await;
+
+        Ok((client, receiver))
+    }
+}
+
+/// Completes when a connection without messages for `timeout` is idle, never when there is no timeout
+async fn idle_deadline(timeout: Option<Duration>) {
+    match timeout {
+        Some(timeout) => sleep(timeout).await,
+        None => pending().await,
+    }
+}
+
+/// Envelope of a `ServerMessageNotification`, for messages of the gateway itself
+fn server_message(message: &str) -> String {
+    to_string(&Envelope {
+        msg_type: ServerMessageNotification::MSG_TYPE.into(),
+        data: serde_json::to_value(ServerMessageNotification {
+            message: message.into(),
+        })
+        .unwrap(),
+    })
+    .unwrap()
+}
+
+/// Sends the envelope to the server. Returns the envelope to send back if it's invalid.
+async fn forward(client: &Client, text: &str) -> Result<(), String> {
+    let envelope = serde_json::from_str::<Envelope>(text).map_err(|err| {
+        to_string(&Envelope {
+            msg_type: INVALID_ENVELOPE.into(),
+            data: err.to_string().into(),
+        })
+        .unwrap()
+    })?;
+
+    client
+        .emit(envelope.msg_type, Payload::Text(vec![envelope.data]))
+        .await
+        .ok();
+    Ok(())
+}
+
+/// Upgrades a request of the WebSocket transport
+pub async fn websocket_handler(ws: WebSocketUpgrade, State(gateway): State<Gateway>) -> Response {
+    ws.on_upgrade(move |socket| gateway.serve_websocket(socket))
+}
@@ -4,6 +4,8 @@ use common::room::RoomId;
 use socketioxide::adapter::Room as SRoom;
 use socketioxide::operators::RoomParam;
 
+/// Socket room of a game room.
+/// Its name is prefixed, so that ids chosen by users never name a `Group` or the personal room of a socket.
 #[derive(Clone)]
 pub struct RoomWrapper(pub RoomId);
 
@@ -11,6 +13,18 @@ impl RoomParam for RoomWrapper {
     type IntoIter = std::iter::Once<SRoom>;
     #[inline(always)]
     fn into_room_iter(self) -> Self::IntoIter {
-        std::iter::once(Cow::Owned(self.0.as_str().into()))
+        std::iter::once(Cow::Owned(format!("room:{}", self.0.as_str())))
+    }
+}
+
+/// Socket room of clients grouped by the server, e.g. by protocol version or by the tournament they follow
+#[derive(Clone, Debug)]
+pub struct Group(pub String);
+
+impl RoomParam for Group {
+    type IntoIter = std::iter::Once<SRoom>;
+    #[inline(always)]
+    fn into_room_iter(self) -> Self::IntoIter {
+        std::iter::once(Cow::Owned(self.0))
     }
 }
@@ -1,54 +1,74 @@
-use std::sync::Arc;
-use std::time::Duration;
+use std::{net::SocketAddr, sync::Arc};
 
 use common::message::client_message::{
     GetCardsMessage, LeaveRoomMessage, ListPlacesMessage, ListRoomsMessage, MakeBidMessage,
-    MakeTrickMessage,
+    MakeTrickMessage, SetConventionCardMessage,
 };
-use common::message::server_notification::{
-    AskBidNotification, AskTrickNotification, AuctionFinishedNotification,
-    AuctionFinishedNotificationInner, DealFinishedNotification, DummyCardsNotification,
-    GameFinishedNotification, MakeBidNotification, MakeTrickNotification,
-    TrickFinishedNotification,
+use common::message::client_message::{
+    JoinTournamentMessage, KibitzMessage, RegisterTournamentMessage, StartRoundMessage,
+    WatchBroadcastMessage,
+};
+use common::message::server_response::{
+    GetCardsResponse, JoinTournamentResponse, KibitzResponse, MakeBidResponse, MakeTrickResponse,
+    RegisterTournamentResponse, SetConventionCardResponse, StartRoundResponse,
+    WatchBroadcastResponse,
 };
-use common::message::server_response::{GetCardsResponse, MakeBidResponse, MakeTrickResponse};
 use common::message::{
-    client_message::{JoinRoomMessage, LoginMessage, RegisterRoomMessage, SelectPlaceMessage},
-    server_notification::{
-        GameStartedNotification, JoinRoomNotification, LeaveRoomNotification,
-        SelectPlaceNotification,
+    client_message::{
+        HelloMessage, JoinRoomMessage, LoginMessage, RegisterMatchMessage, RegisterRoomMessage,
+        SelectPlaceMessage,
     },
+    server_notification::{JoinRoomNotification, LeaveRoomNotification},
     server_response::{
-        JoinRoomResponse, LeaveRoomResponse, ListPlacesResponse, ListRoomsResponse, LoginResponse,
-        RegisterRoomResponse, SelectPlaceResponse,
+        HelloResponse, JoinRoomResponse, LeaveRoomResponse, ListPlacesResponse, ListRoomsResponse,
+        LoginResponse, RegisterRoomResponse, SelectPlaceResponse,
     },
     MessageTrait,
 };
+use common::protocol::{Feature, Protocol, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
 use common::user::User;
-use common::{Bid, BidError, BidStatus, GameState, TrickStatus};
+use broadcast::audience_group;
+use game_flow::RoomContext;
+use gateway::Gateway;
 use handlers::RoomWrapper;
+use room_actor::{RoomCommand, RoomHandle};
 use socketioxide::{
     extract::{Data, SocketRef, State},
     SocketIo,
 };
 use tokio::sync::RwLock;
-use tokio::time::sleep;
 use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, services::ServeDir};
+use tower_http::services::ServeDir;
 use tracing::info;
 use tracing_subscriber::FmtSubscriber;
 
-use state::{RoomState, ServerState};
-use utils::{get_client_or_response, notify, notify_others, send};
+use state::{can_deal, ServerState};
+use utils::{
+    get_client_or_response, join_protocol_groups, notify_group, notify_others,
+    refuse_while_draining, send, Responder,
+};
 
+mod admin;
+mod api;
+mod broadcast;
+mod clock;
+mod config;
+mod game_flow;
+mod gateway;
 mod handlers;
+mod metrics;
+mod pacing;
+mod persistence;
+mod room_actor;
 mod state;
+mod team_match;
+mod tournament;
 mod utils;
 
 #[derive(Clone)]
 struct ClientData {
     user: User,
-    room: Option<Arc<RwLock<RoomState>>>,
+    room: Option<RoomHandle>,
 }
 
 #[tokio::main]
@@ -57,34 +77,58 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     tracing::subscriber::set_global_default(subscriber)?;
 
+    let config = Arc::new(config::Config::from_args(&config::cli().get_matches())?);
+
+    let state = ServerState::new(RwLock::new(state::ServerStateInner::new(config.clone())?));
     let (layer, io) = SocketIo::builder()
-        .with_state(ServerState::new(
-            RwLock::new(state::ServerStateInner::new()),
-        ))
+        .with_state(state.clone())
         .build_layer();
 
     io.ns("/", |s: SocketRef| {
+        // Personal messages are sent to the room named after the socket id
+        s.join(s.id).unwrap();
+        // Until the handshake, the client is assumed to talk the oldest protocol
+        join_protocol_groups(&s, &Protocol::default());
+
+        s.on(
+            HelloMessage::MSG_TYPE,
+            |s: SocketRef, reply: Responder, Data::<HelloMessage>(data)| async move {
+                let Some(protocol) = Protocol::negotiate(data.protocol_version, &data.features, &Feature::ALL) else {
+                    reply.send(&HelloResponse::UnsupportedVersion {
+                        min_version: MIN_PROTOCOL_VERSION,
+                        max_version: PROTOCOL_VERSION,
+                    });
+                    info!("Rejected client talking protocol version {}", data.protocol_version);
+                    s.disconnect().ok();
+                    return;
+                };
+
+                join_protocol_groups(&s, &protocol);
+                s.extensions.insert(protocol.clone());
+
+                reply.send(&HelloResponse::Ok(protocol));
+            },
+        );
+
         s.on(
             LoginMessage::MSG_TYPE,
-            |s: SocketRef, Data::<LoginMessage>(data), state: State<ServerState>| async move {
-                // check username length between 3 and 20
-                if !(3..=20).contains(&data.user.get_username().len()) {
-                    send(&s, &LoginResponse::UsernameInvalidLength);
+            |s: SocketRef, reply: Responder, Data::<LoginMessage>(data), state: State<ServerState>| async move {
+                if refuse_while_draining(&s, &state.0).await {
                     return;
                 }
 
-                // check username characters
-                if !data.user.get_username().chars().all(|c| c.is_alphanumeric() || c == '_') {
-                    send(&s, &LoginResponse::UsernameInvalidCharacters);
+                if let Err(err) = state.read().await.config().usernames.check(data.user.get_username()) {
+                    reply.send(&err);
                     return;
                 }
 
                 if s.extensions.get::<ClientData>().is_some() {
-                    send(&s, &LoginResponse::UserAlreadyLoggedIn);
+                    reply.send(&LoginResponse::UserAlreadyLoggedIn);
+                    return;
                 }
 
                 if !state.write().await.add_user(data.user.clone()) {
-                    send(&s, &LoginResponse::UsernameAlreadyExists);
+                    reply.send(&LoginResponse::UsernameAlreadyExists);
                     return;
                 }
 
@@ -94,7 +138,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 };
                 s.extensions.insert(client_data);
 
-                send(&s, &LoginResponse::Ok);
+                reply.send(&LoginResponse::Ok);
 
                 info!("User \"{}\" logged in", data.user.get_username());
             },
@@ -102,26 +146,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         s.on(
             ListRoomsMessage::MSG_TYPE,
-            |s: SocketRef, state: State<ServerState>| async move {
-                let rooms = state.read().await.get_room_list().await;
-                send(&s, &ListRoomsResponse { rooms });
+            |reply: Responder, state: State<ServerState>| async move {
+                let rooms = state.read().await.get_room_list();
+                reply.send(&ListRoomsResponse { rooms });
             },
         );
 
         s.on(
             RegisterRoomMessage::MSG_TYPE,
-            |s: SocketRef, Data::<RegisterRoomMessage>(data), state: State<ServerState>| async move {
-                let Some(client_data) = get_client_or_response(&s, &RegisterRoomResponse::Unauthenticated) else {return};
+            |reply: Responder, Data::<RegisterRoomMessage>(data), state: State<ServerState>, io: SocketIo| async move {
+                let Some(client_data) = get_client_or_response(&reply, &RegisterRoomResponse::Unauthenticated) else {return};
+                if refuse_while_draining(&reply.socket, &state.0).await {
+                    return;
+                }
 
                 let room_id = data.room_info.id.clone();
 
+                if !can_deal(&data.room_info).await {
+                    reply.send(&RegisterRoomResponse::UnsatisfiableDealConstraints);
+                    return;
+                }
+
                 let message = state
                     .write()
                     .await
-                    .add_room(data.room_info)
-                    .await;
+                    .add_room(data.room_info, &io);
 
-                send(&s, &message);
+                reply.send(&message);
 
                 if message == RegisterRoomResponse::Ok {
                     info!(
@@ -133,32 +184,189 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             },
         );
 
+        s.on(
+            RegisterMatchMessage::MSG_TYPE,
+            |reply: Responder, Data::<RegisterMatchMessage>(data), state: State<ServerState>, io: SocketIo| async move {
+                let Some(client_data) = get_client_or_response(&reply, &RegisterRoomResponse::Unauthenticated) else {return};
+                if refuse_while_draining(&reply.socket, &state.0).await {
+                    return;
+                }
+
+                let room_ids = [data.open_room.id.clone(), data.closed_room.id.clone()];
+
+                if !(can_deal(&data.open_room).await && can_deal(&data.closed_room).await) {
+                    reply.send(&RegisterRoomResponse::UnsatisfiableDealConstraints);
+                    return;
+                }
+
+                let message = state
+                    .write()
+                    .await
+                    .add_match(data.open_room, data.closed_room, data.boards.max(1), &io);
+
+                reply.send(&message);
+
+                if message == RegisterRoomResponse::Ok {
+                    info!(
+                        "Team match in rooms \"{}\" and \"{}\" was registered by \"{}\"",
+                        room_ids[0].as_str(),
+                        room_ids[1].as_str(),
+                        client_data.user.get_username()
+                    );
+                }
+            },
+        );
+
+        s.on(
+            WatchBroadcastMessage::MSG_TYPE,
+            |s: SocketRef, reply: Responder, Data::<WatchBroadcastMessage>(data), state: State<ServerState>| async move {
+                let Some(client_data) = get_client_or_response(&reply, &WatchBroadcastResponse::Unauthenticated) else {return};
+
+                let Some(room) = state.read().await.get_room(&data.room_id) else {
+                    reply.send(&WatchBroadcastResponse::RoomNotFound);
+                    return;
+                };
+                if room.info.broadcast_delay_secs.is_none() {
+                    reply.send(&WatchBroadcastResponse::NoBroadcast);
+                    return;
+                }
+
+                s.join(audience_group(&data.room_id)).unwrap();
+                reply.send(&WatchBroadcastResponse::Ok);
+
+                info!(
+                    "User \"{}\" watches the broadcast of room \"{}\"",
+                    client_data.user.get_username(),
+                    data.room_id.as_str()
+                );
+            },
+        );
+
+        s.on(
+            RegisterTournamentMessage::MSG_TYPE,
+            |reply: Responder, Data::<RegisterTournamentMessage>(data), state: State<ServerState>| async move {
+                let Some(client_data) = get_client_or_response(&reply, &RegisterTournamentResponse::Unauthenticated) else {return};
+
+                let tournament_id = data.tournament_id.clone();
+                let message = state.write().await.add_tournament(
+                    data.tournament_id,
+                    client_data.user.clone(),
+                    data.movement,
+                    data.pairs,
+                    data.boards_per_round.max(1),
+                );
+
+                reply.send(&message);
+
+                if message == RegisterTournamentResponse::Ok {
+                    info!(
+                        "Tournament \"{}\" was registered by \"{}\"",
+                        tournament_id,
+                        client_data.user.get_username()
+                    );
+                }
+            },
+        );
+
+        s.on(
+            JoinTournamentMessage::MSG_TYPE,
+            |s: SocketRef, reply: Responder, Data::<JoinTournamentMessage>(data), state: State<ServerState>| async move {
+                let Some(_) = get_client_or_response(&reply, &JoinTournamentResponse::Unauthenticated) else {return};
+
+                let Some(tournament) = state.read().await.get_tournament(&data.tournament_id) else {
+                    reply.send(&JoinTournamentResponse::TournamentNotFound);
+                    return;
+                };
+
+                let (group, round, standings) = {
+                    let tournament_lock = tournament.lock().unwrap();
+                    let standings = (!tournament_lock.is_round_in_progress())
+                        .then(|| tournament_lock.standings())
+                        .filter(|standings| standings.round > 0);
+                    (tournament_lock.group(), tournament_lock.round_notification(), standings)
+                };
+
+                s.join(group).unwrap();
+                reply.send(&JoinTournamentResponse::Ok);
+
+                if let Some(round) = round {
+                    send(&s, &round);
+                }
+                if let Some(standings) = standings {
+                    send(&s, &standings);
+                }
+            },
+        );
+
+        s.on(
+            StartRoundMessage::MSG_TYPE,
+            |s: SocketRef, reply: Responder, Data::<StartRoundMessage>(data), state: State<ServerState>, io: SocketIo| async move {
+                let Some(client_data) = get_client_or_response(&reply, &StartRoundResponse::Unauthenticated) else {return};
+                if refuse_while_draining(&s, &state.0).await {
+                    return;
+                }
+
+                let mut state_lock = state.write().await;
+                if let Err(err) = state_lock.start_tournament_round(&data.tournament_id, &client_data.user) {
+                    reply.send(&err);
+                    return;
+                }
+                let Some(tournament) = state_lock.get_tournament(&data.tournament_id) else {return};
+                drop(state_lock);
+
+                let (group, round) = {
+                    let tournament_lock = tournament.lock().unwrap();
+                    (tournament_lock.group(), tournament_lock.round_notification())
+                };
+
+                reply.send(&StartRoundResponse::Ok);
+
+                if let Some(round) = round {
+                    info!(
+                        "Round {} of tournament \"{}\" was started",
+                        round.round,
+                        data.tournament_id
+                    );
+                    notify_group(&io, &group, &round);
+                }
+            },
+        );
+
         s.on(
             JoinRoomMessage::MSG_TYPE,
-            |s: SocketRef, Data::<JoinRoomMessage>(data), state: State<ServerState>| async move {
-                let Some(mut client_data) = get_client_or_response(&s, &JoinRoomResponse::Unauthenticated) else {return};
+            |s: SocketRef, reply: Responder, Data::<JoinRoomMessage>(data), state: State<ServerState>, io: SocketIo| async move {
+                let Some(mut client_data) = get_client_or_response(&reply, &JoinRoomResponse::Unauthenticated) else {return};
 
                 if client_data.room.is_some() {
-                    send(&s, &JoinRoomResponse::AlreadyInRoom);
+                    reply.send(&JoinRoomResponse::AlreadyInRoom);
                     return;
                 }
 
                 let room_id = data.room_id.clone();
 
-                let Some(room_state) = state.read().await.get_room(&room_id).await else {
-                    send(&s, &JoinRoomResponse::RoomNotFound);
-                    return;
+                let room = {
+                    let state_lock = state.read().await;
+                    let Some(room) = state_lock.get_room(&room_id) else {
+                        reply.send(&JoinRoomResponse::RoomNotFound);
+                        return;
+                    };
+                    if let Err(err) = state_lock.check_room_capacity(&room) {
+                        reply.send(&err);
+                        return;
+                    }
+                    room
                 };
 
-                room_state.write().await.user_join_room(client_data.user.clone()).await;
+                let ctx = RoomContext { socket: s.clone(), io, state: state.0, room: room.clone() };
+                ctx.send(RoomCommand::Join(client_data.user.clone(), s.id));
 
-                client_data.room = Some(room_state);
+                client_data.room = Some(room);
                 let user = client_data.user.clone();
                 s.extensions.insert(client_data);
 
                 s.join(RoomWrapper(room_id.clone())).unwrap();
 
-                send(&s, &JoinRoomResponse::Ok);
+                reply.send(&JoinRoomResponse::Ok);
 
                 info!(
                     "User \"{}\" joined room \"{}\"",
@@ -171,299 +379,117 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             },
         );
 
-        let leave_room_handler = |s: SocketRef, mut client_data: ClientData, room: Arc<RwLock<RoomState>>, generate_response: bool| async move {
-            let room_id = {
-                let mut room_lock = room.write().await;
-                let room_id = room_lock.info.id.clone();
-                room_lock.user_leave_room(&client_data.user);
-                room_id
-            };
+        let leave_room_handler = |s: SocketRef, io: SocketIo, state: ServerState, mut client_data: ClientData, room: RoomHandle, reply: Option<Responder>| async move {
+            let room_id = room.info.id.clone();
+            let ctx = RoomContext { socket: s.clone(), io, state, room };
+            ctx.send(RoomCommand::Leave(client_data.user.clone()));
 
-            if generate_response {
+            if let Some(reply) = reply {
                 client_data.room = None;
                 s.extensions.insert(client_data.clone());
 
-                send(&s, &LeaveRoomResponse::Ok);
+                reply.send(&LeaveRoomResponse::Ok);
             }
 
             info!("User \"{}\" left room \"{}\"", client_data.user.get_username(), room_id.as_str());
 
             notify_others(&s, &room_id, &LeaveRoomNotification{user: client_data.user});
-            s.leave_all().ok();
+            s.leave(RoomWrapper(room_id)).ok();
         };
 
-        s.on(LeaveRoomMessage::MSG_TYPE, move |s: SocketRef| async move {
-            let Some(client_data) = get_client_or_response(&s, &LeaveRoomResponse::Unauthenticated) else {return};
+        s.on(LeaveRoomMessage::MSG_TYPE, move |s: SocketRef, reply: Responder, state: State<ServerState>, io: SocketIo| async move {
+            let Some(client_data) = get_client_or_response(&reply, &LeaveRoomResponse::Unauthenticated) else {return};
 
             let Some(room) = client_data.room.clone() else {
-                send(&s, &LeaveRoomResponse::NotInRoom);
+                reply.send(&LeaveRoomResponse::NotInRoom);
                 return;
             };
 
-            leave_room_handler(s, client_data, room, true).await;
+            leave_room_handler(s, io, state.0, client_data, room, Some(reply)).await;
         });
 
-        s.on(ListPlacesMessage::MSG_TYPE, |s: SocketRef| async move {
-            let Some(client_data) = get_client_or_response(&s, &ListPlacesResponse::Unauthenticated) else {return};
+        s.on(ListPlacesMessage::MSG_TYPE, |s: SocketRef, reply: Responder, state: State<ServerState>, io: SocketIo| async move {
+            let Some(client_data) = get_client_or_response(&reply, &ListPlacesResponse::Unauthenticated) else {return};
 
             let Some(room) = client_data.room else {
-                send(&s, &ListPlacesResponse::NotInRoom);
+                reply.send(&ListPlacesResponse::NotInRoom);
                 return;
             };
 
-            let player_positions = {
-                let room_lock = room.read().await;
-                room_lock.get_player_positions()
-            };
-
-            send(&s, &ListPlacesResponse::Ok(player_positions));
+            RoomContext { socket: s, io, state: state.0, room }.request(RoomCommand::ListPlaces, reply);
         });
 
         s.on(
             SelectPlaceMessage::MSG_TYPE,
-            |s: SocketRef, Data::<SelectPlaceMessage>(data)| async move {
-                let Some(client_data) = get_client_or_response(&s, &SelectPlaceResponse::Unauthenticated) else {return};
+            |s: SocketRef, reply: Responder, Data::<SelectPlaceMessage>(data), state: State<ServerState>, io: SocketIo| async move {
+                let Some(client_data) = get_client_or_response(&reply, &SelectPlaceResponse::Unauthenticated) else {return};
 
                 let Some(room) = client_data.room else {
-                    send(&s, &SelectPlaceResponse::NotInRoom);
+                    reply.send(&SelectPlaceResponse::NotInRoom);
                     return;
                 };
 
-                let (is_place_free, player_positions, room_id) = {
-                    let mut room_state_lock = room.write().await;
-                    (
-                        room_state_lock.user_select_place(&client_data.user, data.position),
-                        room_state_lock.get_player_positions(),
-                        room_state_lock.info.id.clone()
-                    )
-                };
-
-                let player_position_all_taken: Option<[User; 4]> = player_positions
-                    .clone()
-                    .into_iter()
-                    .collect::<Option<Vec<User>>>()
-                    .map(|v| {
-                        v.try_into().unwrap()
-                    });
-
-                if !is_place_free {
-                    send(&s, &SelectPlaceResponse::PlaceAlreadyTaken);
-                    return;
-                }
-
-                let position_str = match data.position {
-                    Some(pos) => pos.to_u8().to_string(),
-                    None => "*spectator*".into(),
-                };
-                info!("User \"{}\" selected place {} in room \"{}\"", client_data.user.get_username(), position_str, room_id.as_str());
-
-                send(&s, &SelectPlaceResponse::Ok);
-
-                notify_others(&s, &room_id, &SelectPlaceNotification {
-                    user: client_data.user,
-                    position: data.position,
-                });
-
-                let game_state = room.read().await.game.state;
-
-                if game_state != GameState::WaitingForPlayers {
-                    // Player joined to a game that is already running    
-                    room.read().await.send_notifications(&s).await;
-                    return;
-                }
-
-                if let Some(player_position) = player_position_all_taken {
-                    // Game starts now
-                    info!("Game started in room \"{}\"", room_id.as_str());
-
-                    let mut room_lock = room.write().await;
-                    room_lock.game.start();
-
-                    let notifications = vec![
-                        notify(&s, &room_id, GameStartedNotification {
-                            start_position: room_lock.game.current_player,
-                            player_position: player_position.clone(),
-                        }),
-                        notify(&s, &room_id, AskBidNotification {
-                            player: room_lock.game.current_player,
-                            max_bid: Bid::Pass,
-                        })
-                    ];
-
-                    room_lock.append_notifications(notifications);
-                }
+                RoomContext { socket: s, io, state: state.0, room }.request(RoomCommand::SelectPlace(client_data.user, data), reply);
             }
         );
 
-        s.on(GetCardsMessage::MSG_TYPE, |s: SocketRef| async move {
-            let Some(client_data) = get_client_or_response(&s, &GetCardsResponse::Unauthenticated) else {return};
+        s.on(GetCardsMessage::MSG_TYPE, |s: SocketRef, reply: Responder, state: State<ServerState>, io: SocketIo| async move {
+            let Some(client_data) = get_client_or_response(&reply, &GetCardsResponse::Unauthenticated) else {return};
 
             let Some(room) = client_data.room else {
-                send(&s, &GetCardsResponse::NotInRoom);
+                reply.send(&GetCardsResponse::NotInRoom);
                 return;
             };
 
-            let (position, cards) = {
-                let room_lock = room.read().await;
-                let position = room_lock.find_player_position(&client_data.user);
-                let Some(position) = position else {
-                    send(&s, &GetCardsResponse::SpectatorNotAllowed);
-                    return;
-                };
-                let cards = room_lock.game.get_cards(&position).clone();
-                (position, cards)
-            };
-
-            let msg = GetCardsResponse::Ok { cards, position };
-            send(&s, &msg);
+            RoomContext { socket: s, io, state: state.0, room }.request(RoomCommand::GetCards(client_data.user), reply);
         });
 
-        s.on(MakeBidMessage::MSG_TYPE, |s: SocketRef, Data::<MakeBidMessage>(data)| async move {
-            let Some(client_data) = get_client_or_response(&s, &MakeBidResponse::Unauthenticated) else {return};
+        s.on(KibitzMessage::MSG_TYPE, |s: SocketRef, reply: Responder, Data::<KibitzMessage>(data), state: State<ServerState>, io: SocketIo| async move {
+            let Some(client_data) = get_client_or_response(&reply, &KibitzResponse::Unauthenticated) else {return};
 
             let Some(room) = client_data.room else {
-                send(&s, &MakeBidResponse::NotInRoom);
-                return;
-            };
-
-            let mut room_lock = room.write().await;
-
-            let Some(player) = room_lock.find_player_position(&client_data.user) else {
-                send(&s, &MakeBidResponse::SpectatorNotAllowed);
+                reply.send(&KibitzResponse::NotInRoom);
                 return;
             };
 
-            match room_lock.game.place_bid(&player, data.bid) {
-                BidStatus::Error(bid_error) => match bid_error {
-                    BidError::GameStateMismatch => {
-                        send(&s, &MakeBidResponse::AuctionNotInProcess);
-                    },
-                    BidError::PlayerOutOfTurn => {
-                        send(&s, &MakeBidResponse::NotYourTurn);
-                    },
-                    BidError::WrongBid | BidError::CantDouble | BidError::CantRedouble => { // TODO: maybe handle double separately
-                        send(&s, &MakeBidResponse::InvalidBid);
-                    },
-                },
-                next_state => {
-                    send(&s, &MakeBidResponse::Ok);
-
-                    let mut notifications = Vec::new();
-                    notifications.push(notify(&s, &room_lock.info.id, MakeBidNotification {
-                        player,
-                        bid: data.bid,
-                    }));
-                    if next_state == BidStatus::Auction {
-                        notifications.push(notify(&s, &room_lock.info.id, AskBidNotification {
-                            player: room_lock.game.current_player,
-                            max_bid: room_lock.game.max_bid,
-                        }));
-                    } else {
-                        sleep(Duration::from_secs(2)).await;
-
-                        notifications.push(notify(&s, &room_lock.info.id, AuctionFinishedNotification::Winner(AuctionFinishedNotificationInner {
-                            winner: room_lock.game.max_bidder,
-                            max_bid: room_lock.game.max_bid,
-                            game_value: room_lock.game.game_value,
-                        })));
-
-                        if next_state == BidStatus::Finished {
-                            // 4 passes
-
-                            notifications.push(notify(&s, &room_lock.info.id, GameFinishedNotification{result: None}));
-                        } else {
-                            notifications.push(notify(&s, &room_lock.info.id, AskTrickNotification {
-                                player: room_lock.game.current_player,
-                                cards: room_lock.game.current_trick.clone(),
-                            }));
-                        }
-                    }
-                    room_lock.append_notifications(notifications);
-                },
-            }
+            RoomContext { socket: s, io, state: state.0, room }.request(RoomCommand::Kibitz(client_data.user, data), reply);
         });
 
-        s.on(MakeTrickMessage::MSG_TYPE, |s: SocketRef, Data::<MakeTrickMessage>(data), state: State<ServerState>| async move {
-            let Some(client_data) = get_client_or_response(&s, &MakeTrickResponse::Unauthenticated) else {return};
+        s.on(MakeBidMessage::MSG_TYPE, |s: SocketRef, reply: Responder, Data::<MakeBidMessage>(data), state: State<ServerState>, io: SocketIo| async move {
+            let Some(client_data) = get_client_or_response(&reply, &MakeBidResponse::Unauthenticated) else {return};
 
             let Some(room) = client_data.room else {
-                send(&s, &MakeTrickResponse::NotInRoom);
+                reply.send(&MakeBidResponse::NotInRoom);
                 return;
             };
 
-            let mut room_lock = room.write().await;
+            RoomContext { socket: s, io, state: state.0, room }.request(RoomCommand::MakeBid(client_data.user, data), reply);
+        });
 
-            let Some(player) = room_lock.find_player_position(&client_data.user) else {
-                send(&s, &MakeTrickResponse::SpectatorNotAllowed);
+        s.on(SetConventionCardMessage::MSG_TYPE, |s: SocketRef, reply: Responder, Data::<SetConventionCardMessage>(data), state: State<ServerState>, io: SocketIo| async move {
+            let Some(client_data) = get_client_or_response(&reply, &SetConventionCardResponse::Unauthenticated) else {return};
+
+            let Some(room) = client_data.room else {
+                reply.send(&SetConventionCardResponse::NotInRoom);
                 return;
             };
 
-            let room_id = room_lock.info.id.clone();
-
-            let trick_result = room_lock.game.trick(&player, &data.card);
-            send(&s, &MakeTrickResponse::from(&trick_result));
+            RoomContext { socket: s, io, state: state.0, room }.request(RoomCommand::SetConventionCard(client_data.user, data), reply);
+        });
 
-            let mut notifications = Vec::new();
+        s.on(MakeTrickMessage::MSG_TYPE, |s: SocketRef, reply: Responder, Data::<MakeTrickMessage>(data), state: State<ServerState>, io: SocketIo| async move {
+            let Some(client_data) = get_client_or_response(&reply, &MakeTrickResponse::Unauthenticated) else {return};
 
-            if let TrickStatus::Error(_) = trick_result {
+            let Some(room) = client_data.room else {
+                reply.send(&MakeTrickResponse::NotInRoom);
                 return;
-            }
-
-            notifications.push(notify(&s, &room_id, MakeTrickNotification {
-                player,
-                card: data.card,
-            }));
-
-            match trick_result {
-                TrickStatus::TrickInProgress => {
-                    if room_lock.game.trick_no == 0 && room_lock.game.current_trick.len() == 1 {
-                        let msg = DummyCardsNotification::new(room_lock.game.get_dummy_cards().unwrap().clone(),
-                        room_lock.game.get_dummy_player().unwrap());
-                        notifications.push(notify(&s, &room_id, msg));
-                    }
-                }
-                TrickStatus::TrickFinished(trick_state) => {
-                    sleep(Duration::from_secs(2)).await;
-
-                    notifications.push(notify(&s, &room_id, TrickFinishedNotification::from(trick_state)));
-                }
-                TrickStatus::DealFinished(deal_finished) => {
-                    sleep(Duration::from_secs(2)).await;
-
-                    notifications.push(notify(&s, &room_id, TrickFinishedNotification::from(deal_finished.trick_state.clone())));
-
-                    sleep(Duration::from_secs(2)).await;
-
-                    notifications.push(notify(&s, &room_id, DealFinishedNotification::from(deal_finished.clone())));
-
-                    if deal_finished.is_game_finished {
-                        notifications.push(notify(&s, &room_id, GameFinishedNotification{result: None}));
-
-                        state.write().await.remove_room(&room_id);
-
-                        return;
-                    } else {
-                        room_lock.game.start();
-
-                        notifications.push(notify(&s, &room_id, AskBidNotification {
-                            player: deal_finished.next_deal_bidder,
-                            max_bid: Bid::Pass,
-                        }));
-                    }
-                }
-                TrickStatus::Error(_) => ()
-            }
-
-            notifications.push(notify(&s, &room_id, AskTrickNotification {
-                player: room_lock.game.current_player,
-                cards: room_lock.game.current_trick.clone(),
-            }));
+            };
 
-            room_lock.append_notifications(notifications);
+            RoomContext { socket: s, io, state: state.0, room }.request(RoomCommand::MakeTrick(client_data.user, data), reply);
         });
 
         s.on_disconnect(
-            move |s: SocketRef, state: State<ServerState>| async move {
+            move |s: SocketRef, state: State<ServerState>, io: SocketIo| async move {
                 let Some(client_data) = s.extensions.get::<ClientData>() else { return; };
 
                 let username = client_data.user.get_username();
@@ -471,7 +497,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 state.write().await.remove_user(&client_data.user);
 
                 if let Some(room) = client_data.room.clone() {
-                    leave_room_handler(s, client_data.clone(), room, false).await;
+                    leave_room_handler(s, io, state.0, client_data.clone(), room, None).await;
                 }
 
                 info!("User \"{}\" disconnected", username);
@@ -479,31 +505,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     });
 
-    let app = axum::Router::new()
-        .nest_service("/", ServeDir::new("dist"))
+    let addr = SocketAddr::new(config.bind_address, config.port);
+    let gateway = Gateway::new(addr, &config.gateway);
+
+    let shutdown = state.read().await.shutdown_signal();
+
+    let mut app = axum::Router::new()
+        .route("/ws", axum::routing::get(gateway::websocket_handler))
+        .with_state(gateway.clone())
+        .nest("/api", api::router(state.clone()))
+        .merge(metrics::router(state.clone(), io.clone()));
+    if let Some(token) = &config.admin_token {
+        app = app.nest("/admin", admin::router(state, io.clone(), token.clone()));
+    }
+    let app = app
+        .nest_service("/", ServeDir::new(&config.static_dir))
         .layer(
             ServiceBuilder::new()
-                .layer(CorsLayer::permissive())
+                .layer(config.cors_layer()?)
                 .layer(layer),
         );
-    let args = clap::Command::new("bridge-server")
-        .arg(
-            clap::Arg::new("port")
-                .short('p')
-                .long("port")
-                .value_name("PORT")
-                .help("Port to listen on")
-                .default_value("3000"),
-        )
-        .get_matches();
-
-    let port = args.get_one::<String>("port").unwrap();
-    let addr = format!("0.0.0.0:{}", port);
+
+    if let Some(tcp_port) = config.tcp_port {
+        let addr = SocketAddr::new(config.bind_address, tcp_port);
+        info!("Accepting TCP clients on {}", addr);
+        let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
+        tokio::spawn(gateway.serve_tcp(listener));
+    }
 
     info!("Starting server on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            shutdown.notified().await;
+            info!("Server drained, shutting down");
+            io.close().await;
+        })
+        .await
+        .unwrap();
 
     Ok(())
 }
@@ -0,0 +1,178 @@
+use std::{sync::LazyLock, time::Duration};
+
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+use common::GameState;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use serde::Serialize;
+use serde_json::Value;
+use socketioxide::SocketIo;
+
+use crate::state::ServerState;
+
+/// Metrics of the server, exported in the Prometheus text format on `/metrics`
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub struct Metrics {
+    registry: Registry,
+    connected_sockets: IntGauge,
+    logged_in_users: IntGauge,
+    rooms: IntGaugeVec,
+    messages: IntCounterVec,
+    request_duration: HistogramVec,
+    errors: IntCounterVec,
+}
+
+/// Values of the `state` label of `bridge_rooms`
+const GAME_STATES: [GameState; 4] = [
+    GameState::WaitingForPlayers,
+    GameState::Auction,
+    GameState::Tricking,
+    GameState::Finished,
+];
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("bridge".into()), None).unwrap();
+
+        let connected_sockets =
+            IntGauge::new("connected_sockets", "Sockets connected to the server").unwrap();
+        let logged_in_users = IntGauge::new("logged_in_users", "Users logged in").unwrap();
+        let rooms = IntGaugeVec::new(
+            Opts::new("rooms", "Active rooms by the state of their game"),
+            &["state"],
+        )
+        .unwrap();
+        let messages = IntCounterVec::new(
+            Opts::new("messages_total", "Requests handled by their MSG_TYPE"),
+            &["msg_type"],
+        )
+        .unwrap();
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "request_duration_seconds",
+                "Time from receiving a request to sending its response, by the MSG_TYPE of the request",
+            ),
+            &["msg_type"],
+        )
+        .unwrap();
+        let errors = IntCounterVec::new(
+            Opts::new(
+                "errors_total",
+                "Responses other than Ok, by their MSG_TYPE and variant",
+            ),
+            &["response", "variant"],
+        )
+        .unwrap();
+
+        registry
+            .register(Box::new(connected_sockets.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(logged_in_users.clone()))
+            .unwrap();
+        registry.register(Box::new(rooms.clone())).unwrap();
+        registry.register(Box::new(messages.clone())).unwrap();
+        registry
+            .register(Box::new(request_duration.clone()))
+            .unwrap();
+        registry.register(Box::new(errors.clone())).unwrap();
+
+        Self {
+            registry,
+            connected_sockets,
+            logged_in_users,
+            rooms,
+            messages,
+            request_duration,
+            errors,
+        }
+    }
+
+    pub fn request_received(&self, msg_type: &str) {
+        self.messages.with_label_values(&[msg_type]).inc();
+    }
+
+    /// Records the response to a request of type `msg_type`, received `elapsed` ago
+    pub fn response_sent<M: Serialize>(
+        &self,
+        msg_type: &str,
+        elapsed: Duration,
+        response_type: &str,
+        response: &M,
+    ) {
+        self.request_duration
+            .with_label_values(&[msg_type])
+            .observe(elapsed.as_secs_f64());
+
+        let response = serde_json::to_value(response).unwrap_or_default();
+        if let Some(variant) = response_variant(&response).filter(|variant| *variant != "Ok") {
+            self.errors
+                .with_label_values(&[response_type, variant])
+                .inc();
+        }
+    }
+}
+
+/// Variant of a response serialized from an enum, `None` for a struct.
+/// Variants are told apart from fields by being capitalized.
+fn response_variant(response: &Value) -> Option<&str> {
+    match response {
+        Value::String(variant) => Some(variant),
+        Value::Object(map) if map.len() == 1 => map
+            .keys()
+            .next()
+            .map(String::as_str)
+            .filter(|key| key.starts_with(char::is_uppercase)),
+        _ => None,
+    }
+}
+
+#[derive(Clone)]
+struct Scrape {
+    state: ServerState,
+    io: SocketIo,
+}
+
+/// Router serving `/metrics`. Gauges are read from the server when scraped.
+pub fn router(state: ServerState, io: SocketIo) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(Scrape { state, io })
+}
+
+async fn metrics(State(scrape): State<Scrape>) -> impl IntoResponse {
+    let metrics = &*METRICS;
+
+    metrics
+        .connected_sockets
+        .set(scrape.io.sockets().map_or(0, |sockets| sockets.len()) as i64);
+    {
+        let state = scrape.state.read().await;
+        metrics.logged_in_users.set(state.user_count() as i64);
+
+        let rooms = state.get_rooms();
+        for game_state in GAME_STATES {
+            let count = rooms
+                .iter()
+                .filter(|room| room.public_state().state == game_state)
+                .count();
+            metrics
+                .rooms
+                .with_label_values(&[&format!("{:?}", game_state)])
+                .set(count as i64);
+        }
+    }
+
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    encoder
+        .encode(&metrics.registry.gather(), &mut buffer)
+        .unwrap();
+    (
+        [(header::CONTENT_TYPE, encoder.format_type().to_owned())],
+        buffer,
+    )
+}
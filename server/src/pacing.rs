@@ -0,0 +1,103 @@
+use std::{future::Future, time::Duration};
+
+use futures::future::{ready, BoxFuture, FutureExt};
+use socketioxide::extract::SocketRef;
+use tokio::{
+    sync::mpsc::{unbounded_channel, UnboundedSender},
+    time::sleep,
+};
+
+use crate::{
+    config,
+    game_flow::{RoomContext, Transition},
+    room_actor::RoomCommand,
+    utils::SendableNotification,
+};
+
+/// Sends transitions of a room back to it one after another, each after a pause.
+/// The room keeps handling commands during the pause.
+pub struct Pacer {
+    /// Transitions with whether to pause before them
+    sender: UnboundedSender<(RoomContext, BoxFuture<'static, Transition>, bool)>,
+    /// Number of scheduled transitions that haven't run yet
+    pending: usize,
+    /// Pause between replayed notifications, see `replay_notifications`
+    replay_delay: Duration,
+}
+
+impl Pacer {
+    pub fn new(pacing: &config::Pacing) -> Self {
+        let transition_delay = pacing.transition_delay();
+        let (sender, mut receiver) =
+            unbounded_channel::<(RoomContext, BoxFuture<'static, Transition>, bool)>();
+
+        tokio::spawn(async move {
+            while let Some((ctx, transition, pause)) = receiver.recv().await {
+                if pause {
+                    sleep(transition_delay).await;
+                }
+                let transition = transition.await;
+                ctx.send(RoomCommand::Transition(transition));
+            }
+        });
+
+        Self {
+            sender,
+            pending: 0,
+            replay_delay: pacing.replay_delay(),
+        }
+    }
+
+    /// Schedules the transition after the ones already waiting
+    pub fn schedule(&mut self, ctx: &RoomContext, transition: Transition) {
+        self.schedule_after(ctx, ready(transition));
+    }
+
+    /// Schedules the transition made by `work` after the ones already waiting.
+    /// The work runs outside of the room, the pause doesn't end before it's done.
+    pub fn schedule_after(
+        &mut self,
+        ctx: &RoomContext,
+        work: impl Future<Output = Transition> + Send + 'static,
+    ) {
+        self.pending += 1;
+        self.sender.send((ctx.clone(), work.boxed(), true)).ok();
+    }
+
+    /// Schedules the transition made by `work` after the ones already waiting, without the pause.
+    /// It's for transitions that don't follow a move, like the start of the game.
+    pub fn schedule_unpaused(
+        &mut self,
+        ctx: &RoomContext,
+        work: impl Future<Output = Transition> + Send + 'static,
+    ) {
+        self.pending += 1;
+        self.sender.send((ctx.clone(), work.boxed(), false)).ok();
+    }
+
+    /// Marks the oldest transition as run, called by the room when it gets it
+    pub fn finish(&mut self) {
+        self.pending = self.pending.saturating_sub(1);
+    }
+
+    /// Whether no transition is waiting. Moves are refused until then, so that they can't overtake notifications.
+    pub fn is_idle(&self) -> bool {
+        self.pending == 0
+    }
+
+    pub fn replay_delay(&self) -> Duration {
+        self.replay_delay
+    }
+}
+
+/// Sends notifications of a running game to a user that joined it, pausing between them like the game does
+pub async fn replay_notifications(
+    socket: SocketRef,
+    notifications: Vec<Box<dyn SendableNotification + Send + Sync>>,
+    delay: Duration,
+) {
+    for notification in notifications {
+        notification.send(&socket).await;
+        sleep(delay).await;
+    }
+}
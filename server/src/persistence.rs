@@ -0,0 +1,72 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use common::{record::DealRecord, room::RoomId};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Line of the deal log
+#[derive(Serialize, Deserialize)]
+struct LoggedDeal {
+    room: RoomId,
+    record: DealRecord,
+}
+
+/// Records of finished deals kept in `deal_records.jsonl` of the data directory, one JSON object per line,
+/// so that the REST API still serves them after a restart
+#[derive(Clone)]
+pub struct DealLog {
+    path: PathBuf,
+}
+
+impl DealLog {
+    /// Opens the log in `data_dir`, creating the directory if needed
+    pub fn open(data_dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(data_dir)?;
+        Ok(Self {
+            path: data_dir.join("deal_records.jsonl"),
+        })
+    }
+
+    /// Reads all records by their room, skipping lines that can't be parsed
+    pub fn load(&self) -> io::Result<HashMap<RoomId, Vec<DealRecord>>> {
+        let mut records: HashMap<RoomId, Vec<DealRecord>> = HashMap::new();
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(records),
+            Err(err) => return Err(err),
+        };
+        for (index, line) in text.lines().enumerate() {
+            match serde_json::from_str::<LoggedDeal>(line) {
+                Ok(deal) => records.entry(deal.room).or_default().push(deal.record),
+                Err(err) => warn!(
+                    "Skipping line {} of {}: {}",
+                    index + 1,
+                    self.path.display(),
+                    err
+                ),
+            }
+        }
+        Ok(records)
+    }
+
+    pub fn append(&self, room: &RoomId, record: &DealRecord) {
+        let line = serde_json::to_string(&LoggedDeal {
+            room: room.clone(),
+            record: record.clone(),
+        })
+        .unwrap();
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(err) = result {
+            warn!("Can't write to {}: {}", self.path.display(), err);
+        }
+    }
+}
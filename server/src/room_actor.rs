@@ -0,0 +1,388 @@
+use common::{
+    message::{
+        client_message::{
+            KibitzMessage, MakeBidMessage, MakeTrickMessage, SelectPlaceMessage,
+            SetConventionCardMessage,
+        },
+        server_notification::{
+            ConventionCardNotification, GameFinishedNotification, MakeBidNotification,
+            SelectPlaceNotification, ServerMessageNotification,
+        },
+        server_response::{
+            GetCardsResponse, KibitzResponse, ListPlacesResponse, MakeBidResponse,
+            MakeTrickResponse, SelectPlaceResponse, SetConventionCardResponse,
+        },
+    },
+    room::RoomInfo,
+    user::User,
+    BidError, BidStatus, GameState, Player,
+};
+use std::time::Duration;
+
+use socketioxide::{socket::Sid, SocketIo};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedSender},
+    watch,
+};
+use tracing::info;
+
+use crate::{
+    api::PublicRoomState,
+    broadcast::delayed,
+    game_flow::{
+        bid_placed, card_played, move_on_timeout, run_transition, RoomContext, Transition,
+    },
+    handlers::RoomWrapper,
+    pacing::replay_notifications,
+    state::{RoomState, ServerState},
+    utils::{notify, notify_others, Responder, SendableNotification},
+    ClientData,
+};
+
+/// Request to a room. Commands of a room are handled one at a time, in the order they were sent.
+pub enum RoomCommand {
+    Join(User, Sid),
+    Leave(User),
+    ListPlaces,
+    SelectPlace(User, SelectPlaceMessage),
+    GetCards(User),
+    Kibitz(User, KibitzMessage),
+    MakeBid(User, MakeBidMessage),
+    SetConventionCard(User, SetConventionCardMessage),
+    MakeTrick(User, MakeTrickMessage),
+    /// Automatic move of a player that ran out of time, with the number of the timed move
+    Timeout(u64),
+    /// Transition scheduled by the pacer of the room
+    Transition(Transition),
+}
+
+/// Message to a room actor
+enum Envelope {
+    Command(Box<(RoomContext, RoomCommand, Responder)>),
+    /// Closes the room, see `RoomHandle::close`
+    Close {
+        io: SocketIo,
+        state: ServerState,
+        message: String,
+    },
+}
+
+/// Address of a room actor, the only owner of the state of the room.
+/// Sending never waits, so handlers and other rooms can't deadlock on a room.
+#[derive(Clone)]
+pub struct RoomHandle {
+    /// Settings of the room, they don't change after it's created
+    pub info: RoomInfo,
+    sender: UnboundedSender<Envelope>,
+    /// Public state of the room, updated after every command
+    public: watch::Receiver<PublicRoomState>,
+    /// Public state with the delay of the broadcast of the room, the same as `public` if it isn't broadcast
+    published: watch::Receiver<PublicRoomState>,
+}
+
+impl RoomHandle {
+    /// Spawns the actor of the room.
+    /// It stops once the room is closed, or after the room is removed and all its handles are dropped.
+    pub fn spawn(mut room: RoomState) -> Self {
+        let (sender, mut receiver) = unbounded_channel::<Envelope>();
+        let info = room.info.clone();
+        let (public_sender, public) = watch::channel(PublicRoomState::new(&room));
+        let published = match info.broadcast_delay_secs {
+            Some(delay_secs) => delayed(public.clone(), Duration::from_secs(delay_secs)),
+            None => public.clone(),
+        };
+
+        tokio::spawn(async move {
+            while let Some(envelope) = receiver.recv().await {
+                match envelope {
+                    Envelope::Command(command) => {
+                        let (ctx, command, reply) = *command;
+                        handle_command(&ctx, &mut room, command, &reply).await;
+                        public_sender.send_replace(PublicRoomState::new(&room));
+                    }
+                    Envelope::Close { io, state, message } => {
+                        close(&io, &state, &room, message).await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self {
+            info,
+            sender,
+            public,
+            published,
+        }
+    }
+
+    /// Public state of the room after the last handled command
+    pub fn public_state(&self) -> PublicRoomState {
+        self.public.borrow().clone()
+    }
+
+    /// Public state of the room as it may be shown outside of it, see `published`
+    pub fn published_state(&self) -> PublicRoomState {
+        self.published.borrow().clone()
+    }
+
+    /// Closes the room after the commands sent before: its users are told `message` and put back in the lobby,
+    /// and the game stops with its clock and pending transitions.
+    /// The other room of a team match is closed too, as the match can't be finished anymore.
+    pub fn close(&self, io: SocketIo, state: ServerState, message: String) {
+        self.sender
+            .send(Envelope::Close { io, state, message })
+            .ok();
+    }
+
+    fn send(&self, ctx: RoomContext, command: RoomCommand, reply: Responder) {
+        self.sender
+            .send(Envelope::Command(Box::new((ctx, command, reply))))
+            .ok();
+    }
+}
+
+async fn close(io: &SocketIo, state: &ServerState, room: &RoomState, message: String) {
+    let room_id = room.info.id.clone();
+    let linked_room = {
+        let mut state = state.write().await;
+        state.remove_room(&room_id);
+        room.linked_room()
+            .and_then(|linked_room| state.get_room(&linked_room))
+    };
+
+    ServerMessageNotification {
+        message: message.clone(),
+    }
+    .emit_to(io.within(RoomWrapper(room_id.clone())));
+    GameFinishedNotification { result: None }.emit_to(io.within(RoomWrapper(room_id.clone())));
+
+    for sid in room.user_sockets() {
+        let Some(s) = io.get_socket(sid) else {
+            continue;
+        };
+        s.leave(RoomWrapper(room_id.clone())).ok();
+        if let Some(client_data) = s.extensions.get::<ClientData>() {
+            s.extensions.insert(ClientData {
+                room: None,
+                ..client_data
+            });
+        }
+    }
+    info!("Room \"{}\" was closed", room_id.as_str());
+
+    if let Some(linked_room) = linked_room {
+        linked_room.close(io.clone(), state.clone(), message);
+    }
+}
+
+impl RoomContext {
+    /// Sends the command to the room of the context, on behalf of its socket
+    pub fn send(&self, command: RoomCommand) {
+        let reply = Responder::without_ack(self.socket.clone());
+        self.room.send(self.clone(), command, reply);
+    }
+
+    /// Sends the command of a request to the room, the room answers it with `reply`
+    pub fn request(&self, command: RoomCommand, reply: Responder) {
+        self.room.send(self.clone(), command, reply);
+    }
+}
+
+async fn handle_command(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+    command: RoomCommand,
+    reply: &Responder,
+) {
+    match command {
+        RoomCommand::Join(user, sid) => room.user_join_room(user, sid),
+        RoomCommand::Leave(user) => {
+            room.user_leave_room(&user);
+        }
+        RoomCommand::ListPlaces => {
+            reply.send(&ListPlacesResponse::Ok(room.get_player_positions()));
+        }
+        RoomCommand::SelectPlace(user, data) => select_place(ctx, room, reply, user, data.position),
+        RoomCommand::GetCards(user) => {
+            let Some(position) = room.find_player_position(&user) else {
+                reply.send(&GetCardsResponse::SpectatorNotAllowed);
+                return;
+            };
+            let cards = room.game.get_cards(&position).clone();
+            reply.send(&GetCardsResponse::Ok { cards, position });
+        }
+        RoomCommand::Kibitz(user, data) => {
+            if room.find_player_position(&user).is_some() {
+                reply.send(&KibitzResponse::PlayerNotAllowed);
+                return;
+            }
+            if !room.info.allow_kibitzing {
+                reply.send(&KibitzResponse::KibitzingForbidden);
+                return;
+            }
+            let hands = Player::ALL.map(|player| {
+                data.view
+                    .shows(player)
+                    .then(|| room.game.get_cards(&player).clone())
+            });
+            room.add_kibitzer(user);
+            reply.send(&KibitzResponse::Ok { hands });
+        }
+        RoomCommand::MakeBid(user, data) => make_bid(ctx, room, reply, user, data),
+        RoomCommand::SetConventionCard(user, data) => {
+            let Some(player) = room.find_player_position(&user) else {
+                reply.send(&SetConventionCardResponse::SpectatorNotAllowed);
+                return;
+            };
+
+            room.set_convention_card(player, data.card.clone());
+            reply.send(&SetConventionCardResponse::Ok);
+
+            let notification = notify(
+                &ctx.io,
+                &room.info.id,
+                ConventionCardNotification {
+                    player,
+                    card: data.card,
+                },
+            );
+            room.append_notifications(vec![notification]);
+        }
+        RoomCommand::MakeTrick(user, data) => {
+            let Some(player) = room.find_player_position(&user) else {
+                reply.send(&MakeTrickResponse::SpectatorNotAllowed);
+                return;
+            };
+
+            if !room.pacer.is_idle() {
+                // Previous move is still being shown
+                reply.send(&MakeTrickResponse::MoveInProgress);
+                return;
+            }
+
+            let trick_result = room.game.trick(&player, &data.card);
+            reply.send(&MakeTrickResponse::from(&trick_result));
+
+            card_played(ctx, room, player, data.card, trick_result);
+        }
+        RoomCommand::Timeout(move_no) => move_on_timeout(ctx, room, move_no),
+        RoomCommand::Transition(transition) => run_transition(ctx, room, transition).await,
+    }
+}
+
+fn select_place(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+    reply: &Responder,
+    user: User,
+    position: Option<Player>,
+) {
+    if let (Some(table), Some(position)) = (&room.tournament, position) {
+        if !table.is_seat_of(&user, position) {
+            reply.send(&SelectPlaceResponse::NotYourSeat);
+            return;
+        }
+    }
+    if position.is_some() && room.has_kibitzed(&user) {
+        reply.send(&SelectPlaceResponse::KibitzedDeal);
+        return;
+    }
+    if !room.user_select_place(&user, position) {
+        reply.send(&SelectPlaceResponse::PlaceAlreadyTaken);
+        return;
+    }
+    let room_id = room.info.id.clone();
+
+    let position_str = match position {
+        Some(pos) => pos.to_u8().to_string(),
+        None => "*spectator*".into(),
+    };
+    info!(
+        "User \"{}\" selected place {} in room \"{}\"",
+        user.get_username(),
+        position_str,
+        room_id.as_str()
+    );
+
+    reply.send(&SelectPlaceResponse::Ok);
+
+    notify_others(
+        &ctx.socket,
+        &room_id,
+        &SelectPlaceNotification { user, position },
+    );
+
+    if room.game.state != GameState::WaitingForPlayers {
+        // Player joined to a game that is already running
+        tokio::spawn(replay_notifications(
+            ctx.socket.clone(),
+            room.sent_notifications(position),
+            room.pacer.replay_delay(),
+        ));
+        return;
+    }
+
+    let all_places_taken = room.get_player_positions().iter().all(Option::is_some);
+    // A start that is already scheduled checks the places again when it runs
+    if all_places_taken && room.pacer.is_idle() {
+        // The first board is dealt outside of the room, the game starts when it's done
+        let dealt = room.deal(room.board_index());
+        room.pacer
+            .schedule_unpaused(ctx, async move { Transition::StartGame(dealt.await) });
+    }
+}
+
+fn make_bid(
+    ctx: &RoomContext,
+    room: &mut RoomState,
+    reply: &Responder,
+    user: User,
+    data: MakeBidMessage,
+) {
+    let Some(player) = room.find_player_position(&user) else {
+        reply.send(&MakeBidResponse::SpectatorNotAllowed);
+        return;
+    };
+
+    if !room.pacer.is_idle() {
+        // Previous move is still being shown
+        reply.send(&MakeBidResponse::MoveInProgress);
+        return;
+    }
+
+    let annotation = room
+        .get_convention_card(player)
+        .and_then(|card| card.annotate(&room.game.auction, data.bid));
+
+    match room.game.place_bid(&player, data.bid) {
+        BidStatus::Error(bid_error) => match bid_error {
+            BidError::GameStateMismatch => {
+                reply.send(&MakeBidResponse::AuctionNotInProcess);
+            }
+            BidError::PlayerOutOfTurn => {
+                reply.send(&MakeBidResponse::NotYourTurn);
+            }
+            BidError::WrongBid => {
+                reply.send(&MakeBidResponse::InvalidBid);
+            }
+            BidError::CantDouble => {
+                reply.send(&MakeBidResponse::CantDouble);
+            }
+            BidError::CantRedouble => {
+                reply.send(&MakeBidResponse::CantRedouble);
+            }
+        },
+        next_state => {
+            reply.send(&MakeBidResponse::Ok);
+
+            let notification = MakeBidNotification {
+                player,
+                bid: data.bid,
+                annotation,
+                explanation: data.explanation,
+            };
+            bid_placed(ctx, room, notification, next_state);
+        }
+    }
+}
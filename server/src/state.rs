@@ -1,25 +1,87 @@
 // use futures::stream::{StreamExt, TryStreamExt, };
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
-    sync::Arc,
+    future::Future,
+    io,
+    sync::{Arc, Mutex},
     time::Duration,
 };
 
-use futures::stream::StreamExt;
-use socketioxide::extract::SocketRef;
-use tokio::{sync::RwLock, time::sleep};
+use rand::{rngs::StdRng, SeedableRng};
+use socketioxide::{socket::Sid, SocketIo};
+use tokio::{
+    sync::{Notify, RwLock},
+    task::JoinHandle,
+};
+use tracing::warn;
 
 use common::{
-    message::server_response::RegisterRoomResponse,
+    bidding_system::ConventionCard,
+    deal_generator::{random_deal, DealConstraints},
+    duplicate::board_vulnerability,
+    message::{
+        server_notification::{
+            BroadcastDealNotification, MatchScoreboardNotification, TournamentStandingsNotification,
+        },
+        server_response::{
+            JoinRoomResponse, RegisterRoomResponse, RegisterTournamentResponse, StartRoundResponse,
+        },
+    },
+    par::{par, Par},
+    record::DealRecord,
     room::{RoomId, RoomInfo, Visibility},
+    solver::solve_all,
+    tournament::Movement,
     user::User,
-    Game, Player,
+    Card, Game, GameState, Player,
 };
 
-use crate::utils::SendableNotification;
+use crate::{
+    broadcast::Broadcast,
+    clock::Clock,
+    config::{self, Config},
+    handlers::Group,
+    pacing::Pacer,
+    persistence::DealLog,
+    room_actor::RoomHandle,
+    team_match::{Table, TeamMatch},
+    tournament::{RoundTable, Tournament},
+    utils::SendableNotification,
+};
+
+/// Results of a board finished in a room of a team match or a tournament
+pub enum BoardOutcome {
+    Match(MatchScoreboardNotification),
+    /// Standings and the group of users following the tournament, once all tables finished the round
+    Tournament(Option<(Group, TournamentStandingsNotification)>),
+}
+
+/// Board played at several tables, by both rooms of a team match or by all tables of a tournament round
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SharedBoard {
+    Match {
+        open_room: RoomId,
+        board_index: usize,
+    },
+    Tournament {
+        tournament_id: String,
+        board_no: usize,
+    },
+}
+
+/// Board of a team match or a tournament finished in a room
+pub struct BoardFinished {
+    pub outcome: BoardOutcome,
+    pub board: SharedBoard,
+    /// Whether every table of the board played it
+    pub board_complete: bool,
+    /// Whether the room played all its boards
+    pub all_boards_played: bool,
+}
 
 pub struct RoomState {
-    users: HashSet<User>,
+    /// Users in the room with ids of their sockets
+    users: HashMap<User, Sid>,
 
     /// Array of 4 players, where None means that the place is empty.
     /// This array is not cleared when player disconnects, so that no other player can take this place when player disconnects.
@@ -29,17 +91,91 @@ pub struct RoomState {
     /// It is used to inform user that disconnected during game.
     sent_notifications: Vec<Box<dyn SendableNotification + Send + Sync>>,
 
+    /// Convention cards of North-South and East-West partnerships
+    convention_cards: [Option<ConventionCard>; 2],
+
+    /// Users that looked at the hands of the running deal, they can't take a place before the next deal
+    kibitzers: HashSet<User>,
+
+    /// Time limits of players, `None` if the room has none
+    pub clock: Option<Clock>,
+
+    /// Team match this room is a table of
+    pub team_match: Option<(Arc<Mutex<TeamMatch>>, Table)>,
+    /// Delayed broadcast of the room, `None` if the room isn't broadcast
+    pub broadcast: Option<Broadcast>,
+
+    /// Queue of delayed transitions of the game
+    pub pacer: Pacer,
+
+    /// Tournament table played in this room
+    pub tournament: Option<RoundTable>,
+    /// Index of the board played in the team match or the tournament round
+    board_index: usize,
+    /// Number of deals dealt in the room, the seed of the room is advanced by it
+    deals_dealt: u64,
+    /// Par of the running deal, solved in the background while the deal is played
+    par: Option<JoinHandle<Par>>,
+
     pub game: Game,
     pub info: RoomInfo,
 }
 
+/// Random number generator of the deal with given number in a room.
+/// Rooms with a seed deal the same boards every time, the others deal from entropy.
+fn deal_rng(seed: Option<u64>, deal_no: u64) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(deal_no)),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Deals random hands matching the constraints, or any hands if there are none.
+/// Matching hands may take many attempts, so they are dealt on the blocking thread pool.
+async fn deal_hands(constraints: Option<DealConstraints>, mut rng: StdRng) -> [Vec<Card>; 4] {
+    let Some(constraints) = constraints else {
+        return random_deal(&mut rng);
+    };
+    tokio::task::spawn_blocking(move || {
+        constraints.generate(&mut rng).unwrap_or_else(|| {
+            warn!("No deal matching the constraints was found, dealing at random");
+            random_deal(&mut rng)
+        })
+    })
+    .await
+    .unwrap()
+}
+
+/// Whether the deal constraints of a new room, if it has any, can be met.
+/// Impossible constraints are refused right away, possible ones have to produce the first deal of the room in time.
+pub async fn can_deal(info: &RoomInfo) -> bool {
+    let Some(constraints) = info.deal_constraints.clone() else {
+        return true;
+    };
+    let mut rng = deal_rng(info.seed, 0);
+    constraints.is_possible()
+        && tokio::task::spawn_blocking(move || constraints.generate(&mut rng).is_some())
+            .await
+            .unwrap_or(false)
+}
+
 impl RoomState {
-    pub fn new(info: RoomInfo) -> Self {
+    pub fn new(info: RoomInfo, pacing: &config::Pacing) -> Self {
         Self {
-            users: HashSet::new(),
+            users: HashMap::new(),
             player_positions: [None, None, None, None],
             game: Game::new(),
             sent_notifications: Vec::new(),
+            convention_cards: [None, None],
+            kibitzers: HashSet::new(),
+            clock: info.time_control.map(Clock::new),
+            team_match: None,
+            tournament: None,
+            broadcast: None,
+            pacer: Pacer::new(pacing),
+            board_index: 0,
+            deals_dealt: 0,
+            par: None,
             info,
         }
     }
@@ -57,13 +193,13 @@ impl RoomState {
         removed
     }
 
-    pub async fn user_join_room(&mut self, user: User) {
-        self.users.insert(user);
+    pub fn user_join_room(&mut self, user: User, sid: Sid) {
+        self.users.insert(user, sid);
     }
 
     pub fn user_leave_room(&mut self, user: &User) -> bool {
         self._remove_player_from_positions(user);
-        self.users.remove(user)
+        self.users.remove(user).is_some()
     }
 
     pub fn user_select_place(&mut self, user: &User, position: Option<Player>) -> bool {
@@ -81,6 +217,35 @@ impl RoomState {
         }
     }
 
+    /// Remembers that the user looked at the hands, if a deal is running
+    pub fn add_kibitzer(&mut self, user: User) {
+        if matches!(self.game.state, GameState::Auction | GameState::Tricking) {
+            self.kibitzers.insert(user);
+        }
+    }
+
+    /// Whether the user looked at the hands of the running deal
+    pub fn has_kibitzed(&self, user: &User) -> bool {
+        self.kibitzers.contains(user)
+    }
+
+    /// Number of users in the room, players and spectators
+    pub fn user_count(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Ids of sockets of all users in the room, players and spectators
+    pub fn user_sockets(&self) -> Vec<Sid> {
+        self.users.values().copied().collect()
+    }
+
+    /// Id of the other room of the team match this room is a table of
+    pub fn linked_room(&self) -> Option<RoomId> {
+        let (team_match, table) = self.team_match.as_ref()?;
+        let room_id = team_match.lock().unwrap().room(table.other()).clone();
+        Some(room_id)
+    }
+
     pub fn get_player_positions(&self) -> [Option<User>; 4] {
         self.player_positions.clone()
     }
@@ -92,34 +257,248 @@ impl RoomState {
             .map(|pos| Player::from_usize(pos).unwrap())
     }
 
+    /// Returns id of socket of the user sitting at given position
+    pub fn get_player_socket(&self, player: Player) -> Option<Sid> {
+        self.player_positions[player.to_usize()]
+            .as_ref()
+            .and_then(|user| self.users.get(user))
+            .copied()
+    }
+
+    /// Starts the delayed broadcast of the room, if its settings ask for one
+    pub fn start_broadcast(&mut self, io: &SocketIo) {
+        if let Some(delay_secs) = self.info.broadcast_delay_secs {
+            self.broadcast = Some(Broadcast::new(
+                io.clone(),
+                &self.info.id,
+                Duration::from_secs(delay_secs),
+            ));
+        }
+    }
+
+    /// Index of the board played in the team match or the tournament round
+    pub fn board_index(&self) -> usize {
+        self.board_index
+    }
+
+    /// Whether another table of the team match or the tournament round already dealt the board with given index
+    fn is_board_dealt(&self, board_index: usize) -> bool {
+        if let Some((team_match, _)) = &self.team_match {
+            team_match.lock().unwrap().is_board_dealt(board_index)
+        } else if let Some(table) = &self.tournament {
+            let tournament = table.tournament.lock().unwrap();
+            tournament.is_board_dealt(tournament.board_no(table.round, board_index))
+        } else {
+            false
+        }
+    }
+
+    /// Returns the work dealing hands of the board with given index, to be run outside of the room.
+    /// It gives `None` if another table already dealt the board, its hands are played then.
+    pub fn deal(
+        &mut self,
+        board_index: usize,
+    ) -> impl Future<Output = Option<[Vec<Card>; 4]>> + Send + 'static {
+        let rng = deal_rng(self.info.seed, self.deals_dealt);
+        self.deals_dealt += 1;
+        let constraints =
+            (!self.is_board_dealt(board_index)).then(|| self.info.deal_constraints.clone());
+        async move {
+            match constraints {
+                Some(constraints) => Some(deal_hands(constraints, rng).await),
+                None => None,
+            }
+        }
+    }
+
+    /// Starts a deal with hands made by `deal` and refills time banks of the players.
+    /// Tables of a team match or a tournament get the hands and vulnerability of the next board.
+    pub fn start_deal(&mut self, dealt: Option<[Vec<Card>; 4]>) {
+        // Hands are missing only if the board was dealt already, dealing them here just in case
+        let dealt = || dealt.unwrap_or_else(|| random_deal(&mut rand::thread_rng()));
+        let (board_no, hands) = if let Some((team_match, _)) = &self.team_match {
+            let hands = team_match
+                .lock()
+                .unwrap()
+                .board_hands(self.board_index, dealt);
+            (Some(self.board_index + 1), hands)
+        } else if let Some(table) = &self.tournament {
+            let mut tournament = table.tournament.lock().unwrap();
+            let board_no = tournament.board_no(table.round, self.board_index);
+            let hands = tournament.board_hands(board_no, dealt);
+            (Some(board_no), hands)
+        } else {
+            (None, dealt())
+        };
+
+        if let Some(board_no) = board_no {
+            self.game.vulnerable = board_vulnerability(board_no);
+        }
+        self.game.start_with_cards(hands);
+        self.par = None;
+        self.kibitzers.clear();
+        if let Some(clock) = self.clock.as_mut() {
+            clock.reset_banks();
+        }
+        if let Some(broadcast) = &self.broadcast {
+            broadcast.push(Box::new(BroadcastDealNotification {
+                seats: self.get_player_positions(),
+                deal: self.game.deal.clone(),
+            }));
+        }
+    }
+
+    /// Starts solving the par of the running deal, so that it is ready once the deal is played
+    pub fn solve_par(&mut self) {
+        let deal = self.game.deal.clone();
+        self.par = Some(tokio::task::spawn_blocking(move || {
+            par(&solve_all(&deal.hands), deal.vulnerable, deal.dealer)
+        }));
+    }
+
+    /// Par of the finished deal, waits for the solver only if the deal was played faster than it
+    pub fn take_par(&mut self) -> impl Future<Output = Option<Par>> + Send + 'static {
+        let solving = self.par.take();
+        async move { solving?.await.ok() }
+    }
+
+    /// Records the North-South score of the finished board of a team match or a tournament.
+    /// Returns `None` if the room isn't a part of either.
+    pub fn finish_board(&mut self, score: isize) -> Option<BoardFinished> {
+        let board_index = self.board_index;
+        self.board_index += 1;
+
+        if let Some((team_match, table)) = &self.team_match {
+            let mut team_match = team_match.lock().unwrap();
+            team_match.record_result(*table, board_index, score);
+            return Some(BoardFinished {
+                outcome: BoardOutcome::Match(team_match.scoreboard()),
+                board: SharedBoard::Match {
+                    open_room: team_match.room(Table::Open).clone(),
+                    board_index,
+                },
+                board_complete: team_match.is_board_complete(board_index),
+                all_boards_played: self.board_index >= team_match.board_count(),
+            });
+        }
+
+        let table = self.tournament.as_ref()?;
+        let mut tournament = table.tournament.lock().unwrap();
+        let board_no = tournament.board_no(table.round, board_index);
+        tournament.record_score(&table.assignment, board_no, score);
+
+        let table_finished = self.board_index >= tournament.boards_per_round();
+        let standings = if table_finished {
+            tournament
+                .finish_table()
+                .map(|standings| (tournament.group(), standings))
+        } else {
+            None
+        };
+        Some(BoardFinished {
+            outcome: BoardOutcome::Tournament(standings),
+            board: SharedBoard::Tournament {
+                tournament_id: tournament.id().into(),
+                board_no,
+            },
+            board_complete: tournament.is_board_complete(board_no),
+            all_boards_played: table_finished,
+        })
+    }
+
+    pub fn get_convention_card(&self, player: Player) -> Option<&ConventionCard> {
+        self.convention_cards[player.to_usize() % 2].as_ref()
+    }
+
+    pub fn set_convention_card(&mut self, player: Player, card: Option<ConventionCard>) {
+        self.convention_cards[player.to_usize() % 2] = card;
+    }
+
     pub fn append_notifications(
         &mut self,
         notifications: Vec<Box<dyn SendableNotification + Send + Sync>>,
     ) {
+        if let Some(broadcast) = &self.broadcast {
+            for notification in &notifications {
+                broadcast.push(notification.boxed_clone());
+            }
+        }
         self.sent_notifications.extend(notifications);
     }
 
-    pub async fn send_notifications(&self, socket: &SocketRef) {
-        for notification in &self.sent_notifications {
-            notification.send(socket).await;
-            sleep(Duration::from_secs(2)).await;
-        }
+    /// Copies of the notifications sent so far as seen from `place`, to be replayed without holding the room lock
+    pub fn sent_notifications(
+        &self,
+        place: Option<Player>,
+    ) -> Vec<Box<dyn SendableNotification + Send + Sync>> {
+        self.sent_notifications
+            .iter()
+            .map(|notification| notification.for_place(place))
+            .collect()
     }
 }
 
 #[derive(Clone)]
 pub struct ServerStateInner {
-    rooms: HashMap<RoomId, Arc<RwLock<RoomState>>>,
+    rooms: HashMap<RoomId, RoomHandle>,
     users: HashSet<User>,
+    tournaments: HashMap<String, Arc<Mutex<Tournament>>>,
+    /// Records of the deals finished in every room, kept after the room is removed
+    deal_records: HashMap<RoomId, Vec<DealRecord>>,
+    /// Rooms and indices of the records of shared boards that some table hasn't played yet,
+    /// they are withheld from the API so that nobody can look up the hands
+    withheld_records: HashMap<SharedBoard, Vec<(RoomId, usize)>>,
+    /// Where the records are also written, `None` if they are kept in memory only
+    deal_log: Option<DealLog>,
+    config: Arc<Config>,
+    /// Whether the server stopped accepting new users and games before shutdown
+    draining: bool,
+    /// Notified once the server is draining and its last room is removed
+    shutdown: Arc<Notify>,
 }
 
 pub type ServerState = Arc<RwLock<ServerStateInner>>;
 
 impl ServerStateInner {
-    pub fn new() -> Self {
-        Self {
+    /// Creates the state with the records kept in the data directory of the config, if it has one
+    pub fn new(config: Arc<Config>) -> io::Result<Self> {
+        let deal_log = config.data_dir.as_deref().map(DealLog::open).transpose()?;
+        let deal_records = match &deal_log {
+            Some(deal_log) => deal_log.load()?,
+            None => HashMap::new(),
+        };
+        Ok(Self {
             users: HashSet::new(),
             rooms: HashMap::new(),
+            tournaments: HashMap::new(),
+            deal_records,
+            withheld_records: HashMap::new(),
+            deal_log,
+            config,
+            draining: false,
+            shutdown: Arc::new(Notify::new()),
+        })
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Whether `count` more rooms would exceed the limit of the config
+    fn too_many_rooms(&self, count: usize) -> bool {
+        self.config
+            .rooms
+            .max_rooms
+            .is_some_and(|max_rooms| self.rooms.len() + count > max_rooms)
+    }
+
+    /// Checks whether a user can join the room under the limit of the config
+    pub fn check_room_capacity(&self, room: &RoomHandle) -> Result<(), JoinRoomResponse> {
+        match self.config.rooms.max_users {
+            Some(max_users) if room.public_state().users >= max_users => {
+                Err(JoinRoomResponse::RoomFull)
+            }
+            _ => Ok(()),
         }
     }
 
@@ -136,38 +515,238 @@ impl ServerStateInner {
         self.users.remove(user);
     }
 
-    /// Creates a new room with the given `RoomInfo` and returns Arc to it, which should be used to avoid locking ServerState mutex.
-    pub async fn add_room(&mut self, info: RoomInfo) -> RegisterRoomResponse {
-        let entry = self.rooms.entry(info.id.clone());
-        if let Entry::Vacant(entry) = entry {
-            let room = RoomState::new(info);
-            let arc = Arc::new(RwLock::new(room));
-            entry.insert(arc);
-            RegisterRoomResponse::Ok
-        } else {
-            RegisterRoomResponse::RoomIdAlreadyExists
+    pub fn user_count(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Creates a new room with the given `RoomInfo` and spawns its actor.
+    pub fn add_room(&mut self, info: RoomInfo, io: &SocketIo) -> RegisterRoomResponse {
+        if self.rooms.contains_key(&info.id) {
+            return RegisterRoomResponse::RoomIdAlreadyExists;
+        }
+        if self.too_many_rooms(1) {
+            return RegisterRoomResponse::TooManyRooms;
         }
+        let mut room = RoomState::new(info, &self.config.pacing);
+        room.start_broadcast(io);
+        self.rooms
+            .insert(room.info.id.clone(), RoomHandle::spawn(room));
+        RegisterRoomResponse::Ok
     }
 
-    pub async fn get_room_list(&self) -> Vec<RoomId> {
-        futures::stream::iter(self.rooms.values())
-            .filter_map(|room| async {
-                let mutex = room.read().await;
-                if mutex.info.visibility == Visibility::Public {
-                    Some(mutex.info.id.clone())
-                } else {
-                    None
-                }
-            })
+    /// Creates two linked rooms of a team match, playing given number of boards.
+    pub fn add_match(
+        &mut self,
+        open_info: RoomInfo,
+        closed_info: RoomInfo,
+        boards: usize,
+        io: &SocketIo,
+    ) -> RegisterRoomResponse {
+        if open_info.id == closed_info.id
+            || self.rooms.contains_key(&open_info.id)
+            || self.rooms.contains_key(&closed_info.id)
+        {
+            return RegisterRoomResponse::RoomIdAlreadyExists;
+        }
+        if self.too_many_rooms(2) {
+            return RegisterRoomResponse::TooManyRooms;
+        }
+
+        let team_match = Arc::new(Mutex::new(TeamMatch::new(
+            open_info.id.clone(),
+            closed_info.id.clone(),
+            boards,
+        )));
+        for (info, table) in [(open_info, Table::Open), (closed_info, Table::Closed)] {
+            let mut room = RoomState::new(info, &self.config.pacing);
+            room.team_match = Some((team_match.clone(), table));
+            room.start_broadcast(io);
+            self.rooms
+                .insert(room.info.id.clone(), RoomHandle::spawn(room));
+        }
+        RegisterRoomResponse::Ok
+    }
+
+    /// Creates a pairs tournament directed by `director`, its rooms are created at the start of every round.
+    pub fn add_tournament(
+        &mut self,
+        id: String,
+        director: User,
+        movement: Movement,
+        pairs: Vec<[String; 2]>,
+        boards_per_round: usize,
+    ) -> RegisterTournamentResponse {
+        if !movement.supports(pairs.len()) {
+            return RegisterTournamentResponse::UnsupportedPairCount;
+        }
+        let Entry::Vacant(entry) = self.tournaments.entry(id.clone()) else {
+            return RegisterTournamentResponse::TournamentIdAlreadyExists;
+        };
+        entry.insert(Arc::new(Mutex::new(Tournament::new(
+            id,
+            director,
+            movement,
+            pairs,
+            boards_per_round,
+        ))));
+        RegisterTournamentResponse::Ok
+    }
+
+    pub fn get_tournament(&self, id: &str) -> Option<Arc<Mutex<Tournament>>> {
+        self.tournaments.get(id).cloned()
+    }
+
+    /// Starts the next round of a tournament and creates a room for every table of the round.
+    pub fn start_tournament_round(
+        &mut self,
+        id: &str,
+        user: &User,
+    ) -> Result<(), StartRoundResponse> {
+        let tournament = self
+            .get_tournament(id)
+            .ok_or(StartRoundResponse::TournamentNotFound)?;
+        let mut tournament_lock = tournament.lock().unwrap();
+
+        if !tournament_lock.is_director(user) {
+            return Err(StartRoundResponse::NotDirector);
+        }
+        if tournament_lock.is_round_in_progress() {
+            return Err(StartRoundResponse::RoundInProgress);
+        }
+        if tournament_lock.is_finished() {
+            return Err(StartRoundResponse::TournamentFinished);
+        }
+
+        let tables = tournament_lock.next_round_tables();
+        if tables
+            .iter()
+            .any(|table| self.rooms.contains_key(&table.room_id))
+        {
+            return Err(StartRoundResponse::RoomIdAlreadyExists);
+        }
+        if self.too_many_rooms(tables.len()) {
+            return Err(StartRoundResponse::TooManyRooms);
+        }
+
+        let round = tournament_lock.next_round_index();
+        for table in tables {
+            let mut room = RoomState::new(
+                RoomInfo {
+                    id: table.room_id,
+                    visibility: Visibility::Public,
+                    deal_constraints: None,
+                    time_control: None,
+                    allow_kibitzing: false,
+                    broadcast_delay_secs: None,
+                    seed: None,
+                },
+                &self.config.pacing,
+            );
+            room.tournament = Some(RoundTable {
+                tournament: tournament.clone(),
+                round,
+                assignment: table.assignment,
+            });
+            self.rooms
+                .insert(room.info.id.clone(), RoomHandle::spawn(room));
+        }
+        tournament_lock.start_round();
+        Ok(())
+    }
+
+    pub fn get_room_list(&self) -> Vec<RoomId> {
+        self.rooms
+            .values()
+            .filter(|room| room.info.visibility == Visibility::Public)
+            .map(|room| room.info.id.clone())
             .collect()
-            .await
     }
 
-    pub async fn get_room(&self, room_id: &RoomId) -> Option<Arc<RwLock<RoomState>>> {
+    pub fn get_room(&self, room_id: &RoomId) -> Option<RoomHandle> {
         self.rooms.get(room_id).cloned()
     }
 
+    /// All rooms, private ones too
+    pub fn get_rooms(&self) -> Vec<RoomHandle> {
+        self.rooms.values().cloned().collect()
+    }
+
     pub fn remove_room(&mut self, room_id: &RoomId) {
         self.rooms.remove(room_id);
+        self.check_drained();
+    }
+
+    /// Stops accepting new users and games, the server shuts down once the last room is removed
+    pub fn start_draining(&mut self) {
+        self.draining = true;
+        self.check_drained();
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining
+    }
+
+    /// Signal notified once the server drained
+    pub fn shutdown_signal(&self) -> Arc<Notify> {
+        self.shutdown.clone()
+    }
+
+    fn check_drained(&self) {
+        if self.draining && self.rooms.is_empty() {
+            self.shutdown.notify_one();
+        }
+    }
+
+    /// Adds the record of a deal finished in the room, returns its index among the records of the room
+    pub fn add_deal_record(&mut self, room_id: &RoomId, record: DealRecord) -> usize {
+        if let Some(deal_log) = &self.deal_log {
+            deal_log.append(room_id, &record);
+        }
+        let records = self.deal_records.entry(room_id.clone()).or_default();
+        records.push(record);
+        records.len() - 1
+    }
+
+    /// Adds the record of a board played at several tables.
+    /// Records of the board are withheld until it's complete, i.e. every table played it.
+    pub fn add_shared_deal_record(
+        &mut self,
+        room_id: &RoomId,
+        record: DealRecord,
+        board: SharedBoard,
+        board_complete: bool,
+    ) {
+        let index = self.add_deal_record(room_id, record);
+        if board_complete {
+            self.withheld_records.remove(&board);
+        } else {
+            self.withheld_records
+                .entry(board)
+                .or_default()
+                .push((room_id.clone(), index));
+        }
+    }
+
+    /// Records of the deals finished in the room with their numbers, counted from 1,
+    /// `None` if there is no such room and never was.
+    /// Records of shared boards that some table hasn't played yet are left out.
+    pub fn get_deal_records(&self, room_id: &RoomId) -> Option<Vec<(usize, DealRecord)>> {
+        let Some(records) = self.deal_records.get(room_id) else {
+            return self.rooms.contains_key(room_id).then(Vec::new);
+        };
+        let withheld: HashSet<usize> = self
+            .withheld_records
+            .values()
+            .flatten()
+            .filter(|(withheld_room, _)| withheld_room == room_id)
+            .map(|(_, index)| *index)
+            .collect();
+        let records = records
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !withheld.contains(index))
+            .map(|(index, record)| (index + 1, record.clone()))
+            .collect();
+        Some(records)
     }
 }
@@ -0,0 +1,103 @@
+use common::{
+    duplicate::BoardResult, message::server_notification::MatchScoreboardNotification,
+    room::RoomId, Card,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Table {
+    Open,
+    Closed,
+}
+
+impl Table {
+    pub fn other(self) -> Self {
+        match self {
+            Table::Open => Table::Closed,
+            Table::Closed => Table::Open,
+        }
+    }
+}
+
+/// Team match played on the same boards in two linked rooms.
+pub struct TeamMatch {
+    open_room: RoomId,
+    closed_room: RoomId,
+    board_count: usize,
+    /// Hands of the boards, dealt by the table that starts the board first
+    boards: Vec<[Vec<Card>; 4]>,
+    results: Vec<BoardResult>,
+}
+
+impl TeamMatch {
+    pub fn new(open_room: RoomId, closed_room: RoomId, board_count: usize) -> Self {
+        Self {
+            open_room,
+            closed_room,
+            board_count,
+            boards: Vec::new(),
+            results: (1..=board_count)
+                .map(|board_no| BoardResult {
+                    board_no,
+                    open: None,
+                    closed: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns hands of the board with given index, dealing them with `deal` if no table has started it yet
+    pub fn board_hands(
+        &mut self,
+        board_index: usize,
+        deal: impl FnOnce() -> [Vec<Card>; 4],
+    ) -> [Vec<Card>; 4] {
+        if board_index >= self.boards.len() {
+            self.boards.push(deal());
+        }
+        self.boards[board_index].clone()
+    }
+
+    /// Whether a table already dealt the board with given index
+    pub fn is_board_dealt(&self, board_index: usize) -> bool {
+        board_index < self.boards.len()
+    }
+
+    /// Records the North-South score of a board played at given table
+    pub fn record_result(&mut self, table: Table, board_index: usize, score: isize) {
+        let Some(result) = self.results.get_mut(board_index) else {
+            return;
+        };
+        match table {
+            Table::Open => result.open = Some(score),
+            Table::Closed => result.closed = Some(score),
+        }
+    }
+
+    /// Whether both tables played the board with given index
+    pub fn is_board_complete(&self, board_index: usize) -> bool {
+        self.results
+            .get(board_index)
+            .is_some_and(|result| result.open.is_some() && result.closed.is_some())
+    }
+
+    /// Id of the room of given table
+    pub fn room(&self, table: Table) -> &RoomId {
+        match table {
+            Table::Open => &self.open_room,
+            Table::Closed => &self.closed_room,
+        }
+    }
+
+    pub fn board_count(&self) -> usize {
+        self.board_count
+    }
+
+    pub fn scoreboard(&self) -> MatchScoreboardNotification {
+        MatchScoreboardNotification {
+            open_room: self.open_room.clone(),
+            closed_room: self.closed_room.clone(),
+            boards: self.results.clone(),
+            imps: self.results.iter().filter_map(BoardResult::imps).sum(),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::sync::{Arc, Mutex};
+
+use common::{
+    message::server_notification::{
+        TournamentRoundNotification, TournamentStandingsNotification, TournamentTable,
+    },
+    room::RoomId,
+    tournament::{rankings, BoardScore, Movement, TableAssignment},
+    user::User,
+    Card, Player,
+};
+
+use crate::handlers::Group;
+
+/// Pairs tournament controlled by a director, played in new rooms every round.
+pub struct Tournament {
+    id: String,
+    director: User,
+    movement: Movement,
+    pairs: Vec<[String; 2]>,
+    boards_per_round: usize,
+    /// Hands of the boards, dealt by the table that starts the board first
+    boards: Vec<[Vec<Card>; 4]>,
+    scores: Vec<BoardScore>,
+    /// Number of started rounds
+    round: usize,
+    /// Tables of the current round that haven't played all boards yet
+    tables_playing: usize,
+}
+
+/// Link of a room to the table of a tournament round played in it
+pub struct RoundTable {
+    pub tournament: Arc<Mutex<Tournament>>,
+    /// Index of the round
+    pub round: usize,
+    pub assignment: TableAssignment,
+}
+
+impl RoundTable {
+    /// Checks whether `user` belongs to the pair assigned to the direction of `position` at this table
+    pub fn is_seat_of(&self, user: &User, position: Player) -> bool {
+        let pair = match position {
+            Player::North | Player::South => self.assignment.north_south,
+            Player::East | Player::West => self.assignment.east_west,
+        };
+        self.tournament.lock().unwrap().pairs[pair]
+            .iter()
+            .any(|username| username == user.get_username())
+    }
+}
+
+impl Tournament {
+    pub fn new(
+        id: String,
+        director: User,
+        movement: Movement,
+        pairs: Vec<[String; 2]>,
+        boards_per_round: usize,
+    ) -> Self {
+        Self {
+            id,
+            director,
+            movement,
+            pairs,
+            boards_per_round,
+            boards: Vec::new(),
+            scores: Vec::new(),
+            round: 0,
+            tables_playing: 0,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Group of users following the tournament
+    pub fn group(&self) -> Group {
+        Group(format!("tournament:{}", self.id))
+    }
+
+    pub fn is_director(&self, user: &User) -> bool {
+        self.director == *user
+    }
+
+    pub fn is_round_in_progress(&self) -> bool {
+        self.tables_playing > 0
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.round >= self.movement.rounds(self.pairs.len())
+    }
+
+    pub fn boards_per_round(&self) -> usize {
+        self.boards_per_round
+    }
+
+    /// Rooms and table assignments of the next round, which isn't started yet
+    pub fn next_round_tables(&self) -> Vec<TournamentTable> {
+        self.round_tables(self.round)
+    }
+
+    /// Rooms and table assignments of the round with given index
+    fn round_tables(&self, round: usize) -> Vec<TournamentTable> {
+        self.movement
+            .round(self.pairs.len(), round)
+            .into_iter()
+            .map(|assignment| TournamentTable {
+                room_id: RoomId::new(
+                    format!(
+                        "{} round {} table {}",
+                        self.id,
+                        round + 1,
+                        assignment.table + 1
+                    )
+                    .into(),
+                ),
+                assignment,
+            })
+            .collect()
+    }
+
+    pub fn next_round_index(&self) -> usize {
+        self.round
+    }
+
+    /// Starts the next round, its rooms should be created from `next_round_tables` beforehand
+    pub fn start_round(&mut self) {
+        self.tables_playing = self.movement.tables(self.pairs.len());
+        self.round += 1;
+    }
+
+    /// Notification about the last started round, `None` before the first round
+    pub fn round_notification(&self) -> Option<TournamentRoundNotification> {
+        let round = self.round.checked_sub(1)?;
+        let first_board = round * self.boards_per_round + 1;
+        Some(TournamentRoundNotification {
+            tournament_id: self.id.clone(),
+            round: self.round,
+            rounds: self.movement.rounds(self.pairs.len()),
+            pairs: self.pairs.clone(),
+            tables: self.round_tables(round),
+            boards: first_board..=first_board + self.boards_per_round - 1,
+        })
+    }
+
+    /// Number of a board, counted from 1, played as `board_index` in a room of given round
+    pub fn board_no(&self, round: usize, board_index: usize) -> usize {
+        round * self.boards_per_round + board_index + 1
+    }
+
+    /// Returns hands of the board with given number, dealing them with `deal` if no table has started it yet
+    pub fn board_hands(
+        &mut self,
+        board_no: usize,
+        deal: impl FnOnce() -> [Vec<Card>; 4],
+    ) -> [Vec<Card>; 4] {
+        if board_no > self.boards.len() {
+            self.boards.push(deal());
+        }
+        self.boards[board_no - 1].clone()
+    }
+
+    /// Whether a table already dealt the board with given number
+    pub fn is_board_dealt(&self, board_no: usize) -> bool {
+        board_no <= self.boards.len()
+    }
+
+    /// Records the North-South score of a board played at given table
+    pub fn record_score(&mut self, assignment: &TableAssignment, board_no: usize, score: isize) {
+        self.scores.push(BoardScore {
+            board_no,
+            north_south: assignment.north_south,
+            east_west: assignment.east_west,
+            score,
+        });
+    }
+
+    /// Whether every table of its round played the board with given number
+    pub fn is_board_complete(&self, board_no: usize) -> bool {
+        let tables = self.movement.tables(self.pairs.len());
+        self.scores
+            .iter()
+            .filter(|score| score.board_no == board_no)
+            .count()
+            >= tables
+    }
+
+    /// Marks a table of the current round as finished.
+    /// Returns standings once all tables finished the round.
+    pub fn finish_table(&mut self) -> Option<TournamentStandingsNotification> {
+        self.tables_playing = self.tables_playing.saturating_sub(1);
+        if self.is_round_in_progress() {
+            return None;
+        }
+        Some(self.standings())
+    }
+
+    pub fn standings(&self) -> TournamentStandingsNotification {
+        TournamentStandingsNotification {
+            tournament_id: self.id.clone(),
+            round: self.round,
+            rounds: self.movement.rounds(self.pairs.len()),
+            pairs: self.pairs.clone(),
+            rankings: rankings(self.movement, self.pairs.len(), &self.scores),
+        }
+    }
+}
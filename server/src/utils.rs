@@ -1,43 +1,267 @@
-use std::{future::Future, pin::Pin};
+use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc, sync::Mutex, time::Instant};
 
-use common::{message::MessageTrait, room::RoomId};
-use serde::Serialize;
-use socketioxide::extract::SocketRef;
+use common::{
+    message::{server_notification::ServerMessageNotification, MessageTrait},
+    protocol::{Feature, Protocol, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION},
+    room::RoomId,
+    Player,
+};
+use serde::{de::IgnoredAny, Serialize};
+use socketioxide::{
+    adapter::LocalAdapter,
+    extract::{AckSender, SocketRef},
+    handler::{FromMessageParts, Value},
+    operators::BroadcastOperators,
+    socket::{Sid, Socket},
+    SocketIo,
+};
 
-use crate::{handlers::RoomWrapper, ClientData};
+use crate::{
+    handlers::{Group, RoomWrapper},
+    metrics::METRICS,
+    state::ServerState,
+    ClientData,
+};
+
+/// Group of the clients talking given version of the protocol
+fn protocol_group(version: u32) -> Group {
+    Group(format!("protocol:{}", version))
+}
+
+/// Group of the clients that don't support given feature
+fn missing_feature_group(feature: Feature) -> Group {
+    Group(format!("missing-feature:{:?}", feature))
+}
+
+/// Puts the socket in the groups of its protocol version and of the features it doesn't support,
+/// so that notifications can leave out clients that can't read them
+pub fn join_protocol_groups(socket: &SocketRef, protocol: &Protocol) {
+    for version in MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION {
+        socket.leave(protocol_group(version)).ok();
+    }
+    socket.join(protocol_group(protocol.version)).unwrap();
+    for feature in Feature::ALL {
+        if protocol.supports(feature) {
+            socket.leave(missing_feature_group(feature)).ok();
+        } else {
+            socket.join(missing_feature_group(feature)).unwrap();
+        }
+    }
+}
+
+/// Protocol agreed on with the client of the socket
+fn protocol(socket: &SocketRef) -> Protocol {
+    socket.extensions.get::<Protocol>().unwrap_or_default()
+}
+
+/// Version of the protocol agreed on with the client of the socket
+fn protocol_version(socket: &SocketRef) -> u32 {
+    protocol(socket).version
+}
+
+/// Whether the client of the socket can read message `M`
+fn can_read<M: MessageTrait>(socket: &SocketRef) -> bool {
+    let protocol = protocol(socket);
+    protocol.version >= M::SINCE_VERSION && M::FEATURE.is_none_or(|f| protocol.supports(f))
+}
+
+/// Leaves out clients talking a protocol older than message `M` or not supporting its feature
+fn except_outdated<M: MessageTrait>(mut operators: BroadcastOperators) -> BroadcastOperators {
+    for version in MIN_PROTOCOL_VERSION..M::SINCE_VERSION {
+        operators = operators.except(protocol_group(version));
+    }
+    if let Some(feature) = M::FEATURE {
+        operators = operators.except(missing_feature_group(feature));
+    }
+    operators
+}
 
 /// Sends given message to user that makes request (given by socket)
 pub fn send<M>(socket: &SocketRef, message: &M)
 where
     M: MessageTrait + Serialize,
 {
-    socket.emit(M::MSG_TYPE, message).unwrap();
+    if !can_read::<M>(socket) {
+        return;
+    }
+    match message.for_version(protocol_version(socket)) {
+        Some(message) => socket.emit(M::MSG_TYPE, &message).unwrap(),
+        None => socket.emit(M::MSG_TYPE, message).unwrap(),
+    }
+}
+
+/// Sends the response to the request being handled.
+/// It's sent as the acknowledgement of the request if the client asked for one, otherwise as a standalone message.
+pub struct Responder {
+    pub socket: SocketRef,
+    ack: Mutex<Option<AckSender>>,
+    /// `MSG_TYPE` of the request and when it was received, until the response is recorded in the metrics
+    request: Mutex<Option<(String, Instant)>>,
+}
+
+impl Responder {
+    /// Responder sending only standalone messages, for requests made by the server itself
+    pub fn without_ack(socket: SocketRef) -> Self {
+        Self {
+            socket,
+            ack: Mutex::new(None),
+            request: Mutex::new(None),
+        }
+    }
+
+    /// Sends the response. Only the first response is sent as the acknowledgement.
+    pub fn send<M>(&self, message: &M)
+    where
+        M: MessageTrait + Serialize,
+    {
+        if let Some((msg_type, received)) = self.request.lock().unwrap().take() {
+            METRICS.response_sent(&msg_type, received.elapsed(), M::MSG_TYPE, message);
+        }
+        match self.ack.lock().unwrap().take() {
+            Some(ack) if can_read::<M>(&self.socket) => {
+                match message.for_version(protocol_version(&self.socket)) {
+                    Some(message) => ack.send(&message).unwrap(),
+                    None => ack.send(message).unwrap(),
+                }
+            }
+            _ => send(&self.socket, message),
+        }
+    }
+}
+
+impl FromMessageParts<LocalAdapter> for Responder {
+    type Error = Infallible;
+
+    fn from_message_parts(
+        s: &Arc<Socket<LocalAdapter>>,
+        v: &mut Value,
+        ack_id: &Option<i64>,
+    ) -> Result<Self, Infallible> {
+        let ack = ack_id.map(|_| AckSender::from_message_parts(s, v, ack_id).unwrap());
+        // The packet is the array of the event name and its data
+        let msg_type = v
+            .as_str()
+            .and_then(|packet| serde_json::from_str::<(String, IgnoredAny)>(packet).ok())
+            .map(|(msg_type, _)| msg_type);
+        if let Some(msg_type) = &msg_type {
+            METRICS.request_received(msg_type);
+        }
+        Ok(Self {
+            socket: SocketRef::from_message_parts(s, v, ack_id).unwrap(),
+            ack: Mutex::new(ack),
+            request: Mutex::new(msg_type.map(|msg_type| (msg_type, Instant::now()))),
+        })
+    }
 }
 
 /// Send message to room with given `RoomId``
 pub fn notify<M>(
-    socket: &SocketRef,
+    io: &SocketIo,
     room: &RoomId,
     message: M,
 ) -> Box<dyn SendableNotification + Send + Sync>
 where
-    M: MessageTrait + Serialize + SendableNotification + Send + Sync + 'static,
+    M: MessageTrait + Serialize + Clone + Send + Sync + 'static,
 {
-    socket
-        .within(RoomWrapper(room.clone()))
+    except_outdated::<M>(io.within(RoomWrapper(room.clone())))
         .emit(M::MSG_TYPE, &message)
         .unwrap();
 
     Box::new(message)
 }
 
+/// Send message to room with given `RoomId`, except for socket `sid` which gets `personal` message instead.
+/// Only the message for the room is returned, so that `personal` is never resent to other users.
+pub fn notify_personal<M>(
+    io: &SocketIo,
+    room: &RoomId,
+    sid: Option<Sid>,
+    message: M,
+    personal: &M,
+) -> Box<dyn SendableNotification + Send + Sync>
+where
+    M: MessageTrait + Serialize + Clone + Send + Sync + 'static,
+{
+    let Some(sid) = sid else {
+        return notify(io, room, message);
+    };
+
+    except_outdated::<M>(io.within(RoomWrapper(room.clone())))
+        .except(sid)
+        .emit(M::MSG_TYPE, &message)
+        .unwrap();
+    except_outdated::<M>(io.within(sid))
+        .emit(M::MSG_TYPE, personal)
+        .unwrap();
+
+    Box::new(message)
+}
+
+/// Send message to room with given `RoomId`, except for socket `sid` of player `hidden_from` which gets `redacted` message instead.
+/// Both messages are returned, so that a replay sends `redacted` to `hidden_from` and the full message to everyone else.
+pub fn notify_redacted<M>(
+    io: &SocketIo,
+    room: &RoomId,
+    sid: Option<Sid>,
+    hidden_from: Player,
+    message: M,
+    redacted: M,
+) -> Box<dyn SendableNotification + Send + Sync>
+where
+    M: MessageTrait + Serialize + Clone + Send + Sync + 'static,
+{
+    match sid {
+        Some(sid) => {
+            except_outdated::<M>(io.within(RoomWrapper(room.clone())))
+                .except(sid)
+                .emit(M::MSG_TYPE, &message)
+                .unwrap();
+            except_outdated::<M>(io.within(sid))
+                .emit(M::MSG_TYPE, &redacted)
+                .unwrap();
+        }
+        None => {
+            except_outdated::<M>(io.within(RoomWrapper(room.clone())))
+                .emit(M::MSG_TYPE, &message)
+                .unwrap();
+        }
+    }
+
+    Box::new(Redacted {
+        message,
+        redacted,
+        hidden_from,
+    })
+}
+
+/// Sends given message to every connected client
+pub fn notify_all<M>(io: &SocketIo, message: &M)
+where
+    M: MessageTrait + Serialize,
+{
+    if let Some(operators) = io.of("/") {
+        except_outdated::<M>(operators)
+            .emit(M::MSG_TYPE, message)
+            .ok();
+    }
+}
+
+/// Sends given message to every client in the group
+pub fn notify_group<M>(io: &SocketIo, group: &Group, message: &M)
+where
+    M: MessageTrait + Serialize,
+{
+    except_outdated::<M>(io.within(group.clone()))
+        .emit(M::MSG_TYPE, message)
+        .ok();
+}
+
 /// Send message to everyone in room with given `RoomId` except for use that makes request
 pub fn notify_others<M>(socket: &SocketRef, room: &RoomId, message: &M)
 where
     M: MessageTrait + Serialize,
 {
-    socket
-        .to(RoomWrapper(room.clone()))
+    except_outdated::<M>(socket.to(RoomWrapper(room.clone())))
         .emit(M::MSG_TYPE, message)
         .unwrap();
 }
@@ -45,28 +269,95 @@ where
 /// If user that makes request isn't logged in,
 /// it sends given error message and returns None.
 /// Otherwise returns Some with `ClientData` of logged user.
-pub fn get_client_or_response<M>(socket: &SocketRef, response: &M) -> Option<ClientData>
+pub fn get_client_or_response<M>(reply: &Responder, response: &M) -> Option<ClientData>
 where
     M: MessageTrait + Serialize,
 {
-    let data = socket.extensions.get::<ClientData>();
+    let data = reply.socket.extensions.get::<ClientData>();
     if data.is_none() {
-        send(socket, response);
+        reply.send(response);
     }
     data
 }
 
+/// If the server is draining before shutdown, tells the client and disconnects it instead of handling its request.
+/// Returns whether the request was refused.
+pub async fn refuse_while_draining(socket: &SocketRef, state: &ServerState) -> bool {
+    if !state.read().await.is_draining() {
+        return false;
+    }
+    send(
+        socket,
+        &ServerMessageNotification {
+            message: "The server is shutting down and doesn't accept new games".into(),
+        },
+    );
+    socket.clone().disconnect().ok();
+    true
+}
+
 // WATCH OUT! Ugly shit
 pub trait SendableNotification: Send + Sync {
     // How did we get here?
     fn send<'a>(&'a self, socket: &'a SocketRef) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+    /// Sends the notification to the clients selected by `operators`, also outside of a message handler
+    fn emit_to(&self, operators: BroadcastOperators);
+
+    fn boxed_clone(&self) -> Box<dyn SendableNotification + Send + Sync>;
+
+    /// Copy of the notification as seen by the user at `place`, `None` for spectators
+    fn for_place(&self, _place: Option<Player>) -> Box<dyn SendableNotification + Send + Sync> {
+        self.boxed_clone()
+    }
 }
 
-impl<T: MessageTrait + Serialize + Send + Sync> SendableNotification for T {
+impl<T: MessageTrait + Serialize + Clone + Send + Sync + 'static> SendableNotification for T {
     fn send<'a>(&'a self, socket: &'a SocketRef) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
         let socket = socket.clone();
         Box::pin(async move {
             send(&socket, self);
         })
     }
+
+    fn emit_to(&self, operators: BroadcastOperators) {
+        except_outdated::<T>(operators).emit(T::MSG_TYPE, self).ok();
+    }
+
+    fn boxed_clone(&self) -> Box<dyn SendableNotification + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+/// Notification with parts hidden from one player, see `notify_redacted`
+#[derive(Clone)]
+struct Redacted<M> {
+    message: M,
+    redacted: M,
+    hidden_from: Player,
+}
+
+impl<M: MessageTrait + Serialize + Clone + Send + Sync + 'static> SendableNotification
+    for Redacted<M>
+{
+    fn send<'a>(&'a self, socket: &'a SocketRef) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        // The place of the socket isn't known here, so nothing hidden is sent
+        self.redacted.send(socket)
+    }
+
+    fn emit_to(&self, operators: BroadcastOperators) {
+        self.message.emit_to(operators);
+    }
+
+    fn boxed_clone(&self) -> Box<dyn SendableNotification + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn for_place(&self, place: Option<Player>) -> Box<dyn SendableNotification + Send + Sync> {
+        if place == Some(self.hidden_from) {
+            self.redacted.boxed_clone()
+        } else {
+            self.message.boxed_clone()
+        }
+    }
 }
@@ -0,0 +1,47 @@
+mod support;
+
+use common::{
+    message::{
+        client_message::{JoinRoomMessage, MakeBidMessage},
+        server_notification::{GameFinishedNotification, ServerMessageNotification},
+        server_response::{JoinRoomResponse, MakeBidResponse},
+    },
+    room::RoomId,
+    Bid,
+};
+use support::{room_info, TestServer};
+
+#[test]
+fn closed_room_stops_its_game() {
+    let server = TestServer::start_with_config("admin_token = \"secret\"");
+    let (mut players, started) = server.start_game(room_info("closed"));
+
+    let (status, _) = server.http("DELETE", "/admin/rooms/closed", Some("wrong"));
+    assert_eq!(status, 401);
+    let (status, _) = server.http("DELETE", "/admin/rooms/closed", Some("secret"));
+    assert_eq!(status, 204);
+
+    for player in &mut players {
+        player.recv::<ServerMessageNotification>();
+        player.recv::<GameFinishedNotification>();
+    }
+
+    let dealer = &mut players[started.start_position.to_usize()];
+    let response = dealer.request(&MakeBidMessage {
+        bid: Bid::Pass,
+        explanation: None,
+    });
+    assert!(
+        matches!(response, MakeBidResponse::NotInRoom),
+        "{:?}",
+        response
+    );
+    let response = dealer.request(&JoinRoomMessage {
+        room_id: RoomId::new("closed".into()),
+    });
+    assert!(
+        matches!(response, JoinRoomResponse::RoomNotFound),
+        "{:?}",
+        response
+    );
+}
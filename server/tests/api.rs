@@ -0,0 +1,73 @@
+mod support;
+
+use common::{
+    message::{
+        client_message::{MakeBidMessage, RegisterMatchMessage, RegisterRoomMessage},
+        server_notification::{GameFinishedNotification, GameStartedNotification},
+        server_response::{MakeBidResponse, RegisterRoomResponse},
+    },
+    room::{RoomInfo, Visibility},
+    Bid,
+};
+use support::{room_info, seat_players, Client, TestServer};
+
+/// Passes out the deal of a room playing its last board and waits for the game to finish
+fn pass_out(mut players: [Client; 4], started: GameStartedNotification) {
+    let mut player = started.start_position;
+    for _ in 0..4 {
+        let response = players[player.to_usize()].request(&MakeBidMessage {
+            bid: Bid::Pass,
+            explanation: None,
+        });
+        assert!(matches!(response, MakeBidResponse::Ok), "{:?}", response);
+        player = player.next();
+    }
+    players[0].recv::<GameFinishedNotification>();
+}
+
+#[test]
+fn private_room_is_not_shown() {
+    let server = TestServer::start();
+    let mut client = server.login("owner");
+    let response = client.request(&RegisterRoomMessage {
+        room_info: RoomInfo {
+            visibility: Visibility::Private,
+            ..room_info("private")
+        },
+    });
+    assert_eq!(response, RegisterRoomResponse::Ok);
+    let response = client.request(&RegisterRoomMessage {
+        room_info: room_info("public"),
+    });
+    assert_eq!(response, RegisterRoomResponse::Ok);
+
+    assert_eq!(server.http("GET", "/api/rooms/private", None).0, 404);
+    assert_eq!(server.http("GET", "/api/rooms/public", None).0, 200);
+}
+
+#[test]
+fn match_boards_are_withheld_until_both_tables_played_them() {
+    let server = TestServer::start();
+    let mut captain = server.login("captain");
+    let response = captain.request(&RegisterMatchMessage {
+        open_room: room_info("open"),
+        closed_room: room_info("closed"),
+        boards: 1,
+    });
+    assert_eq!(response, RegisterRoomResponse::Ok);
+
+    let open = ["north1", "east1", "south1", "west1"].map(|username| server.login(username));
+    let (open, started) = seat_players("open", open);
+    pass_out(open, started);
+    let (status, deals) = server.http("GET", "/api/rooms/open/deals", None);
+    assert_eq!((status, deals.as_str()), (200, "[]"));
+    assert_eq!(server.http("GET", "/api/rooms/open/deals/1", None).0, 404);
+
+    let closed = ["north2", "east2", "south2", "west2"].map(|username| server.login(username));
+    let (closed, started) = seat_players("closed", closed);
+    pass_out(closed, started);
+    for room in ["open", "closed"] {
+        let (status, _) = server.http("GET", &format!("/api/rooms/{}/deals/1", room), None);
+        assert_eq!(status, 200);
+    }
+}
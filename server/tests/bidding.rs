@@ -0,0 +1,55 @@
+mod support;
+
+use common::{
+    message::{client_message::MakeBidMessage, server_response::MakeBidResponse},
+    Bid,
+};
+use support::{room_info, TestServer};
+
+#[test]
+fn invalid_doubles_are_told_apart() {
+    let server = TestServer::start();
+    let (mut players, started) = server.start_game(room_info("doubles"));
+    let dealer = &mut players[started.start_position.to_usize()];
+
+    let response = dealer.request(&MakeBidMessage {
+        bid: Bid::Double,
+        explanation: None,
+    });
+    assert!(
+        matches!(response, MakeBidResponse::CantDouble),
+        "{:?}",
+        response
+    );
+
+    let response = dealer.request(&MakeBidMessage {
+        bid: Bid::Redouble,
+        explanation: None,
+    });
+    assert!(
+        matches!(response, MakeBidResponse::CantRedouble),
+        "{:?}",
+        response
+    );
+}
+
+#[test]
+fn invalid_doubles_are_invalid_bids_for_clients_without_handshake() {
+    let server = TestServer::start();
+    let players =
+        ["north", "east", "south", "west"].map(|seat| server.login_without_handshake(seat));
+    let (mut players, started) = server.start_game_with(room_info("old doubles"), players);
+    let dealer = &mut players[started.start_position.to_usize()];
+
+    for bid in [Bid::Double, Bid::Redouble] {
+        let response = dealer.request(&MakeBidMessage {
+            bid,
+            explanation: None,
+        });
+        assert!(
+            matches!(response, MakeBidResponse::InvalidBid),
+            "{:?}",
+            response
+        );
+    }
+}
@@ -0,0 +1,67 @@
+mod support;
+
+use common::{
+    message::{
+        client_message::{MakeBidMessage, SelectPlaceMessage},
+        server_notification::MakeBidNotification,
+        server_response::{MakeBidResponse, SelectPlaceResponse},
+    },
+    Bid,
+};
+use support::{room_info, TestServer};
+
+#[test]
+fn explanation_hidden_from_partner() {
+    let server = TestServer::start();
+    let (mut players, started) = server.start_game(room_info("explanation"));
+
+    let dealer = started.start_position;
+    let response = players[dealer.to_usize()].request(&MakeBidMessage {
+        bid: Bid::Pass,
+        explanation: Some("Nothing to say".into()),
+    });
+    assert!(matches!(response, MakeBidResponse::Ok), "{:?}", response);
+
+    for (seat, player) in players.iter_mut().enumerate() {
+        let notification = player.recv::<MakeBidNotification>();
+        assert_eq!(notification.player, dealer);
+        let expected = if seat == dealer.get_partner().to_usize() {
+            None
+        } else {
+            Some("Nothing to say".into())
+        };
+        assert_eq!(notification.explanation, expected, "seat {}", seat);
+    }
+}
+
+#[test]
+fn explanation_hidden_from_partner_after_reconnecting() {
+    let server = TestServer::start();
+    let (mut players, started) = server.start_game(room_info("replayed-explanation"));
+
+    let dealer = started.start_position;
+    let response = players[dealer.to_usize()].request(&MakeBidMessage {
+        bid: Bid::Pass,
+        explanation: Some("Nothing to say".into()),
+    });
+    assert!(matches!(response, MakeBidResponse::Ok), "{:?}", response);
+
+    let mut players = players.map(Some);
+    let usernames = ["north", "east", "south", "west"];
+    for (seat, expected) in [
+        (dealer.get_partner(), None),
+        (dealer.next(), Some("Nothing to say".into())),
+    ] {
+        let old = players[seat.to_usize()].take().unwrap();
+        let mut player = server.reconnect(old, usernames[seat.to_usize()]);
+        player.join("replayed-explanation");
+        let response = player.request(&SelectPlaceMessage {
+            position: Some(seat),
+        });
+        assert_eq!(response, SelectPlaceResponse::Ok);
+
+        let replayed = player.recv::<MakeBidNotification>();
+        assert_eq!(replayed.player, dealer);
+        assert_eq!(replayed.explanation, expected, "seat {:?}", seat);
+    }
+}
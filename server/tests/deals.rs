@@ -0,0 +1,31 @@
+mod support;
+
+use common::{
+    message::{client_message::GetCardsMessage, server_response::GetCardsResponse},
+    room::RoomInfo,
+    Card,
+};
+use support::{room_info, Client, TestServer};
+
+fn cards(player: &mut Client) -> Vec<Card> {
+    match player.request(&GetCardsMessage {}) {
+        GetCardsResponse::Ok { cards, .. } => cards,
+        response => panic!("{:?}", response),
+    }
+}
+
+#[test]
+fn rooms_with_the_same_seed_deal_the_same_boards() {
+    let server = TestServer::start();
+    let seeded = |room: &str| RoomInfo {
+        seed: Some(7),
+        ..room_info(room)
+    };
+    let (mut first, _) = server.start_game(seeded("first"));
+    let players = ["north2", "east2", "south2", "west2"].map(|seat| server.login(seat));
+    let (mut second, _) = server.start_game_with(seeded("second"), players);
+
+    for (first, second) in first.iter_mut().zip(second.iter_mut()) {
+        assert_eq!(cards(first), cards(second));
+    }
+}
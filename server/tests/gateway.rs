@@ -0,0 +1,39 @@
+mod support;
+
+use std::{thread::sleep, time::Duration};
+
+use common::message::{
+    client_message::ListRoomsMessage, server_notification::ServerMessageNotification,
+};
+use support::TestServer;
+
+#[test]
+fn connections_over_the_limit_are_refused() {
+    let server = TestServer::start_with_config("[gateway]\nmax_connections = 2");
+    let mut first = server.login("first");
+    let _second = server.login("second");
+
+    let mut refused = server.connect_without_handshake();
+    let notification = refused.recv::<ServerMessageNotification>();
+    assert_eq!(notification.message, "The server has too many connections");
+    assert!(refused.is_closed());
+
+    // Connections still open keep working
+    first.request(&ListRoomsMessage {});
+}
+
+#[test]
+fn idle_connections_are_closed() {
+    let server = TestServer::start_with_config("[gateway]\nidle_timeout_secs = 1");
+    let mut idle = server.login("idle");
+    let mut active = server.login("active");
+
+    for _ in 0..3 {
+        sleep(Duration::from_millis(500));
+        active.request(&ListRoomsMessage {});
+    }
+
+    let notification = idle.recv::<ServerMessageNotification>();
+    assert_eq!(notification.message, "The connection was idle for too long");
+    assert!(idle.is_closed());
+}
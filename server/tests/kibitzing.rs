@@ -0,0 +1,51 @@
+mod support;
+
+use common::{
+    message::{
+        client_message::{KibitzMessage, SelectPlaceMessage},
+        server_response::{KibitzResponse, SelectPlaceResponse},
+    },
+    room::{RoomInfo, SpectatorView},
+    Player,
+};
+use support::{room_info, TestServer};
+
+#[test]
+fn kibitzer_cannot_take_a_place_in_the_same_deal() {
+    let server = TestServer::start();
+    let (mut players, _) = server.start_game(RoomInfo {
+        allow_kibitzing: true,
+        ..room_info("kibitzing")
+    });
+
+    let mut kibitzer = server.login("kibitzer");
+    kibitzer.join("kibitzing");
+    let response = kibitzer.request(&KibitzMessage {
+        view: SpectatorView::AllHands,
+    });
+    assert!(
+        matches!(response, KibitzResponse::Ok { .. }),
+        "{:?}",
+        response
+    );
+
+    let response = players[0].request(&SelectPlaceMessage { position: None });
+    assert!(
+        matches!(response, SelectPlaceResponse::Ok),
+        "{:?}",
+        response
+    );
+
+    let response = kibitzer.request(&SelectPlaceMessage {
+        position: Some(Player::North),
+    });
+    assert_eq!(response, SelectPlaceResponse::KibitzedDeal);
+
+    // Spectators that didn't look at the hands can still take the place
+    let mut spectator = server.login("spectator");
+    spectator.join("kibitzing");
+    let response = spectator.request(&SelectPlaceMessage {
+        position: Some(Player::North),
+    });
+    assert_eq!(response, SelectPlaceResponse::Ok);
+}
@@ -0,0 +1,38 @@
+mod support;
+
+use common::{
+    message::{client_message::LoginMessage, server_response::LoginResponse},
+    user::User,
+};
+use support::TestServer;
+
+#[test]
+fn username_length_is_checked_against_the_configured_range() {
+    let server = TestServer::start();
+
+    let mut client = server.connect();
+    let response = client.request(&LoginMessage {
+        user: User::new("ab"),
+    });
+    assert!(
+        matches!(
+            response,
+            LoginResponse::UsernameLengthOutOfRange {
+                min_length: 3,
+                max_length: 20
+            }
+        ),
+        "{:?}",
+        response
+    );
+
+    let mut client = server.connect_without_handshake();
+    let response = client.request(&LoginMessage {
+        user: User::new("ab"),
+    });
+    assert!(
+        matches!(response, LoginResponse::UsernameInvalidLength),
+        "{:?}",
+        response
+    );
+}
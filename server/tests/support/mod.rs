@@ -0,0 +1,329 @@
+//! Runs the server binary and talks to it over its newline-delimited JSON transport.
+
+#![allow(dead_code)]
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use common::{
+    message::{
+        client_message::{
+            HelloMessage, JoinRoomMessage, LoginMessage, RegisterRoomMessage, SelectPlaceMessage,
+        },
+        server_notification::GameStartedNotification,
+        server_response::{
+            HelloResponse, JoinRoomResponse, LoginResponse, RegisterRoomResponse,
+            SelectPlaceResponse,
+        },
+        MessageTrait, RequestTrait,
+    },
+    protocol::Feature,
+    room::{RoomId, RoomInfo, Visibility},
+    user::User,
+    Player,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// Longest wait for a message before the test fails
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Server process running without pauses between game transitions, killed when dropped
+pub struct TestServer {
+    process: Child,
+    port: u16,
+    tcp_port: u16,
+    config: PathBuf,
+}
+
+impl TestServer {
+    pub fn start() -> Self {
+        Self::start_with_config("")
+    }
+
+    /// Starts the server with given top-level settings of the config file
+    pub fn start_with_config(settings: &str) -> Self {
+        let port = free_port();
+        let tcp_port = free_port();
+        let config = std::env::temp_dir().join(format!("bridge-test-{}.toml", tcp_port));
+        std::fs::write(
+            &config,
+            format!(
+                "{}\n[pacing]\ntransition_delay_ms = 0\nreplay_delay_ms = 0\n",
+                settings
+            ),
+        )
+        .unwrap();
+
+        let process = Command::new(env!("CARGO_BIN_EXE_server"))
+            .arg("--config")
+            .arg(&config)
+            .args(["--bind", "127.0.0.1"])
+            .args(["--port", &port.to_string()])
+            .args(["--tcp-port", &tcp_port.to_string()])
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let server = Self {
+            process,
+            port,
+            tcp_port,
+            config,
+        };
+        // The TCP transport is up before HTTP, probing HTTP doesn't take a connection of the gateway
+        let start = Instant::now();
+        while TcpStream::connect(("127.0.0.1", port)).is_err() {
+            assert!(start.elapsed() < TIMEOUT, "server didn't start");
+            sleep(Duration::from_millis(50));
+        }
+        server
+    }
+
+    /// Makes an HTTP request without a body, returns the status code and the body of the response
+    pub fn http(&self, method: &str, path: &str, token: Option<&str>) -> (u16, String) {
+        let mut stream = TcpStream::connect(("127.0.0.1", self.port)).unwrap();
+        stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+        let authorization = token
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Length: 0\r\nConnection: close\r\n\r\n",
+            method, path, authorization
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response
+            .split(' ')
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .unwrap();
+        let body = response.split_once("\r\n\r\n").map_or("", |(_, body)| body);
+        (status, body.into())
+    }
+
+    /// Connects a client talking the protocol of this build with all features
+    pub fn connect(&self) -> Client {
+        let mut client = Client::connect(self.tcp_port);
+        let response = client.request(&HelloMessage::new(Feature::ALL.to_vec()));
+        assert!(matches!(response, HelloResponse::Ok(_)), "{:?}", response);
+        client
+    }
+
+    /// Connects a client that doesn't send the handshake, like the ones from before it
+    pub fn connect_without_handshake(&self) -> Client {
+        Client::connect(self.tcp_port)
+    }
+
+    /// Connects a client talking the protocol of this build and logs in as `username`
+    pub fn login(&self, username: &str) -> Client {
+        let mut client = self.connect();
+        client.login(username);
+        client
+    }
+
+    /// Disconnects `client` of `username` and logs in again,
+    /// waiting until the server noticed the disconnection
+    pub fn reconnect(&self, client: Client, username: &str) -> Client {
+        drop(client);
+        let start = Instant::now();
+        loop {
+            let mut client = self.connect();
+            let response = client.request(&LoginMessage {
+                user: User::new(username),
+            });
+            match response {
+                LoginResponse::Ok => return client,
+                LoginResponse::UsernameAlreadyExists if start.elapsed() < TIMEOUT => {
+                    sleep(Duration::from_millis(50))
+                }
+                response => panic!("{:?}", response),
+            }
+        }
+    }
+
+    /// Connects a client that doesn't send the handshake and logs in as `username`
+    pub fn login_without_handshake(&self, username: &str) -> Client {
+        let mut client = self.connect_without_handshake();
+        client.login(username);
+        client
+    }
+
+    /// Logs in four players, seats them North to West in a new room and waits for the game to start
+    pub fn start_game(&self, room_info: RoomInfo) -> ([Client; 4], GameStartedNotification) {
+        let players = ["north", "east", "south", "west"].map(|seat| self.login(seat));
+        self.start_game_with(room_info, players)
+    }
+
+    /// Seats logged in `players` North to West in a new room and waits for the game to start
+    pub fn start_game_with(
+        &self,
+        room_info: RoomInfo,
+        mut players: [Client; 4],
+    ) -> ([Client; 4], GameStartedNotification) {
+        let room = room_info.id.as_str().to_owned();
+        let response = players[0].request(&RegisterRoomMessage { room_info });
+        assert_eq!(response, RegisterRoomResponse::Ok);
+        seat_players(&room, players)
+    }
+}
+
+/// Seats logged in `players` North to West in an existing room and waits for the game to start
+pub fn seat_players(
+    room: &str,
+    mut players: [Client; 4],
+) -> ([Client; 4], GameStartedNotification) {
+    for (seat, player) in players.iter_mut().enumerate() {
+        player.join(room);
+        let response = player.request(&SelectPlaceMessage {
+            position: Player::from_usize(seat),
+        });
+        assert!(
+            matches!(response, SelectPlaceResponse::Ok),
+            "{:?}",
+            response
+        );
+    }
+
+    let started = players[0].recv::<GameStartedNotification>();
+    (players, started)
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.process.kill().ok();
+        self.process.wait().ok();
+        std::fs::remove_file(&self.config).ok();
+    }
+}
+
+/// Settings of a public room with no options
+pub fn room_info(room: &str) -> RoomInfo {
+    RoomInfo {
+        id: RoomId::new(room.into()),
+        visibility: Visibility::Public,
+        deal_constraints: None,
+        time_control: None,
+        allow_kibitzing: false,
+        broadcast_delay_secs: None,
+        seed: None,
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    #[serde(rename = "type")]
+    msg_type: String,
+    data: Value,
+}
+
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    /// Received messages not asked for yet
+    pending: Vec<Envelope>,
+}
+
+impl Client {
+    fn connect(port: u16) -> Self {
+        let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+        Self {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            writer: stream,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn send<M: MessageTrait + Serialize>(&mut self, message: &M) {
+        let envelope = Envelope {
+            msg_type: M::MSG_TYPE.into(),
+            data: serde_json::to_value(message).unwrap(),
+        };
+        writeln!(self.writer, "{}", serde_json::to_string(&envelope).unwrap()).unwrap();
+    }
+
+    /// Returns the oldest message of type `M`, waiting for it if none was received yet.
+    /// Messages of other types are kept for later calls.
+    pub fn recv<M: MessageTrait + DeserializeOwned>(&mut self) -> M {
+        if let Some(index) = self
+            .pending
+            .iter()
+            .position(|envelope| envelope.msg_type == M::MSG_TYPE)
+        {
+            let envelope = self.pending.remove(index);
+            return serde_json::from_value(envelope.data).unwrap();
+        }
+        loop {
+            let mut line = String::new();
+            let read = self
+                .reader
+                .read_line(&mut line)
+                .unwrap_or_else(|err| panic!("no {} received: {}", M::MSG_TYPE, err));
+            assert!(read > 0, "connection closed before {}", M::MSG_TYPE);
+            let envelope: Envelope = serde_json::from_str(&line).unwrap();
+            if envelope.msg_type == M::MSG_TYPE {
+                return serde_json::from_value(envelope.data).unwrap();
+            }
+            self.pending.push(envelope);
+        }
+    }
+
+    /// Whether the server closed the connection after the messages received so far
+    pub fn is_closed(&mut self) -> bool {
+        let mut line = String::new();
+        loop {
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return true,
+                Ok(_) => line.clear(),
+                Err(_) => return false,
+            }
+        }
+    }
+
+    /// Whether a message of type `M` was received and not asked for yet
+    pub fn has_received<M: MessageTrait>(&self) -> bool {
+        self.pending
+            .iter()
+            .any(|envelope| envelope.msg_type == M::MSG_TYPE)
+    }
+
+    pub fn request<M>(&mut self, message: &M) -> M::Response
+    where
+        M: RequestTrait + Serialize,
+        M::Response: DeserializeOwned,
+    {
+        self.send(message);
+        self.recv::<M::Response>()
+    }
+
+    fn login(&mut self, username: &str) {
+        let response = self.request(&LoginMessage {
+            user: User::new(username),
+        });
+        assert!(matches!(response, LoginResponse::Ok), "{:?}", response);
+    }
+
+    pub fn join(&mut self, room: &str) {
+        let response = self.request(&JoinRoomMessage {
+            room_id: RoomId::new(room.into()),
+        });
+        assert!(matches!(response, JoinRoomResponse::Ok), "{:?}", response);
+    }
+}
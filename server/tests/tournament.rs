@@ -0,0 +1,95 @@
+mod support;
+
+use common::{
+    message::{
+        client_message::{
+            JoinTournamentMessage, ListPlacesMessage, RegisterRoomMessage,
+            RegisterTournamentMessage, SelectPlaceMessage, StartRoundMessage,
+        },
+        server_notification::TournamentRoundNotification,
+        server_response::{
+            JoinTournamentResponse, ListPlacesResponse, RegisterRoomResponse,
+            RegisterTournamentResponse, SelectPlaceResponse, StartRoundResponse,
+        },
+    },
+    tournament::Movement,
+    Player,
+};
+use support::{room_info, Client, TestServer};
+
+fn register_tournament(director: &mut Client, tournament_id: &str) {
+    let response = director.request(&RegisterTournamentMessage {
+        tournament_id: tournament_id.into(),
+        movement: Movement::Mitchell,
+        pairs: vec![
+            ["north".into(), "south".into()],
+            ["east".into(), "west".into()],
+        ],
+        boards_per_round: 1,
+    });
+    assert_eq!(response, RegisterTournamentResponse::Ok);
+}
+
+#[test]
+fn tournament_seats_are_enforced() {
+    let server = TestServer::start();
+    let mut director = server.login("director");
+    register_tournament(&mut director, "seats");
+    let response = director.request(&StartRoundMessage {
+        tournament_id: "seats".into(),
+    });
+    assert_eq!(response, StartRoundResponse::Ok);
+
+    let room = "seats round 1 table 1";
+    let mut north = server.login("north");
+    let mut east = server.login("east");
+    north.join(room);
+    east.join(room);
+
+    for (player, position) in [(&mut north, Player::East), (&mut east, Player::South)] {
+        let response = player.request(&SelectPlaceMessage {
+            position: Some(position),
+        });
+        assert_eq!(response, SelectPlaceResponse::NotYourSeat);
+    }
+    for (player, position) in [(&mut north, Player::North), (&mut east, Player::West)] {
+        let response = player.request(&SelectPlaceMessage {
+            position: Some(position),
+        });
+        assert_eq!(response, SelectPlaceResponse::Ok);
+    }
+}
+
+#[test]
+fn room_named_like_a_tournament_group_is_separate() {
+    let server = TestServer::start();
+    let mut director = server.login("director");
+    register_tournament(&mut director, "groups");
+    let response = director.request(&JoinTournamentMessage {
+        tournament_id: "groups".into(),
+    });
+    assert_eq!(response, JoinTournamentResponse::Ok);
+
+    // The room has the name the server uses internally for followers of the tournament
+    let mut outsider = server.login("outsider");
+    let response = outsider.request(&RegisterRoomMessage {
+        room_info: room_info("tournament:groups"),
+    });
+    assert_eq!(response, RegisterRoomResponse::Ok);
+    outsider.join("tournament:groups");
+
+    let response = director.request(&StartRoundMessage {
+        tournament_id: "groups".into(),
+    });
+    assert_eq!(response, StartRoundResponse::Ok);
+    director.recv::<TournamentRoundNotification>();
+
+    // Messages to the outsider keep their order, so the notification would come before this response
+    let response = outsider.request(&ListPlacesMessage {});
+    assert!(
+        matches!(response, ListPlacesResponse::Ok(_)),
+        "{:?}",
+        response
+    );
+    assert!(!outsider.has_received::<TournamentRoundNotification>());
+}
@@ -0,0 +1,86 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use client_core::{Client, ClientState};
+use common::{Bid, Player};
+
+/// Number of log lines kept for the message pane
+const LOG_CAPACITY: usize = 200;
+
+#[derive(Clone, Eq, PartialEq)]
+pub enum LogLevel {
+    Info,
+    Error,
+}
+
+/// Messages from the server shown below the table, newest last
+#[derive(Clone, Default)]
+pub struct Log {
+    lines: Arc<Mutex<VecDeque<(LogLevel, String)>>>,
+}
+
+impl Log {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create_info(&self, msg: String) {
+        self.push(LogLevel::Info, msg);
+    }
+
+    pub fn create_error(&self, msg: String) {
+        self.push(LogLevel::Error, msg);
+    }
+
+    fn push(&self, level: LogLevel, msg: String) {
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == LOG_CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back((level, msg));
+    }
+
+    /// Returns up to `count` newest lines
+    pub fn last(&self, count: usize) -> Vec<(LogLevel, String)> {
+        let lines = self.lines.lock().unwrap();
+        lines
+            .iter()
+            .skip(lines.len().saturating_sub(count))
+            .cloned()
+            .collect()
+    }
+}
+
+/// State of the terminal client: the shared client state and what's typed on the keyboard
+pub struct App {
+    pub client: Client,
+    /// Calls of the current auction in order
+    pub auction: Vec<(Player, Bid)>,
+    /// Index of the highlighted room in the lobby
+    pub selected_room: usize,
+    /// Text typed into the current prompt, or the first key of a bid or card shortcut
+    pub input: String,
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            auction: Vec::new(),
+            selected_room: 0,
+            input: String::new(),
+        }
+    }
+
+    /// Forgets everything about the table after the game is over
+    pub fn clear_table(&mut self) {
+        let name = std::mem::take(&mut self.client.name);
+        let rooms = std::mem::take(&mut self.client.rooms);
+        self.client = Client::new();
+        self.client.name = name;
+        self.client.rooms = rooms;
+        self.client.state = ClientState::InLobby;
+        self.auction = Vec::new();
+        self.input = String::new();
+    }
+}
@@ -0,0 +1,399 @@
+use std::sync::Arc;
+
+use client_core::{add_handler, handler::emit, utils::update_user_seat, ClientState};
+use common::{
+    hand::{high_card_points, shape_str},
+    message::{
+        client_message::{
+            GetCardsMessage, JoinRoomMessage, LeaveRoomMessage, ListPlacesMessage, ListRoomsMessage,
+        },
+        server_notification::{
+            AskBidNotification, AskTrickNotification, AuctionFinishedNotification,
+            DealFinishedNotification, DummyCardsNotification, GameFinishedNotification,
+            GameStartedNotification, JoinRoomNotification, LeaveRoomNotification,
+            MakeBidNotification, MakeTrickNotification, SelectPlaceNotification,
+            ServerMessageNotification, TrickFinishedNotification,
+        },
+        server_response::{
+            GetCardsResponse, JoinRoomResponse, LeaveRoomResponse, ListPlacesResponse,
+            ListRoomsResponse, LoginResponse, MakeBidResponse, MakeTrickResponse,
+            RegisterRoomResponse, SelectPlaceResponse,
+        },
+        GetErrorMessage,
+    },
+    room::RoomId,
+    Card, Player,
+};
+use rust_socketio::asynchronous::ClientBuilder;
+use tokio::sync::Mutex;
+
+use crate::app::{App, Log};
+
+/// Registers handlers of all server messages the terminal client reacts to
+pub fn add_handlers(mut builder: ClientBuilder, app: &Arc<Mutex<App>>, log: &Log) -> ClientBuilder {
+    add_handler!(builder, LoginResponse, app, log, |app, log, msg, s| {
+        match msg {
+            LoginResponse::Ok => {
+                app.lock().await.client.state = ClientState::InLobby;
+                emit(&s, &ListRoomsMessage {}).await;
+            }
+            err => log.create_error(err.err_msg()),
+        }
+    });
+
+    add_handler!(
+        builder,
+        ListRoomsResponse,
+        app,
+        log,
+        |app, _log, msg, _s| {
+            let mut app_lock = app.lock().await;
+            app_lock.client.rooms = msg
+                .rooms
+                .iter()
+                .map(|room| room.as_str().to_string())
+                .collect();
+            app_lock.selected_room = app_lock
+                .selected_room
+                .min(app_lock.client.rooms.len().saturating_sub(1));
+        }
+    );
+
+    add_handler!(
+        builder,
+        RegisterRoomResponse,
+        app,
+        log,
+        |app, log, msg, s| {
+            match msg {
+                RegisterRoomResponse::Ok => {
+                    let room_id =
+                        RoomId::new(app.lock().await.client.selected_room_name.clone().into());
+                    emit(&s, &JoinRoomMessage { room_id }).await;
+                }
+                err => log.create_error(format!("Creating the room failed: {:?}", err)),
+            }
+        }
+    );
+
+    add_handler!(builder, JoinRoomResponse, app, log, |app, log, msg, s| {
+        match msg {
+            JoinRoomResponse::Ok => {
+                app.lock().await.client.state = ClientState::InRoom;
+                emit(&s, &ListPlacesMessage {}).await;
+            }
+            err => log.create_error(err.err_msg()),
+        }
+    });
+
+    add_handler!(
+        builder,
+        ListPlacesResponse,
+        app,
+        log,
+        |app, log, msg, _s| {
+            match msg {
+                ListPlacesResponse::Ok(seats) => app.lock().await.client.seats = seats,
+                err => log.create_error(err.err_msg()),
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        SelectPlaceResponse,
+        app,
+        log,
+        |_app, log, msg, s| {
+            match msg {
+                SelectPlaceResponse::Ok => emit(&s, &ListPlacesMessage {}).await,
+                err => log.create_error(err.err_msg()),
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        SelectPlaceNotification,
+        app,
+        log,
+        |app, _log, msg, _s| {
+            update_user_seat(&mut app.lock().await.client.seats, msg.user, msg.position);
+        }
+    );
+
+    add_handler!(
+        builder,
+        JoinRoomNotification,
+        app,
+        log,
+        |_app, log, msg, _s| {
+            log.create_info(format!(
+                "Player {} joined the room.",
+                msg.user.get_username()
+            ));
+        }
+    );
+
+    add_handler!(
+        builder,
+        LeaveRoomResponse,
+        app,
+        log,
+        |app, _log, _msg, s| {
+            app.lock().await.clear_table();
+            emit(&s, &ListRoomsMessage {}).await;
+        }
+    );
+
+    add_handler!(
+        builder,
+        LeaveRoomNotification,
+        app,
+        log,
+        |app, log, msg, _s| {
+            update_user_seat(&mut app.lock().await.client.seats, msg.user.clone(), None);
+            log.create_info(format!("Player {} left the room.", msg.user.get_username()));
+        }
+    );
+
+    add_handler!(
+        builder,
+        GameStartedNotification,
+        app,
+        log,
+        |app, _log, _msg, s| {
+            app.lock().await.client.state = ClientState::Playing;
+            emit(&s, &GetCardsMessage {}).await;
+        }
+    );
+
+    add_handler!(builder, GetCardsResponse, app, log, |app, log, msg, _s| {
+        match msg {
+            GetCardsResponse::Ok { cards, position } => {
+                let mut app_lock = app.lock().await;
+                app_lock.client.card_list = Some(cards);
+                app_lock.client.selected_seat = Some(position);
+            }
+            err => log.create_error(err.err_msg()),
+        }
+    });
+
+    add_handler!(
+        builder,
+        AskBidNotification,
+        app,
+        log,
+        |app, log, msg, _s| {
+            let mut app_lock = app.lock().await;
+            let is_my_turn = app_lock.client.selected_seat == Some(msg.player);
+            if let (true, Some(time_left)) = (is_my_turn, msg.time_left) {
+                log.create_info(format!(
+                    "Your turn: {}s + {}s time bank",
+                    time_left.move_ms / 1000,
+                    time_left.bank_ms / 1000
+                ));
+            }
+            app_lock.client.game_current_player = Some(msg.player);
+            app_lock.client.legal_bids = if is_my_turn {
+                msg.legal_bids
+            } else {
+                Vec::new()
+            };
+        }
+    );
+
+    add_handler!(builder, MakeBidResponse, app, log, |_app, log, msg, _s| {
+        match msg {
+            MakeBidResponse::Ok => {}
+            err => log.create_error(err.err_msg()),
+        }
+    });
+
+    add_handler!(
+        builder,
+        MakeBidNotification,
+        app,
+        log,
+        |app, log, msg, _s| {
+            if let Some(annotation) = msg
+                .annotation
+                .as_ref()
+                .filter(|annotation| annotation.alert)
+            {
+                log.create_info(format!(
+                    "Alert! {} {}: {}",
+                    msg.player, msg.bid, annotation.meaning
+                ));
+            }
+            if let Some(explanation) = &msg.explanation {
+                log.create_info(format!(
+                    "{} explains {}: {}",
+                    msg.player, msg.bid, explanation
+                ));
+            }
+            let mut app_lock = app.lock().await;
+            app_lock.client.player_bids[msg.player.to_usize()] = Some(msg.bid);
+            app_lock.auction.push((msg.player, msg.bid));
+        }
+    );
+
+    add_handler!(
+        builder,
+        AuctionFinishedNotification,
+        app,
+        log,
+        |app, log, msg, _s| {
+            let mut app_lock = app.lock().await;
+            app_lock.client.legal_bids = Vec::new();
+            match msg {
+                AuctionFinishedNotification::Winner(msg) => {
+                    log.create_info(format!("Contract: {} by {}", msg.max_bid, msg.winner));
+                    app_lock.client.game_max_bid = Some(msg.max_bid);
+                    app_lock.client.game_max_bidder = Some(msg.winner);
+                    app_lock.client.game_current_player = Some(msg.winner);
+                }
+                AuctionFinishedNotification::NoWinner => {
+                    log.create_info(String::from("Passed out, the deal is redealt"));
+                    app_lock.auction = Vec::new();
+                }
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        DummyCardsNotification,
+        app,
+        log,
+        |app, _log, msg, _s| {
+            let mut app_lock = app.lock().await;
+            app_lock.client.dummy_cards = Some(msg.cards);
+            app_lock.client.dummy_player = Some(msg.dummy);
+        }
+    );
+
+    add_handler!(
+        builder,
+        AskTrickNotification,
+        app,
+        log,
+        |app, _log, msg, _s| {
+            let mut app_lock = app.lock().await;
+            app_lock.client.game_current_player = Some(msg.player);
+            app_lock.client.legal_cards = msg.legal_cards;
+        }
+    );
+
+    add_handler!(
+        builder,
+        MakeTrickResponse,
+        app,
+        log,
+        |_app, log, msg, _s| {
+            match msg {
+                MakeTrickResponse::Ok => {}
+                err => log.create_error(err.err_msg()),
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        MakeTrickNotification,
+        app,
+        log,
+        |app, _log, msg, _s| {
+            let client = &mut app.lock().await.client;
+            client.current_placed_cards[msg.player.to_usize()] = Some(msg.card);
+            for cards in [client.card_list.as_mut(), client.dummy_cards.as_mut()]
+                .into_iter()
+                .flatten()
+            {
+                cards.retain(|card| *card != msg.card);
+            }
+        }
+    );
+
+    add_handler!(
+        builder,
+        TrickFinishedNotification,
+        app,
+        log,
+        |app, log, msg, _s| {
+            app.lock().await.client.current_placed_cards = [None, None, None, None];
+            log.create_info(format!(
+                "Trick {} taken by {}",
+                msg.cards
+                    .iter()
+                    .map(Card::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                msg.taker
+            ));
+        }
+    );
+
+    add_handler!(
+        builder,
+        DealFinishedNotification,
+        app,
+        log,
+        |app, log, msg, s| {
+            let result = if msg.contract_succeeded {
+                "won"
+            } else {
+                "lost"
+            };
+            log.create_info(format!("Contract {} by {}", result, msg.bidder));
+            if let Some(par) = msg.par {
+                log.create_info(format!("Par: {}", par));
+            }
+            for (index, hand) in msg.record.deal.hands.iter().enumerate() {
+                log.create_info(format!(
+                    "{}: {} HCP, {}",
+                    Player::from_usize(index).unwrap(),
+                    high_card_points(hand),
+                    shape_str(hand)
+                ));
+            }
+            {
+                let mut app_lock = app.lock().await;
+                app_lock.client.points = msg.points;
+                app_lock.client.legal_cards = Vec::new();
+                app_lock.client.game_max_bid = None;
+                app_lock.client.game_max_bidder = None;
+                app_lock.client.dummy_cards = None;
+                app_lock.client.dummy_player = None;
+                app_lock.client.player_bids = [None, None, None, None];
+                app_lock.auction = Vec::new();
+            }
+            emit(&s, &GetCardsMessage {}).await;
+        }
+    );
+
+    add_handler!(
+        builder,
+        GameFinishedNotification,
+        app,
+        log,
+        |app, log, _msg, s| {
+            log.create_info(String::from("Game finished!"));
+            app.lock().await.clear_table();
+            emit(&s, &LeaveRoomMessage {}).await;
+            emit(&s, &ListRoomsMessage {}).await;
+        }
+    );
+
+    add_handler!(
+        builder,
+        ServerMessageNotification,
+        app,
+        log,
+        |_app, log, msg, _s| {
+            log.create_info(format!("Message from the server: {}", msg.message));
+        }
+    );
+
+    builder
+}
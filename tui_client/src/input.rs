@@ -0,0 +1,213 @@
+use client_core::ClientState;
+use common::{
+    message::{
+        client_message::{
+            JoinRoomMessage, LeaveRoomMessage, ListRoomsMessage, LoginMessage, MakeBidMessage,
+            MakeTrickMessage, RegisterRoomMessage, SelectPlaceMessage,
+        },
+        MessageTrait,
+    },
+    room::{RoomId, RoomInfo, Visibility},
+    user::User,
+    Bid, BidType, Card, Player, Rank, Suit,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Serialize;
+use serde_json::to_string;
+
+use crate::app::App;
+
+/// What the event loop should do after a key press
+pub enum Action {
+    /// Emit message with given type and payload
+    Send(&'static str, String),
+    Quit,
+}
+
+fn send<M>(message: &M) -> Option<Action>
+where
+    M: MessageTrait + Serialize,
+{
+    Some(Action::Send(M::MSG_TYPE, to_string(message).unwrap()))
+}
+
+fn suit_from_key(key: char) -> Option<Suit> {
+    match key {
+        'c' => Some(Suit::Clubs),
+        'd' => Some(Suit::Diamonds),
+        'h' => Some(Suit::Hearts),
+        's' => Some(Suit::Spades),
+        _ => None,
+    }
+}
+
+fn rank_from_key(key: char) -> Option<Rank> {
+    match key {
+        't' => Some(Rank::Ten),
+        'j' => Some(Rank::Jack),
+        'q' => Some(Rank::Queen),
+        'k' => Some(Rank::King),
+        'a' => Some(Rank::Ace),
+        '2'..='9' => key.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Edits the prompt text, returns whether the key was consumed
+fn edit_input(input: &mut String, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char(c) => input.push(c),
+        KeyCode::Backspace => {
+            input.pop();
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Reacts to a key press, returning a message for the server if the key triggers one
+pub fn handle_key(app: &mut App, key: KeyEvent) -> Option<Action> {
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+        return Some(Action::Quit);
+    }
+
+    match app.client.state {
+        ClientState::Logging => logging_key(app, key.code),
+        ClientState::InLobby => lobby_key(app, key.code),
+        ClientState::CreatingRoom => creating_room_key(app, key.code),
+        ClientState::InRoom => room_key(app, key.code),
+        ClientState::Playing => playing_key(app, key.code),
+        ClientState::Replaying => None,
+    }
+}
+
+fn logging_key(app: &mut App, key: KeyCode) -> Option<Action> {
+    if key != KeyCode::Enter {
+        edit_input(&mut app.input, key);
+        return None;
+    }
+    app.client.name = app.input.trim().to_string();
+    send(&LoginMessage {
+        user: User::new(&app.client.name),
+    })
+}
+
+fn lobby_key(app: &mut App, key: KeyCode) -> Option<Action> {
+    match key {
+        KeyCode::Up => app.selected_room = app.selected_room.saturating_sub(1),
+        KeyCode::Down if app.selected_room + 1 < app.client.rooms.len() => app.selected_room += 1,
+        KeyCode::Enter => {
+            let room = app.client.rooms.get(app.selected_room)?.clone();
+            app.client.selected_room_name = room.clone();
+            return send(&JoinRoomMessage {
+                room_id: RoomId::new(room.into()),
+            });
+        }
+        KeyCode::Char('c') => {
+            app.input = String::new();
+            app.client.state = ClientState::CreatingRoom;
+        }
+        KeyCode::Char('r') => return send(&ListRoomsMessage {}),
+        KeyCode::Char('q') => return Some(Action::Quit),
+        _ => {}
+    }
+    None
+}
+
+fn creating_room_key(app: &mut App, key: KeyCode) -> Option<Action> {
+    match key {
+        KeyCode::Esc => {
+            app.input = String::new();
+            app.client.state = ClientState::InLobby;
+            None
+        }
+        KeyCode::Enter if !app.input.trim().is_empty() => {
+            let name = std::mem::take(&mut app.input).trim().to_string();
+            app.client.selected_room_name = name.clone();
+            app.client.state = ClientState::InLobby;
+            send(&RegisterRoomMessage {
+                room_info: RoomInfo {
+                    id: RoomId::new(name.into()),
+                    visibility: Visibility::Public,
+                    deal_constraints: None,
+                    time_control: None,
+                    allow_kibitzing: false,
+                    broadcast_delay_secs: None,
+                    seed: None,
+                },
+            })
+        }
+        key => {
+            edit_input(&mut app.input, key);
+            None
+        }
+    }
+}
+
+fn room_key(app: &mut App, key: KeyCode) -> Option<Action> {
+    let position = match key {
+        KeyCode::Char('n') => Player::North,
+        KeyCode::Char('e') => Player::East,
+        KeyCode::Char('s') => Player::South,
+        KeyCode::Char('w') => Player::West,
+        KeyCode::Char('l') => return send(&LeaveRoomMessage {}),
+        KeyCode::Char('q') => return Some(Action::Quit),
+        _ => return None,
+    };
+    app.client.selected_seat = Some(position);
+    send(&SelectPlaceMessage {
+        position: Some(position),
+    })
+}
+
+/// During the auction a bid is a level followed by a strain, e.g. `1n` or `4s`, or one of `p`, `x` and `r`.
+/// During the play a card is a rank followed by a suit, e.g. `ts` for the ten of spades.
+fn playing_key(app: &mut App, key: KeyCode) -> Option<Action> {
+    let key = match key {
+        KeyCode::Char(key) => key.to_ascii_lowercase(),
+        KeyCode::Esc | KeyCode::Backspace => {
+            app.input = String::new();
+            return None;
+        }
+        _ => return None,
+    };
+    let pending = app.input.chars().next();
+
+    if app.client.game_max_bid.is_none() {
+        let bid = match (pending, key) {
+            (_, 'p') => Some(Bid::Pass),
+            (_, 'x') => Some(Bid::Double),
+            (_, 'r') => Some(Bid::Redouble),
+            (Some(level @ '1'..='7'), 'n') => Bid::new(level as u8 - b'0', BidType::NoTrump),
+            (Some(level @ '1'..='7'), key) => suit_from_key(key)
+                .and_then(|suit| Bid::new(level as u8 - b'0', BidType::Trump(suit))),
+            (_, '1'..='7') => {
+                app.input = key.to_string();
+                return None;
+            }
+            _ => None,
+        };
+        app.input = String::new();
+        let bid = bid?;
+        app.client.placed_bid = Some(bid);
+        return send(&MakeBidMessage {
+            bid,
+            explanation: None,
+        });
+    }
+
+    let card = match pending.and_then(rank_from_key) {
+        Some(rank) => suit_from_key(key).map(|suit| Card::new(rank, suit)),
+        None => {
+            if rank_from_key(key).is_some() {
+                app.input = key.to_string();
+                return None;
+            }
+            None
+        }
+    };
+    app.input = String::new();
+    let card = card?;
+    app.client.placed_trick = Some(card);
+    send(&MakeTrickMessage { card })
+}
@@ -0,0 +1,118 @@
+mod app;
+mod handlers;
+mod input;
+mod ui;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use client_core::handler::{connect, handshake};
+use common::protocol::Feature;
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures_util::StreamExt;
+use ratatui::DefaultTerminal;
+use rust_socketio::asynchronous::{Client as Socket, ClientBuilder};
+use tokio::{sync::Mutex, time::interval};
+
+use app::{App, Log};
+use handlers::add_handlers;
+use input::{handle_key, Action};
+
+/// Redraws the screen on server events and key presses until the user quits
+async fn run(
+    terminal: &mut DefaultTerminal,
+    socket: &Socket,
+    app: &Arc<Mutex<App>>,
+    log: &Log,
+) -> std::io::Result<()> {
+    let mut events = EventStream::new();
+    // Server messages change the state in the background, so the screen is redrawn periodically
+    let mut redraw = interval(Duration::from_millis(100));
+
+    loop {
+        {
+            let app_lock = app.lock().await;
+            terminal.draw(|frame| ui::draw(frame, &app_lock, log))?;
+        }
+
+        tokio::select! {
+            _ = redraw.tick() => {}
+            event = events.next() => {
+                let Some(event) = event else {
+                    return Ok(());
+                };
+                let Event::Key(key) = event? else {
+                    continue;
+                };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                let action = handle_key(&mut *app.lock().await, key);
+                match action {
+                    Some(Action::Send(msg_type, payload)) => {
+                        if let Err(err) = socket.emit(msg_type, payload).await {
+                            log.create_error(format!("Sending failed: {}", err));
+                        }
+                    }
+                    Some(Action::Quit) => return Ok(()),
+                    None => {}
+                }
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let args = clap::Command::new("bridge-tui-client")
+        .arg(
+            clap::Arg::new("port")
+                .short('p')
+                .long("port")
+                .value_name("PORT")
+                .help("Port to connect to")
+                .default_value("3000"),
+        )
+        .arg(
+            clap::Arg::new("server_ip")
+                .short('s')
+                .long("server_ip")
+                .value_name("SERVER_IP")
+                .help("Server IP to connect to")
+                .default_value("localhost"),
+        )
+        .arg(
+            clap::Arg::new("protocol")
+                .short('g')
+                .long("protocol")
+                .value_name("PROTOCOL")
+                .help("Protocol to use (http or https)")
+                .default_value("http"),
+        )
+        .get_matches();
+
+    let port = args.get_one::<String>("port").unwrap();
+    let host = args.get_one::<String>("server_ip").unwrap();
+    let protocol = args.get_one::<String>("protocol").unwrap();
+    let server_url = format!("{}://{}:{}/", protocol, host, port);
+
+    println!("Connecting to {}", server_url);
+
+    let app = Arc::new(Mutex::new(App::new()));
+    let log = Log::new();
+
+    let builder = add_handlers(ClientBuilder::new(&server_url).namespace("/"), &app, &log);
+    let socket = connect(builder).await.expect("Connection failed");
+    if let Err(err) = handshake(&socket, vec![Feature::Acknowledgements]).await {
+        eprintln!("{}", err);
+        socket.disconnect().await.ok();
+        return Ok(());
+    }
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &socket, &app, &log).await;
+    ratatui::restore();
+
+    socket.disconnect().await.ok();
+    result
+}
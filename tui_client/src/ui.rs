@@ -0,0 +1,289 @@
+use std::cmp::Reverse;
+
+use client_core::ClientState;
+use common::{Bid, BidType, Card, Player, Suit};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::app::{App, Log, LogLevel};
+
+fn suit_symbol(suit: Suit) -> Span<'static> {
+    let (symbol, color) = match suit {
+        Suit::Clubs => ("♣", Color::White),
+        Suit::Diamonds => ("♦", Color::LightRed),
+        Suit::Hearts => ("♥", Color::LightRed),
+        Suit::Spades => ("♠", Color::White),
+    };
+    Span::styled(symbol, Style::new().fg(color))
+}
+
+fn card_spans(card: Card) -> Vec<Span<'static>> {
+    vec![
+        Span::raw(card.rank.to_str().to_string()),
+        suit_symbol(card.suit),
+    ]
+}
+
+fn bid_spans(bid: Bid) -> Vec<Span<'static>> {
+    match bid {
+        Bid::Pass => vec![Span::raw("Pass")],
+        Bid::Double => vec![Span::raw("X").red()],
+        Bid::Redouble => vec![Span::raw("XX").blue()],
+        Bid::Play(level, BidType::NoTrump) => vec![Span::raw(format!("{}NT", level))],
+        Bid::Play(level, BidType::Trump(suit)) => {
+            vec![Span::raw(level.to_string()), suit_symbol(suit)]
+        }
+    }
+}
+
+/// One line per suit, spades first, cards from the highest
+fn hand_lines(cards: &[Card]) -> Vec<Line<'static>> {
+    [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs]
+        .into_iter()
+        .map(|suit| {
+            let mut ranks: Vec<_> = cards.iter().filter(|card| card.suit == suit).collect();
+            ranks.sort_by_key(|card| Reverse(card.rank));
+            let mut spans = vec![suit_symbol(suit), Span::raw(" ")];
+            spans.extend(
+                ranks
+                    .iter()
+                    .map(|card| Span::raw(format!("{} ", card.rank.to_str()))),
+            );
+            if ranks.is_empty() {
+                spans.push(Span::raw("-"));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+pub fn draw(frame: &mut Frame, app: &App, log: &Log) {
+    let [title_area, main_area, log_area, help_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(10),
+        Constraint::Length(8),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let name = if app.client.name.is_empty() {
+        String::from("not logged in")
+    } else {
+        app.client.name.clone()
+    };
+    frame.render_widget(
+        Line::from(format!(" Bridge | {}", name)).bold().reversed(),
+        title_area,
+    );
+
+    let help = match app.client.state {
+        ClientState::Logging => {
+            draw_prompt(frame, main_area, "Nickname", &app.input);
+            "Enter log in  Ctrl+C quit"
+        }
+        ClientState::InLobby => {
+            draw_lobby(frame, main_area, app);
+            "↑/↓ select  Enter join  c create room  r refresh  q quit"
+        }
+        ClientState::CreatingRoom => {
+            draw_prompt(frame, main_area, "Room name", &app.input);
+            "Enter create  Esc back"
+        }
+        ClientState::InRoom => {
+            draw_seats(frame, main_area, app);
+            "n/e/s/w take seat  l leave room  q quit"
+        }
+        ClientState::Playing => {
+            draw_table(frame, main_area, app);
+            if app.client.game_max_bid.is_none() {
+                "bid: level 1-7 + c/d/h/s/n, p pass, x double, r redouble  Esc clear  Ctrl+C quit"
+            } else {
+                "card: rank 2-9/t/j/q/k/a + suit c/d/h/s  Esc clear  Ctrl+C quit"
+            }
+        }
+        ClientState::Replaying => "Ctrl+C quit",
+    };
+    frame.render_widget(Line::from(help).dark_gray(), help_area);
+
+    let lines: Vec<Line> = log
+        .last(log_area.height.saturating_sub(2) as usize)
+        .into_iter()
+        .map(|(level, msg)| match level {
+            LogLevel::Info => Line::from(msg),
+            LogLevel::Error => Line::from(msg).red(),
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title("Messages")),
+        log_area,
+    );
+}
+
+fn draw_prompt(frame: &mut Frame, area: Rect, label: &str, input: &str) {
+    let [area] = Layout::vertical([Constraint::Length(3)]).areas(area);
+    frame.render_widget(
+        Paragraph::new(format!("{}_", input)).block(Block::bordered().title(label.to_string())),
+        area,
+    );
+}
+
+fn draw_lobby(frame: &mut Frame, area: Rect, app: &App) {
+    let items: Vec<ListItem> = app
+        .client
+        .rooms
+        .iter()
+        .map(|room| ListItem::new(room.as_str()))
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.selected_room));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::bordered().title("Rooms"))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> "),
+        area,
+        &mut state,
+    );
+}
+
+fn draw_seats(frame: &mut Frame, area: Rect, app: &App) {
+    let lines: Vec<Line> = Player::ALL
+        .iter()
+        .map(|player| {
+            let user = app.client.seats[player.to_usize()]
+                .as_ref()
+                .map_or(String::from("(empty)"), |user| {
+                    user.get_username().to_string()
+                });
+            let line = Line::from(format!("{:<6} {}", player.to_str(), user));
+            if app.client.selected_seat == Some(*player) {
+                line.bold()
+            } else {
+                line
+            }
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::bordered().title(format!("Room {}", app.client.selected_room_name))),
+        area,
+    );
+}
+
+/// Cards known to this client for given seat: own hand or the dummy
+fn known_hand(app: &App, player: Player) -> Option<&Vec<Card>> {
+    if app.client.selected_seat == Some(player) {
+        app.client.card_list.as_ref()
+    } else if app.client.dummy_player == Some(player) {
+        app.client.dummy_cards.as_ref()
+    } else {
+        None
+    }
+}
+
+fn draw_table(frame: &mut Frame, area: Rect, app: &App) {
+    let [table_area, side_area] =
+        Layout::horizontal([Constraint::Min(48), Constraint::Length(36)]).areas(area);
+    let [top, middle, bottom] = Layout::vertical([Constraint::Ratio(1, 3); 3]).areas(table_area);
+    let [west, center, east] = Layout::horizontal([Constraint::Ratio(1, 3); 3]).areas(middle);
+    let [_, north, _] = Layout::horizontal([Constraint::Ratio(1, 3); 3]).areas(top);
+    let [_, south, _] = Layout::horizontal([Constraint::Ratio(1, 3); 3]).areas(bottom);
+
+    for (player, seat_area) in [
+        (Player::North, north),
+        (Player::East, east),
+        (Player::South, south),
+        (Player::West, west),
+    ] {
+        let user = app.client.seats[player.to_usize()]
+            .as_ref()
+            .map_or(String::new(), |user| user.get_username().to_string());
+        let mut block = Block::bordered().title(format!("{} {}", player.to_str(), user));
+        if app.client.game_current_player == Some(player) {
+            block = block.border_style(Style::new().yellow());
+        }
+        let lines = known_hand(app, player).map_or(Vec::new(), |cards| hand_lines(cards));
+        frame.render_widget(Paragraph::new(lines).block(block), seat_area);
+    }
+
+    // Current trick, each card on the side of the player who played it
+    let trick_card = |player: Player| {
+        app.client.current_placed_cards[player.to_usize()].map_or(vec![Span::raw("  ")], card_spans)
+    };
+    let mut west_east = vec![Span::raw(" ")];
+    west_east.extend(trick_card(Player::West));
+    west_east.push(Span::raw("      "));
+    west_east.extend(trick_card(Player::East));
+    let trick = vec![
+        Line::from(trick_card(Player::North)).centered(),
+        Line::from(""),
+        Line::from(west_east).centered(),
+        Line::from(""),
+        Line::from(trick_card(Player::South)).centered(),
+    ];
+    frame.render_widget(
+        Paragraph::new(trick).block(Block::new().borders(Borders::ALL).title("Trick")),
+        center,
+    );
+
+    let [contract_area, auction_area] =
+        Layout::vertical([Constraint::Length(4), Constraint::Min(4)]).areas(side_area);
+    let contract = match (app.client.game_max_bid, app.client.game_max_bidder) {
+        (Some(bid), Some(bidder)) => {
+            let mut spans = vec![Span::raw("Contract: ")];
+            spans.extend(bid_spans(bid));
+            spans.push(Span::raw(format!(" by {}", bidder)));
+            Line::from(spans)
+        }
+        _ => Line::from("Auction in progress"),
+    };
+    let points = app.client.points;
+    let pending = if app.input.is_empty() {
+        Line::from("")
+    } else {
+        Line::from(format!("Typed: {}", app.input)).yellow()
+    };
+    frame.render_widget(
+        Paragraph::new(vec![
+            contract,
+            Line::from(format!(
+                "Points NS {} EW {}",
+                points[Player::North.to_usize()] + points[Player::South.to_usize()],
+                points[Player::East.to_usize()] + points[Player::West.to_usize()]
+            )),
+        ])
+        .block(Block::bordered()),
+        contract_area,
+    );
+    draw_auction(frame, auction_area, app, pending);
+}
+
+/// Auction grid with one column per player, the first call in the dealer's column
+fn draw_auction(frame: &mut Frame, area: Rect, app: &App, pending: Line) {
+    let first_column = app
+        .auction
+        .first()
+        .map_or(0, |(player, _)| player.to_usize());
+    let mut cells: Vec<Option<Bid>> = vec![None; first_column];
+    cells.extend(app.auction.iter().map(|(_, bid)| Some(*bid)));
+
+    let rows: Vec<Row> = cells
+        .chunks(4)
+        .map(|row| {
+            Row::new(row.iter().map(|bid| {
+                Cell::from(bid.map_or(Line::from(""), |bid| Line::from(bid_spans(bid))))
+            }))
+        })
+        .collect();
+    let header = Row::new(Player::ALL.map(|player| player.to_str().to_string())).bold();
+    frame.render_widget(
+        Table::new(rows, [Constraint::Length(7); 4])
+            .header(header)
+            .block(Block::bordered().title("Auction").title_bottom(pending)),
+        area,
+    );
+}
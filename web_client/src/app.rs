@@ -0,0 +1,406 @@
+use common::{
+    message::{
+        client_message::{
+            GetCardsMessage, HelloMessage, JoinRoomMessage, LeaveRoomMessage, ListPlacesMessage,
+            ListRoomsMessage, LoginMessage, MakeBidMessage, MakeTrickMessage, RegisterRoomMessage,
+            SelectPlaceMessage,
+        },
+        server_notification::{
+            AskBidNotification, AskTrickNotification, AuctionFinishedNotification,
+            DealFinishedNotification, DummyCardsNotification, GameFinishedNotification,
+            GameStartedNotification, JoinRoomNotification, LeaveRoomNotification,
+            MakeBidNotification, MakeTrickNotification, SelectPlaceNotification,
+            ServerMessageNotification, TrickFinishedNotification,
+        },
+        server_response::{
+            GetCardsResponse, HelloResponse, JoinRoomResponse, LeaveRoomResponse,
+            ListPlacesResponse, ListRoomsResponse, LoginResponse, MakeBidResponse,
+            MakeTrickResponse, RegisterRoomResponse, SelectPlaceResponse,
+        },
+        GetErrorMessage,
+    },
+    room::{RoomId, RoomInfo, Visibility},
+    user::User,
+    Bid, Card, Player,
+};
+
+use crate::socket::Socket;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    Login,
+    Lobby,
+    Room,
+    Playing,
+}
+
+/// State of the browser client, changed by server messages and clicks
+pub struct App {
+    pub socket: Socket,
+    pub screen: Screen,
+    /// Nickname typed on the login screen
+    pub name: String,
+    /// Name of the room typed in the lobby
+    pub new_room: String,
+    pub rooms: Vec<RoomId>,
+    /// Room the client is joining or sitting in
+    pub room: Option<RoomId>,
+    pub seats: [Option<User>; 4],
+    pub seat: Option<Player>,
+    /// Own cards, sorted by suit and rank
+    pub hand: Vec<Card>,
+    pub dummy: Option<(Player, Vec<Card>)>,
+    /// Calls of the current auction in order
+    pub auction: Vec<(Player, Bid)>,
+    pub contract: Option<(Bid, Player)>,
+    pub current_player: Option<Player>,
+    /// Bids that can be placed, non-empty only when it's this client's turn to bid
+    pub legal_bids: Vec<Bid>,
+    /// Cards that can be played, non-empty only when it's this client's turn to play
+    pub legal_cards: Vec<Card>,
+    pub trick: [Option<Card>; 4],
+    /// Messages shown under the table, newest last
+    pub log: Vec<String>,
+}
+
+fn sort_cards(cards: &mut [Card]) {
+    cards.sort_by(|a, b| b.suit.cmp(&a.suit).then(b.rank.cmp(&a.rank)));
+}
+
+impl App {
+    pub fn new(socket: Socket) -> Self {
+        Self {
+            socket,
+            screen: Screen::Login,
+            name: String::new(),
+            new_room: String::new(),
+            rooms: Vec::new(),
+            room: None,
+            seats: [None, None, None, None],
+            seat: None,
+            hand: Vec::new(),
+            dummy: None,
+            auction: Vec::new(),
+            contract: None,
+            current_player: None,
+            legal_bids: Vec::new(),
+            legal_cards: Vec::new(),
+            trick: [None; 4],
+            log: Vec::new(),
+        }
+    }
+
+    fn info(&mut self, msg: String) {
+        self.log.push(msg);
+    }
+
+    fn error(&mut self, msg: String) {
+        self.log.push(format!("Error: {}", msg));
+    }
+
+    /// Forgets the table after leaving the room
+    fn clear_table(&mut self) {
+        self.screen = Screen::Lobby;
+        self.room = None;
+        self.seats = [None, None, None, None];
+        self.seat = None;
+        self.clear_deal();
+    }
+
+    fn vacate_seat(&mut self, user: &User) {
+        for seat in self.seats.iter_mut() {
+            if seat.as_ref() == Some(user) {
+                *seat = None;
+            }
+        }
+    }
+
+    fn clear_deal(&mut self) {
+        self.hand = Vec::new();
+        self.dummy = None;
+        self.auction = Vec::new();
+        self.contract = None;
+        self.current_player = None;
+        self.legal_bids = Vec::new();
+        self.legal_cards = Vec::new();
+        self.trick = [None; 4];
+    }
+
+    // ACTIONS -----------------------------------------------------------------------------------
+
+    /// Tells the server which protocol the client talks, the answer comes before any other
+    pub fn hello(&mut self) {
+        self.socket.emit(&HelloMessage::new(Vec::new()));
+    }
+
+    pub fn login(&mut self) {
+        self.socket.emit(&LoginMessage {
+            user: User::new(self.name.trim()),
+        });
+    }
+
+    pub fn refresh_rooms(&mut self) {
+        self.socket.emit(&ListRoomsMessage {});
+    }
+
+    pub fn create_room(&mut self) {
+        let name = self.new_room.trim();
+        if name.is_empty() {
+            return;
+        }
+        let id = RoomId::new(name.to_string().into());
+        self.room = Some(id.clone());
+        self.socket.emit(&RegisterRoomMessage {
+            room_info: RoomInfo {
+                id,
+                visibility: Visibility::Public,
+                deal_constraints: None,
+                time_control: None,
+                allow_kibitzing: false,
+                broadcast_delay_secs: None,
+                seed: None,
+            },
+        });
+    }
+
+    pub fn join_room(&mut self, index: usize) {
+        let Some(room_id) = self.rooms.get(index).cloned() else {
+            return;
+        };
+        self.room = Some(room_id.clone());
+        self.socket.emit(&JoinRoomMessage { room_id });
+    }
+
+    pub fn sit(&mut self, position: Player) {
+        self.socket.emit(&SelectPlaceMessage {
+            position: Some(position),
+        });
+    }
+
+    pub fn leave_room(&mut self) {
+        self.socket.emit(&LeaveRoomMessage {});
+    }
+
+    pub fn bid(&mut self, index: usize) {
+        let Some(bid) = self.legal_bids.get(index).copied() else {
+            return;
+        };
+        self.socket.emit(&MakeBidMessage {
+            bid,
+            explanation: None,
+        });
+    }
+
+    /// Plays card with given index from own hand, or from the dummy if `from_dummy`
+    pub fn play(&mut self, index: usize, from_dummy: bool) {
+        let cards = if from_dummy {
+            self.dummy.as_ref().map(|(_, cards)| cards)
+        } else {
+            Some(&self.hand)
+        };
+        let Some(card) = cards.and_then(|cards| cards.get(index)).copied() else {
+            return;
+        };
+        self.socket.emit(&MakeTrickMessage { card });
+    }
+
+    // SERVER MESSAGES ---------------------------------------------------------------------------
+
+    pub fn on_hello(&mut self, msg: HelloResponse) {
+        if !matches!(msg, HelloResponse::Ok(_)) {
+            self.error(msg.err_msg());
+        }
+    }
+
+    pub fn on_login(&mut self, msg: LoginResponse) {
+        match msg {
+            LoginResponse::Ok => {
+                self.screen = Screen::Lobby;
+                self.refresh_rooms();
+            }
+            err => self.error(err.err_msg()),
+        }
+    }
+
+    pub fn on_list_rooms(&mut self, msg: ListRoomsResponse) {
+        self.rooms = msg.rooms;
+    }
+
+    pub fn on_register_room(&mut self, msg: RegisterRoomResponse) {
+        match (msg, self.room.clone()) {
+            (RegisterRoomResponse::Ok, Some(room_id)) => {
+                self.socket.emit(&JoinRoomMessage { room_id })
+            }
+            (RegisterRoomResponse::Ok, None) => {}
+            (err, _) => self.error(format!("Creating the room failed: {:?}", err)),
+        }
+    }
+
+    pub fn on_join_room(&mut self, msg: JoinRoomResponse) {
+        match msg {
+            JoinRoomResponse::Ok => {
+                self.screen = Screen::Room;
+                self.socket.emit(&ListPlacesMessage {});
+            }
+            err => self.error(err.err_msg()),
+        }
+    }
+
+    pub fn on_list_places(&mut self, msg: ListPlacesResponse) {
+        match msg {
+            ListPlacesResponse::Ok(seats) => self.seats = seats,
+            err => self.error(err.err_msg()),
+        }
+    }
+
+    pub fn on_select_place(&mut self, msg: SelectPlaceResponse) {
+        match msg {
+            SelectPlaceResponse::Ok => self.socket.emit(&ListPlacesMessage {}),
+            err => self.error(err.err_msg()),
+        }
+    }
+
+    pub fn on_place_selected(&mut self, msg: SelectPlaceNotification) {
+        self.vacate_seat(&msg.user);
+        if let Some(position) = msg.position {
+            self.seats[position.to_usize()] = Some(msg.user);
+        }
+    }
+
+    pub fn on_user_joined(&mut self, msg: JoinRoomNotification) {
+        self.info(format!(
+            "Player {} joined the room.",
+            msg.user.get_username()
+        ));
+    }
+
+    pub fn on_leave_room(&mut self, _msg: LeaveRoomResponse) {
+        self.clear_table();
+        self.refresh_rooms();
+    }
+
+    pub fn on_user_left(&mut self, msg: LeaveRoomNotification) {
+        self.vacate_seat(&msg.user);
+        self.info(format!("Player {} left the room.", msg.user.get_username()));
+    }
+
+    pub fn on_game_started(&mut self, _msg: GameStartedNotification) {
+        self.screen = Screen::Playing;
+        self.socket.emit(&GetCardsMessage {});
+    }
+
+    pub fn on_cards(&mut self, msg: GetCardsResponse) {
+        match msg {
+            GetCardsResponse::Ok {
+                mut cards,
+                position,
+            } => {
+                sort_cards(&mut cards);
+                self.hand = cards;
+                self.seat = Some(position);
+            }
+            err => self.error(err.err_msg()),
+        }
+    }
+
+    pub fn on_ask_bid(&mut self, msg: AskBidNotification) {
+        self.current_player = Some(msg.player);
+        self.legal_bids = if self.seat == Some(msg.player) {
+            msg.legal_bids
+        } else {
+            Vec::new()
+        };
+    }
+
+    pub fn on_make_bid(&mut self, msg: MakeBidResponse) {
+        if !matches!(msg, MakeBidResponse::Ok) {
+            self.error(msg.err_msg());
+        }
+    }
+
+    pub fn on_bid_made(&mut self, msg: MakeBidNotification) {
+        if let Some(annotation) = msg.annotation.filter(|annotation| annotation.alert) {
+            self.info(format!(
+                "Alert! {} {}: {}",
+                msg.player, msg.bid, annotation.meaning
+            ));
+        }
+        self.auction.push((msg.player, msg.bid));
+    }
+
+    pub fn on_auction_finished(&mut self, msg: AuctionFinishedNotification) {
+        self.legal_bids = Vec::new();
+        match msg {
+            AuctionFinishedNotification::Winner(msg) => {
+                self.info(format!("Contract: {} by {}", msg.max_bid, msg.winner));
+                self.contract = Some((msg.max_bid, msg.winner));
+                self.current_player = Some(msg.winner);
+            }
+            AuctionFinishedNotification::NoWinner => {
+                self.info(String::from("Passed out, the deal is redealt"));
+                self.auction = Vec::new();
+            }
+        }
+    }
+
+    pub fn on_dummy_cards(&mut self, mut msg: DummyCardsNotification) {
+        sort_cards(&mut msg.cards);
+        self.dummy = Some((msg.dummy, msg.cards));
+    }
+
+    pub fn on_ask_trick(&mut self, msg: AskTrickNotification) {
+        self.current_player = Some(msg.player);
+        self.legal_cards = msg.legal_cards;
+    }
+
+    pub fn on_make_trick(&mut self, msg: MakeTrickResponse) {
+        if !matches!(msg, MakeTrickResponse::Ok) {
+            self.error(msg.err_msg());
+        }
+    }
+
+    pub fn on_trick_made(&mut self, msg: MakeTrickNotification) {
+        self.trick[msg.player.to_usize()] = Some(msg.card);
+        self.legal_cards = Vec::new();
+        self.hand.retain(|card| *card != msg.card);
+        if let Some((_, cards)) = self.dummy.as_mut() {
+            cards.retain(|card| *card != msg.card);
+        }
+    }
+
+    pub fn on_trick_finished(&mut self, msg: TrickFinishedNotification) {
+        self.trick = [None; 4];
+        self.info(format!(
+            "Trick {} taken by {}",
+            msg.cards
+                .iter()
+                .map(Card::to_string)
+                .collect::<Vec<_>>()
+                .join(" "),
+            msg.taker
+        ));
+    }
+
+    pub fn on_deal_finished(&mut self, msg: DealFinishedNotification) {
+        let result = if msg.contract_succeeded {
+            "won"
+        } else {
+            "lost"
+        };
+        self.info(format!("Contract {} by {}", result, msg.bidder));
+        if let Some(par) = msg.par {
+            self.info(format!("Par: {}", par));
+        }
+        self.clear_deal();
+        self.socket.emit(&GetCardsMessage {});
+    }
+
+    pub fn on_game_finished(&mut self, _msg: GameFinishedNotification) {
+        self.info(String::from("Game finished!"));
+        self.leave_room();
+    }
+
+    pub fn on_server_message(&mut self, msg: ServerMessageNotification) {
+        self.info(format!("Message from the server: {}", msg.message));
+    }
+}
@@ -0,0 +1,162 @@
+//! Browser client compiled to WebAssembly.
+//! It speaks the same socket.io events as the other clients, using the message types from `common`.
+
+mod app;
+mod socket;
+mod view;
+
+use std::cell::RefCell;
+
+use common::{
+    message::{
+        server_notification::{
+            AskBidNotification, AskTrickNotification, AuctionFinishedNotification,
+            DealFinishedNotification, DummyCardsNotification, GameFinishedNotification,
+            GameStartedNotification, JoinRoomNotification, LeaveRoomNotification,
+            MakeBidNotification, MakeTrickNotification, SelectPlaceNotification,
+            ServerMessageNotification, TrickFinishedNotification,
+        },
+        server_response::{
+            GetCardsResponse, HelloResponse, JoinRoomResponse, LeaveRoomResponse,
+            ListPlacesResponse, ListRoomsResponse, LoginResponse, MakeBidResponse,
+            MakeTrickResponse, RegisterRoomResponse, SelectPlaceResponse,
+        },
+        MessageTrait,
+    },
+    Player,
+};
+use serde::de::DeserializeOwned;
+use wasm_bindgen::prelude::*;
+use web_sys::{Element, Event, HtmlInputElement};
+
+use app::App;
+use socket::Socket;
+
+thread_local! {
+    static APP: RefCell<Option<App>> = const { RefCell::new(None) };
+}
+
+fn root() -> Element {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id("app"))
+        .expect("Page has no #app element")
+}
+
+/// Runs `f` on the state and redraws the page
+fn update(f: impl FnOnce(&mut App)) {
+    APP.with(|app| {
+        let mut app = app.borrow_mut();
+        let app = app.as_mut().expect("Client is not started");
+        f(app);
+        root().set_inner_html(&view::render(app));
+    });
+}
+
+fn register<M>(socket: &Socket, handler: fn(&mut App, M))
+where
+    M: MessageTrait + DeserializeOwned + 'static,
+{
+    socket.on(move |msg: M| update(|app| handler(app, msg)));
+}
+
+/// Dispatches clicks on elements with `data-action`
+fn on_click(event: Event) {
+    let Some(target) = event
+        .target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+        .and_then(|target| target.closest("[data-action]").ok().flatten())
+    else {
+        return;
+    };
+    let action = target.get_attribute("data-action").unwrap_or_default();
+    let arg = target
+        .get_attribute("data-arg")
+        .and_then(|arg| arg.parse::<usize>().ok());
+
+    update(|app| match (action.as_str(), arg) {
+        ("login", _) => app.login(),
+        ("refresh", _) => app.refresh_rooms(),
+        ("create", _) => app.create_room(),
+        ("join", Some(index)) => app.join_room(index),
+        ("sit", Some(position)) => {
+            if let Some(position) = Player::from_usize(position) {
+                app.sit(position);
+            }
+        }
+        ("leave", _) => app.leave_room(),
+        ("bid", Some(index)) => app.bid(index),
+        ("card", Some(index)) => app.play(index, false),
+        ("dummy-card", Some(index)) => app.play(index, true),
+        _ => {}
+    });
+}
+
+/// Keeps text typed into inputs with `data-field`, without redrawing the page
+fn on_input(event: Event) {
+    let Some(input) = event
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+    else {
+        return;
+    };
+    let value = input.value();
+    APP.with(|app| {
+        let mut app = app.borrow_mut();
+        let Some(app) = app.as_mut() else {
+            return;
+        };
+        match input.get_attribute("data-field").as_deref() {
+            Some("name") => app.name = value,
+            Some("new_room") => app.new_room = value,
+            _ => {}
+        }
+    });
+}
+
+fn listen(element: &Element, event: &str, handler: fn(Event)) {
+    let closure = Closure::<dyn FnMut(Event)>::new(handler);
+    element
+        .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+        .unwrap();
+    closure.forget();
+}
+
+#[wasm_bindgen(start)]
+pub fn start() {
+    let socket = socket::connect();
+
+    register::<HelloResponse>(&socket, App::on_hello);
+    register::<LoginResponse>(&socket, App::on_login);
+    register::<ListRoomsResponse>(&socket, App::on_list_rooms);
+    register::<RegisterRoomResponse>(&socket, App::on_register_room);
+    register::<JoinRoomResponse>(&socket, App::on_join_room);
+    register::<ListPlacesResponse>(&socket, App::on_list_places);
+    register::<SelectPlaceResponse>(&socket, App::on_select_place);
+    register::<SelectPlaceNotification>(&socket, App::on_place_selected);
+    register::<JoinRoomNotification>(&socket, App::on_user_joined);
+    register::<LeaveRoomResponse>(&socket, App::on_leave_room);
+    register::<LeaveRoomNotification>(&socket, App::on_user_left);
+    register::<GameStartedNotification>(&socket, App::on_game_started);
+    register::<GetCardsResponse>(&socket, App::on_cards);
+    register::<AskBidNotification>(&socket, App::on_ask_bid);
+    register::<MakeBidResponse>(&socket, App::on_make_bid);
+    register::<MakeBidNotification>(&socket, App::on_bid_made);
+    register::<AuctionFinishedNotification>(&socket, App::on_auction_finished);
+    register::<DummyCardsNotification>(&socket, App::on_dummy_cards);
+    register::<AskTrickNotification>(&socket, App::on_ask_trick);
+    register::<MakeTrickResponse>(&socket, App::on_make_trick);
+    register::<MakeTrickNotification>(&socket, App::on_trick_made);
+    register::<TrickFinishedNotification>(&socket, App::on_trick_finished);
+    register::<DealFinishedNotification>(&socket, App::on_deal_finished);
+    register::<GameFinishedNotification>(&socket, App::on_game_finished);
+    register::<ServerMessageNotification>(&socket, App::on_server_message);
+
+    APP.with(|app| *app.borrow_mut() = Some(App::new(socket)));
+    update(App::hello);
+
+    let root = root();
+    listen(&root, "click", on_click);
+    listen(&root, "input", on_input);
+    update(|_| {});
+}
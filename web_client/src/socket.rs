@@ -0,0 +1,52 @@
+use common::message::MessageTrait;
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Socket of the socket.io JavaScript client, loaded by `index.html`
+    #[derive(Clone)]
+    pub type Socket;
+
+    /// Connects to the server that served the page
+    #[wasm_bindgen(js_name = io)]
+    pub fn connect() -> Socket;
+
+    #[wasm_bindgen(method, js_name = emit)]
+    fn emit_value(this: &Socket, event: &str, payload: JsValue);
+
+    #[wasm_bindgen(method, js_name = on)]
+    fn on_value(this: &Socket, event: &str, callback: &Closure<dyn FnMut(JsValue)>);
+}
+
+impl Socket {
+    /// Sends given message to server
+    pub fn emit<M>(&self, message: &M)
+    where
+        M: MessageTrait + Serialize,
+    {
+        let json = serde_json::to_string(message).unwrap();
+        // Payload is sent as an object, the same way the Rust clients send it
+        let payload = js_sys::JSON::parse(&json).unwrap();
+        self.emit_value(M::MSG_TYPE, payload);
+    }
+
+    /// Registers handler for server message of type `M` for the whole lifetime of the page
+    pub fn on<M, F>(&self, mut handler: F)
+    where
+        M: MessageTrait + DeserializeOwned,
+        F: FnMut(M) + 'static,
+    {
+        let callback = Closure::<dyn FnMut(JsValue)>::new(move |payload: JsValue| {
+            let json: String = js_sys::JSON::stringify(&payload).unwrap().into();
+            match serde_json::from_str::<M>(&json) {
+                Ok(message) => handler(message),
+                Err(err) => web_sys::console::error_1(
+                    &format!("Invalid {} message: {}", M::MSG_TYPE, err).into(),
+                ),
+            }
+        });
+        self.on_value(M::MSG_TYPE, &callback);
+        callback.forget();
+    }
+}
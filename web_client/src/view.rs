@@ -0,0 +1,219 @@
+use std::fmt::Write;
+
+use common::{Bid, BidType, Card, Player, Suit};
+
+use crate::app::{App, Screen};
+
+/// Escapes text typed by users before it's put into HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn suit_html(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Clubs => r#"<span class="suit black">♣</span>"#,
+        Suit::Diamonds => r#"<span class="suit red">♦</span>"#,
+        Suit::Hearts => r#"<span class="suit red">♥</span>"#,
+        Suit::Spades => r#"<span class="suit black">♠</span>"#,
+    }
+}
+
+fn card_html(card: Card) -> String {
+    format!("{}{}", card.rank.to_str(), suit_html(card.suit))
+}
+
+fn bid_html(bid: Bid) -> String {
+    match bid {
+        Bid::Pass => String::from("Pass"),
+        Bid::Double => String::from(r#"<span class="red">X</span>"#),
+        Bid::Redouble => String::from(r#"<span class="blue">XX</span>"#),
+        Bid::Play(level, BidType::NoTrump) => format!("{}NT", level),
+        Bid::Play(level, BidType::Trump(suit)) => format!("{}{}", level, suit_html(suit)),
+    }
+}
+
+/// Renders the whole page for the current state.
+/// Clickable elements carry `data-action` and `data-arg` attributes handled in `lib.rs`.
+pub fn render(app: &App) -> String {
+    let mut html = match app.screen {
+        Screen::Login => render_login(app),
+        Screen::Lobby => render_lobby(app),
+        Screen::Room => render_room(app),
+        Screen::Playing => render_table(app),
+    };
+
+    html.push_str(r#"<ul class="log">"#);
+    for line in app.log.iter().rev().take(10) {
+        write!(html, "<li>{}</li>", escape(line)).unwrap();
+    }
+    html.push_str("</ul>");
+    html
+}
+
+fn render_login(app: &App) -> String {
+    format!(
+        r#"<div class="panel">
+            <h3>What's your nickname?</h3>
+            <input data-field="name" type="text" maxlength="20" value="{}">
+            <button data-action="login">Log in</button>
+        </div>"#,
+        escape(&app.name)
+    )
+}
+
+fn render_lobby(app: &App) -> String {
+    let mut html = String::from(
+        r#"<div class="panel"><h3>Rooms</h3><button data-action="refresh">Refresh</button><ul>"#,
+    );
+    for (index, room) in app.rooms.iter().enumerate() {
+        write!(
+            html,
+            r#"<li>{} <button data-action="join" data-arg="{}">Join</button></li>"#,
+            escape(room.as_str()),
+            index
+        )
+        .unwrap();
+    }
+    write!(
+        html,
+        r#"</ul>
+        <input data-field="new_room" type="text" placeholder="Room name" value="{}">
+        <button data-action="create">Create a room</button></div>"#,
+        escape(&app.new_room)
+    )
+    .unwrap();
+    html
+}
+
+fn render_room(app: &App) -> String {
+    let room = app.room.as_ref().map_or("", |room| room.as_str());
+    let mut html = format!(r#"<div class="panel"><h3>Room {}</h3><ul>"#, escape(room));
+    for player in Player::ALL {
+        match &app.seats[player.to_usize()] {
+            Some(user) => write!(html, "<li>{}: {}</li>", player, escape(user.get_username())),
+            None => write!(
+                html,
+                r#"<li>{}: <button data-action="sit" data-arg="{}">Sit</button></li>"#,
+                player,
+                player.to_usize()
+            ),
+        }
+        .unwrap();
+    }
+    html.push_str(r#"</ul><button data-action="leave">Leave</button></div>"#);
+    html
+}
+
+/// Cards of a hand in suit order, clickable if they can be played
+fn hand_html(cards: &[Card], legal_cards: &[Card], action: &str) -> String {
+    let mut html = String::from(r#"<div class="hand">"#);
+    for (index, card) in cards.iter().enumerate() {
+        if legal_cards.contains(card) {
+            write!(
+                html,
+                r#"<button class="card" data-action="{}" data-arg="{}">{}</button>"#,
+                action,
+                index,
+                card_html(*card)
+            )
+        } else {
+            write!(html, r#"<span class="card">{}</span>"#, card_html(*card))
+        }
+        .unwrap();
+    }
+    html.push_str("</div>");
+    html
+}
+
+fn render_table(app: &App) -> String {
+    let mut html = String::from(r#"<div class="table">"#);
+
+    for player in Player::ALL {
+        let user = app.seats[player.to_usize()]
+            .as_ref()
+            .map_or(String::new(), |user| escape(user.get_username()));
+        let turn = if app.current_player == Some(player) {
+            " turn"
+        } else {
+            ""
+        };
+        let cards = if app.seat == Some(player) {
+            hand_html(&app.hand, &app.legal_cards, "card")
+        } else {
+            match &app.dummy {
+                Some((dummy, cards)) if *dummy == player => {
+                    hand_html(cards, &app.legal_cards, "dummy-card")
+                }
+                _ => String::new(),
+            }
+        };
+        write!(
+            html,
+            r#"<div class="seat {}{}"><h4>{} {}</h4>{}</div>"#,
+            player.to_str().to_lowercase(),
+            turn,
+            player,
+            user,
+            cards
+        )
+        .unwrap();
+    }
+
+    html.push_str(r#"<div class="trick">"#);
+    for player in Player::ALL {
+        if let Some(card) = app.trick[player.to_usize()] {
+            write!(
+                html,
+                r#"<span class="{}">{}</span>"#,
+                player.to_str().to_lowercase(),
+                card_html(card)
+            )
+            .unwrap();
+        }
+    }
+    html.push_str("</div></div>");
+
+    // Auction with the first call in the dealer's column
+    html.push_str(r#"<div class="panel"><table class="auction"><tr>"#);
+    for player in Player::ALL {
+        write!(html, "<th>{}</th>", player).unwrap();
+    }
+    html.push_str("</tr><tr>");
+    let first_column = app
+        .auction
+        .first()
+        .map_or(0, |(player, _)| player.to_usize());
+    for cell in 0..first_column + app.auction.len() {
+        if cell > 0 && cell.is_multiple_of(4) {
+            html.push_str("</tr><tr>");
+        }
+        let bid = cell
+            .checked_sub(first_column)
+            .map_or(String::new(), |index| bid_html(app.auction[index].1));
+        write!(html, "<td>{}</td>", bid).unwrap();
+    }
+    html.push_str("</tr></table>");
+
+    if let Some((bid, declarer)) = app.contract {
+        write!(html, "<p>Contract: {} by {}</p>", bid_html(bid), declarer).unwrap();
+    }
+
+    if !app.legal_bids.is_empty() {
+        html.push_str(r#"<div class="bids">"#);
+        for (index, bid) in app.legal_bids.iter().enumerate() {
+            write!(
+                html,
+                r#"<button data-action="bid" data-arg="{}">{}</button>"#,
+                index,
+                bid_html(*bid)
+            )
+            .unwrap();
+        }
+        html.push_str("</div>");
+    }
+    html.push_str("</div>");
+    html
+}